@@ -40,6 +40,18 @@ impl Orientation {
 
 // ----------------------------------------------------------------------------
 
+/// Direction of widgets laid out along a line, like meters and tapes.
+#[derive(Clone, Copy, Debug, Display, EnumIter, Eq, PartialEq)]
+pub enum LinearOrientation {
+    #[strum(to_string = "Horizontal")]
+    Horizontal,
+
+    #[strum(to_string = "Vertical")]
+    Vertical,
+}
+
+// ----------------------------------------------------------------------------
+
 #[derive(Clone, Copy, Debug, Display, EnumIter, Eq, PartialEq)]
 pub enum Winding {
     #[strum(to_string = "Clockwise")]
@@ -148,6 +160,16 @@ impl WidgetShape {
         }
     }
 
+    pub(crate) fn outline_points(&self, center: Pos2, radius: f32, rotation: Rot2) -> Vec<Pos2> {
+        (0..Self::RESOLUTION)
+            .map(move |i| {
+                let angle = (i as f32 / Self::RESOLUTION as f32) * TAU;
+                let shape_radius = self.eval(angle - (rotation * Vec2::RIGHT).angle());
+                center + Vec2::angled(angle) * radius * shape_radius
+            })
+            .collect_vec()
+    }
+
     pub(crate) fn paint_shape(
         &self,
         ui: &mut Ui,
@@ -157,13 +179,7 @@ impl WidgetShape {
         stroke: Stroke,
         rotation: Rot2,
     ) {
-        let outline_points = (0..Self::RESOLUTION)
-            .map(move |i| {
-                let angle = (i as f32 / Self::RESOLUTION as f32) * TAU;
-                let shape_radius = self.eval(angle - (rotation * Vec2::RIGHT).angle());
-                center + Vec2::angled(angle) * radius * shape_radius
-            })
-            .collect_vec();
+        let outline_points = self.outline_points(center, radius, rotation);
 
         // https://github.com/emilk/egui/issues/513
         outline_points
@@ -342,6 +358,9 @@ pub(crate) fn normalized_angle_unsigned_incl(angle: f32) -> f32 {
 
 pub(crate) trait SymLog {
     fn symlog(&self, base: Self) -> Self;
+
+    /// Inverse of [`SymLog::symlog`].
+    fn symexp(&self, base: Self) -> Self;
 }
 
 impl SymLog for f32 {
@@ -352,6 +371,14 @@ impl SymLog for f32 {
             self.abs().log(base) * self.signum()
         }
     }
+
+    fn symexp(&self, base: Self) -> Self {
+        if self.abs() < 1.0 {
+            self * base
+        } else {
+            base.powf(self.abs()) * self.signum()
+        }
+    }
 }
 
 // ----------------------------------------------------------------------------
@@ -0,0 +1,544 @@
+use std::f32::consts::TAU;
+
+use egui::{Align2, FontId, Pos2, Response, Sense, Shape, Stroke, Ui, Vec2, Widget};
+use emath::{normalized_angle, Rot2};
+
+use crate::common::{Orientation, Winding};
+use crate::compasses::{CompassStyle, CompassStylePreset};
+
+// ----------------------------------------------------------------------------
+
+/// Combined into one function (rather than two) to make it easier
+/// for the borrow checker.
+type GetSetValue<'a> = Box<dyn 'a + FnMut(Option<f32>) -> f32>;
+
+fn get(get_set_value: &mut GetSetValue<'_>) -> f32 {
+    (get_set_value)(None)
+}
+
+fn set(get_set_value: &mut GetSetValue<'_>, value: f32) {
+    (get_set_value)(Some(value));
+}
+
+// ----------------------------------------------------------------------------
+
+/// Keeps the points of a convex polygon on the side of the line going
+/// through `origin` that `normal` points to.
+fn clip_polygon(points: &[Pos2], origin: Pos2, normal: Vec2) -> Vec<Pos2> {
+    let distance = |point: Pos2| (point - origin).dot(normal);
+    let mut clipped_points = Vec::new();
+
+    for (&a, &b) in points.iter().zip(points.iter().cycle().skip(1)) {
+        let (distance_a, distance_b) = (distance(a), distance(b));
+
+        if distance_a >= 0.0 {
+            clipped_points.push(a);
+        }
+
+        if (distance_a >= 0.0) != (distance_b >= 0.0) {
+            clipped_points.push(a + (b - a) * (distance_a / (distance_a - distance_b)));
+        }
+    }
+
+    clipped_points
+}
+
+// ----------------------------------------------------------------------------
+
+/// Artificial horizon showing a pitch ladder and a roll scale. Positive
+/// pitch raises the nose, positive roll banks the aircraft in the winding
+/// direction.
+#[must_use = "You should put this widget in an ui with `ui.add(widget);`"]
+pub struct AttitudeIndicator<'a> {
+    get_set_pitch: GetSetValue<'a>,
+    get_set_roll: GetSetValue<'a>,
+    interactive: bool,
+    diameter: f32,
+    orientation: Orientation,
+    winding: Winding,
+    pitch_spread: f32,
+    pitch_ladder_step: f32,
+    animated: bool,
+    show_pitch_ladder: bool,
+    show_roll_scale: bool,
+    style: Option<CompassStyle>,
+}
+
+impl<'a> AttitudeIndicator<'a> {
+    pub fn new(pitch: &'a mut f32, roll: &'a mut f32) -> Self {
+        Self::from_get_set(
+            move |v: Option<f32>| {
+                if let Some(v) = v {
+                    *pitch = v;
+                }
+                *pitch
+            },
+            move |v: Option<f32>| {
+                if let Some(v) = v {
+                    *roll = v;
+                }
+                *roll
+            },
+        )
+    }
+
+    pub fn from_get_set(
+        get_set_pitch: impl 'a + FnMut(Option<f32>) -> f32,
+        get_set_roll: impl 'a + FnMut(Option<f32>) -> f32,
+    ) -> Self {
+        Self {
+            get_set_pitch: Box::new(get_set_pitch),
+            get_set_roll: Box::new(get_set_roll),
+            interactive: true,
+            diameter: 128.0,
+            orientation: Orientation::Top,
+            winding: Winding::Clockwise,
+            pitch_spread: 60.0f32.to_radians(),
+            pitch_ladder_step: 10.0f32.to_radians(),
+            animated: false,
+            show_pitch_ladder: true,
+            show_roll_scale: true,
+            style: None,
+        }
+    }
+
+    /// Dragging the bezel sets the roll, dragging inside of it the pitch.
+    pub fn interactive(mut self, interactive: bool) -> Self {
+        self.interactive = interactive;
+        self
+    }
+
+    pub fn diameter(mut self, diameter: impl Into<f32>) -> Self {
+        let diameter = diameter.into();
+        assert!(diameter > 0.0);
+        self.diameter = diameter;
+        self
+    }
+
+    /// Direction of the sky when level.
+    pub fn orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    pub fn winding(mut self, winding: Winding) -> Self {
+        self.winding = winding;
+        self
+    }
+
+    /// Pitch angle visible across the whole diameter.
+    pub fn pitch_spread(mut self, pitch_spread: impl Into<f32>) -> Self {
+        let pitch_spread = pitch_spread.into();
+        assert!(pitch_spread > 0.0);
+        self.pitch_spread = pitch_spread;
+        self
+    }
+
+    pub fn pitch_ladder_step(mut self, pitch_ladder_step: impl Into<f32>) -> Self {
+        let pitch_ladder_step = pitch_ladder_step.into();
+        assert!(pitch_ladder_step > 0.0);
+        self.pitch_ladder_step = pitch_ladder_step;
+        self
+    }
+
+    pub fn animated(mut self, animated: bool) -> Self {
+        self.animated = animated;
+        self
+    }
+
+    pub fn show_pitch_ladder(mut self, show_pitch_ladder: bool) -> Self {
+        self.show_pitch_ladder = show_pitch_ladder;
+        self
+    }
+
+    pub fn show_roll_scale(mut self, show_roll_scale: bool) -> Self {
+        self.show_roll_scale = show_roll_scale;
+        self
+    }
+
+    /// Uses [`CompassStyle::system_style`] when `None`, with the outline and
+    /// the aircraft symbol highlighted on interaction.
+    pub fn style(mut self, style: Option<CompassStyle>) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn style_preset(mut self, preset: CompassStylePreset) -> Self {
+        self.style = Some(preset.style());
+        self
+    }
+
+    /// Screen direction of the sky for the given roll.
+    fn ladder_up(&self, roll: f32) -> Vec2 {
+        Rot2::from_angle(-roll * self.winding.to_float()) * self.orientation.rot2() * Vec2::RIGHT
+    }
+
+    /// Screen position of the middle of the horizon line for the given
+    /// attitude.
+    fn horizon_center(&self, center: Pos2, pitch: f32, roll: f32) -> Pos2 {
+        center - self.ladder_up(roll) * (pitch * self.pixels_per_radian())
+    }
+
+    fn pixels_per_radian(&self) -> f32 {
+        self.diameter / self.pitch_spread
+    }
+}
+
+impl<'a> Widget for AttitudeIndicator<'a> {
+    fn ui(mut self, ui: &mut Ui) -> Response {
+        let desired_size = Vec2::splat(self.diameter);
+
+        let (rect, mut response) = ui.allocate_exact_size(
+            desired_size,
+            if self.interactive {
+                Sense::click_and_drag()
+            } else {
+                Sense::hover()
+            },
+        );
+
+        let radius = self.diameter / 2.0;
+        let bezel_radius = radius * 0.8;
+        let up = self.orientation.rot2() * Vec2::RIGHT;
+
+        if response.drag_started() {
+            let press_origin = ui.input(|input| input.pointer.press_origin().unwrap());
+            let dragging_roll = press_origin.distance(rect.center()) >= bezel_radius;
+            ui.memory_mut(|memory| memory.data.insert_temp(response.id, dragging_roll));
+        }
+
+        if response.dragged() {
+            let dragging_roll = ui.memory_mut(|memory| memory.data.get_temp::<bool>(response.id));
+            let pointer_pos = response.interact_pointer_pos().unwrap();
+            let previous_pos = pointer_pos - response.drag_delta();
+
+            if dragging_roll == Some(true) {
+                // The horizon follows the pointer around the center
+                let angle_delta = normalized_angle(
+                    (pointer_pos - rect.center()).angle() - (previous_pos - rect.center()).angle(),
+                );
+
+                let new_roll = get(&mut self.get_set_roll) - angle_delta * self.winding.to_float();
+                set(&mut self.get_set_roll, normalized_angle(new_roll));
+            } else {
+                // The horizon follows the pointer along the pitch ladder
+                let roll = get(&mut self.get_set_roll);
+                let ladder_up = self.ladder_up(roll);
+
+                let new_pitch = get(&mut self.get_set_pitch)
+                    - response.drag_delta().dot(ladder_up) / self.pixels_per_radian();
+                set(
+                    &mut self.get_set_pitch,
+                    new_pitch.clamp(-TAU / 4.0, TAU / 4.0),
+                );
+            }
+
+            response.mark_changed();
+        }
+
+        if response.drag_released() {
+            ui.memory_mut(|memory| memory.data.remove::<bool>(response.id));
+        }
+
+        if ui.is_rect_visible(rect) {
+            let (pitch, roll) = if self.animated {
+                (
+                    ui.ctx().animate_value_with_time(
+                        response.id.with("pitch"),
+                        get(&mut self.get_set_pitch),
+                        ui.style().animation_time,
+                    ),
+                    ui.ctx().animate_value_with_time(
+                        response.id.with("roll"),
+                        get(&mut self.get_set_roll),
+                        ui.style().animation_time,
+                    ),
+                )
+            } else {
+                (get(&mut self.get_set_pitch), get(&mut self.get_set_roll))
+            };
+
+            let style = self
+                .style
+                .clone()
+                .unwrap_or_else(|| CompassStyle::interactive_system_style(ui, &response));
+
+            let ladder_up = self.ladder_up(roll);
+            let ladder_right = -ladder_up.rot90();
+            let horizon_center = self.horizon_center(rect.center(), pitch, roll);
+
+            // Sky and ground
+            {
+                const CIRCLE_SEGMENTS: usize = 64;
+
+                let circle_points = (0..CIRCLE_SEGMENTS)
+                    .map(|i| {
+                        rect.center()
+                            + Vec2::angled(i as f32 / CIRCLE_SEGMENTS as f32 * TAU) * radius
+                    })
+                    .collect::<Vec<_>>();
+
+                ui.painter()
+                    .circle_filled(rect.center(), radius, style.sky_color);
+
+                let ground_points = clip_polygon(&circle_points, horizon_center, -ladder_up);
+
+                if ground_points.len() >= 3 {
+                    ui.painter().add(Shape::convex_polygon(
+                        ground_points,
+                        style.ground_color,
+                        Stroke::NONE,
+                    ));
+                }
+            }
+
+            if self.show_pitch_ladder {
+                let ladder_limit = (TAU / 4.0 / self.pitch_ladder_step).floor() as isize * 2;
+
+                for step in -ladder_limit..=ladder_limit {
+                    let step_pitch = step as f32 * self.pitch_ladder_step / 2.0;
+                    let rung_center =
+                        horizon_center + ladder_up * (step_pitch * self.pixels_per_radian());
+
+                    // Only draw the rungs within the ladder window
+                    if rung_center.distance(rect.center()) > bezel_radius * 0.75 {
+                        continue;
+                    }
+
+                    if step == 0 {
+                        ui.painter().line_segment(
+                            [
+                                horizon_center - ladder_right * radius,
+                                horizon_center + ladder_right * radius,
+                            ],
+                            style.axis_stroke,
+                        );
+                        continue;
+                    }
+
+                    let is_major = step % 2 == 0;
+                    let rung_half_width = radius * if is_major { 0.25 } else { 0.125 };
+
+                    ui.painter().line_segment(
+                        [
+                            rung_center - ladder_right * rung_half_width,
+                            rung_center + ladder_right * rung_half_width,
+                        ],
+                        style.tick_stroke,
+                    );
+
+                    if is_major {
+                        let rung_label = format!("{:.0}", step_pitch.to_degrees().abs());
+                        let font_id = FontId::new(radius / 8.0, style.label_font_family.clone());
+
+                        for side in [-1.0, 1.0] {
+                            ui.painter().text(
+                                rung_center
+                                    + ladder_right * side * (rung_half_width + radius / 8.0),
+                                Align2::CENTER_CENTER,
+                                &rung_label,
+                                font_id.clone(),
+                                style.text_color,
+                            );
+                        }
+                    }
+                }
+            }
+
+            if self.show_roll_scale {
+                for roll_tick in [
+                    -60.0, -45.0, -30.0, -20.0, -10.0, 0.0, 10.0, 20.0, 30.0, 45.0, 60.0,
+                ] {
+                    let tick_direction = Rot2::from_angle(f32::to_radians(roll_tick)) * up;
+                    let tick_length = if roll_tick % 30.0 == 0.0 {
+                        radius * 0.15
+                    } else {
+                        radius * 0.08
+                    };
+
+                    ui.painter().line_segment(
+                        [
+                            rect.center() + tick_direction * (radius - tick_length),
+                            rect.center() + tick_direction * radius,
+                        ],
+                        style.tick_stroke,
+                    );
+                }
+
+                // Sky pointer, rotating with the horizon
+                let pointer_size = radius * 0.08;
+                let pointer_tip = rect.center() + ladder_up * (radius - radius * 0.15);
+
+                ui.painter().add(Shape::convex_polygon(
+                    vec![
+                        pointer_tip,
+                        pointer_tip - ladder_up * pointer_size * 1.5 + ladder_right * pointer_size,
+                        pointer_tip - ladder_up * pointer_size * 1.5 - ladder_right * pointer_size,
+                    ],
+                    style.tick_stroke.color,
+                    Stroke::NONE,
+                ));
+            }
+
+            // Fixed aircraft symbol
+            {
+                let aircraft_stroke = Stroke::new(3.0, style.cursor_color);
+                let wing_right = -up.rot90();
+
+                for side in [-1.0, 1.0] {
+                    ui.painter().add(Shape::line(
+                        vec![
+                            rect.center() + wing_right * side * radius * 0.5,
+                            rect.center() + wing_right * side * radius * 0.2,
+                            rect.center() + wing_right * side * radius * 0.2 - up * radius * 0.06,
+                        ],
+                        aircraft_stroke,
+                    ));
+                }
+
+                ui.painter()
+                    .circle_filled(rect.center(), 2.5, style.cursor_color);
+            }
+
+            ui.painter()
+                .circle_stroke(rect.center(), radius, style.outline_stroke);
+        }
+
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::TAU;
+
+    use egui::{pos2, vec2, Pos2, Vec2};
+
+    use super::{clip_polygon, AttitudeIndicator};
+    use crate::common::{Orientation, Winding};
+
+    const EPSILON: f32 = 1e-4;
+
+    fn assert_vec2_eq(a: Vec2, b: Vec2) {
+        assert!((a - b).length() < EPSILON, "{:?} != {:?}", a, b);
+    }
+
+    fn assert_pos2_eq(a: Pos2, b: Pos2) {
+        assert_vec2_eq(a.to_vec2(), b.to_vec2());
+    }
+
+    fn square() -> Vec<Pos2> {
+        vec![
+            pos2(0.0, 0.0),
+            pos2(2.0, 0.0),
+            pos2(2.0, 2.0),
+            pos2(0.0, 2.0),
+        ]
+    }
+
+    #[test]
+    fn clip_polygon_keeps_the_normal_side() {
+        let clipped_points = clip_polygon(&square(), pos2(1.0, 1.0), Vec2::DOWN);
+
+        assert_eq!(clipped_points.len(), 4);
+        for (a, b) in clipped_points.into_iter().zip([
+            pos2(2.0, 1.0),
+            pos2(2.0, 2.0),
+            pos2(0.0, 2.0),
+            pos2(0.0, 1.0),
+        ]) {
+            assert_pos2_eq(a, b);
+        }
+    }
+
+    #[test]
+    fn clip_polygon_cuts_corners_diagonally() {
+        let clipped_points = clip_polygon(&square(), pos2(1.5, 1.5), vec2(1.0, 1.0));
+
+        assert_eq!(clipped_points.len(), 3);
+        for (a, b) in
+            clipped_points
+                .into_iter()
+                .zip([pos2(2.0, 1.0), pos2(2.0, 2.0), pos2(1.0, 2.0)])
+        {
+            assert_pos2_eq(a, b);
+        }
+    }
+
+    #[test]
+    fn clip_polygon_handles_lines_missing_the_polygon() {
+        assert_eq!(
+            clip_polygon(&square(), pos2(1.0, -1.0), Vec2::DOWN),
+            square()
+        );
+        assert!(clip_polygon(&square(), pos2(1.0, 3.0), Vec2::DOWN).is_empty());
+    }
+
+    #[test]
+    fn level_attitude_centers_the_horizon() {
+        let (mut pitch, mut roll) = (0.0, 0.0);
+        let attitude_indicator = AttitudeIndicator::new(&mut pitch, &mut roll);
+
+        assert_vec2_eq(attitude_indicator.ladder_up(0.0), Vec2::UP);
+        assert_pos2_eq(
+            attitude_indicator.horizon_center(pos2(100.0, 100.0), 0.0, 0.0),
+            pos2(100.0, 100.0),
+        );
+    }
+
+    #[test]
+    fn pitch_spread_maps_to_the_diameter() {
+        let (mut pitch, mut roll) = (0.0, 0.0);
+        let attitude_indicator = AttitudeIndicator::new(&mut pitch, &mut roll)
+            .diameter(200.0)
+            .pitch_spread(TAU / 4.0);
+
+        // Nose up by half the spread lowers the horizon to the bottom edge
+        assert_pos2_eq(
+            attitude_indicator.horizon_center(pos2(100.0, 100.0), TAU / 8.0, 0.0),
+            pos2(100.0, 200.0),
+        );
+        assert_pos2_eq(
+            attitude_indicator.horizon_center(pos2(100.0, 100.0), -TAU / 8.0, 0.0),
+            pos2(100.0, 0.0),
+        );
+    }
+
+    #[test]
+    fn roll_rotates_the_horizon_against_the_winding() {
+        let (mut pitch, mut roll) = (0.0, 0.0);
+        let clockwise = AttitudeIndicator::new(&mut pitch, &mut roll);
+
+        // Banking right puts the sky on the left
+        assert_vec2_eq(clockwise.ladder_up(TAU / 4.0), Vec2::LEFT);
+
+        let (mut pitch, mut roll) = (0.0, 0.0);
+        let counterclockwise =
+            AttitudeIndicator::new(&mut pitch, &mut roll).winding(Winding::Counterclockwise);
+
+        assert_vec2_eq(counterclockwise.ladder_up(TAU / 4.0), Vec2::RIGHT);
+    }
+
+    #[test]
+    fn orientation_points_the_sky() {
+        let (mut pitch, mut roll) = (0.0, 0.0);
+        let attitude_indicator =
+            AttitudeIndicator::new(&mut pitch, &mut roll).orientation(Orientation::Right);
+
+        assert_vec2_eq(attitude_indicator.ladder_up(0.0), Vec2::RIGHT);
+        assert_pos2_eq(
+            attitude_indicator.horizon_center(pos2(0.0, 0.0), 10.0f32.to_radians(), 0.0),
+            pos2(
+                -attitude_indicator.pixels_per_radian() * 10.0f32.to_radians(),
+                0.0,
+            ),
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_diameter_is_rejected() {
+        let (mut pitch, mut roll) = (0.0, 0.0);
+        let _ = AttitudeIndicator::new(&mut pitch, &mut roll).diameter(0.0);
+    }
+}
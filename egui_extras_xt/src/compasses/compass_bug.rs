@@ -0,0 +1,130 @@
+use egui::{Color32, Pos2, Shape, Stroke, Ui, Vec2};
+use strum::{Display, EnumIter};
+
+// ----------------------------------------------------------------------------
+
+/// Combined into one function (rather than two) to make it easier
+/// for the borrow checker.
+type GetSetValue<'a> = Box<dyn 'a + FnMut(Option<f32>) -> f32>;
+
+// ----------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, Display, EnumIter, Eq, PartialEq)]
+pub enum CompassBugKind {
+    #[strum(to_string = "Heading")]
+    Heading,
+
+    #[strum(to_string = "Course")]
+    Course,
+
+    #[strum(to_string = "Waypoint")]
+    Waypoint,
+}
+
+impl CompassBugKind {
+    fn default_color(&self) -> Color32 {
+        match *self {
+            CompassBugKind::Heading => Color32::from_rgb(0x00, 0xFF, 0xFF),
+            CompassBugKind::Course => Color32::from_rgb(0x00, 0xFF, 0x00),
+            CompassBugKind::Waypoint => Color32::from_rgb(0xFF, 0x00, 0xFF),
+        }
+    }
+
+    /// Outline of the bug, with `x` running along the edge of the compass and
+    /// `y` pointing inwards from it.
+    fn outline(&self, size: f32) -> Vec<Vec2> {
+        match *self {
+            CompassBugKind::Heading => vec![
+                Vec2::new(-size / 2.0, 0.0),
+                Vec2::new(size / 2.0, 0.0),
+                Vec2::new(size / 2.0, size / 3.0),
+                Vec2::new(-size / 2.0, size / 3.0),
+            ],
+            CompassBugKind::Course => vec![
+                Vec2::new(-size / 3.0, 0.0),
+                Vec2::new(size / 3.0, 0.0),
+                Vec2::new(0.0, size / 2.0),
+            ],
+            CompassBugKind::Waypoint => vec![
+                Vec2::new(0.0, 0.0),
+                Vec2::new(size / 3.0, size / 3.0),
+                Vec2::new(0.0, size * 2.0 / 3.0),
+                Vec2::new(-size / 3.0, size / 3.0),
+            ],
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Bearing indicator drawn on the edge of a compass, like a heading bug
+/// or a waypoint bearing, or a target value on the edge of a tape. Bound to
+/// its own value separate from the value of the widget.
+#[must_use = "You should put this bug into a compass with `compass.bug(bug);`"]
+pub struct CompassBug<'a> {
+    get_set_value: GetSetValue<'a>,
+    pub(crate) kind: CompassBugKind,
+    pub(crate) color: Option<Color32>,
+    pub(crate) interactive: bool,
+}
+
+impl<'a> CompassBug<'a> {
+    pub fn new(kind: CompassBugKind, value: &'a mut f32) -> Self {
+        Self::from_get_set(kind, move |v: Option<f32>| {
+            if let Some(v) = v {
+                *value = v;
+            }
+            *value
+        })
+    }
+
+    pub fn from_get_set(
+        kind: CompassBugKind,
+        get_set_value: impl 'a + FnMut(Option<f32>) -> f32,
+    ) -> Self {
+        Self {
+            get_set_value: Box::new(get_set_value),
+            kind,
+            color: None,
+            interactive: true,
+        }
+    }
+
+    pub fn color(mut self, color: Color32) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn interactive(mut self, interactive: bool) -> Self {
+        self.interactive = interactive;
+        self
+    }
+
+    pub(crate) fn get(&mut self) -> f32 {
+        (self.get_set_value)(None)
+    }
+
+    pub(crate) fn set(&mut self, value: f32) {
+        (self.get_set_value)(Some(value));
+    }
+
+    /// Screen outline of the bug, `to_screen` maps from a space where `x` runs
+    /// along the edge of the compass and `y` points inwards from it.
+    pub(crate) fn outline(&self, size: f32, to_screen: impl Fn(Vec2) -> Pos2) -> Vec<Pos2> {
+        self.kind.outline(size).into_iter().map(to_screen).collect()
+    }
+
+    pub(crate) fn paint(
+        &self,
+        ui: &Ui,
+        outline: Vec<Pos2>,
+        outline_color: Color32,
+        highlighted: bool,
+    ) {
+        let fill = self.color.unwrap_or_else(|| self.kind.default_color());
+        let stroke = Stroke::new(if highlighted { 2.0 } else { 1.0 }, outline_color);
+
+        ui.painter()
+            .add(Shape::convex_polygon(outline, fill, stroke));
+    }
+}
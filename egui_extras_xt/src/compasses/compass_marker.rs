@@ -1,7 +1,11 @@
 use std::f32::consts::TAU;
+use std::hash::Hash;
 
 use ecolor::Hsva;
-use egui::{vec2, Align2, Color32, FontFamily, FontId, Rect, Shape, Stroke, Ui, Vec2};
+use egui::{
+    pos2, show_tooltip_at_pointer, vec2, Align2, Color32, FontFamily, FontId, Id, Rect, Shape,
+    Stroke, TextureId, Ui, Vec2,
+};
 use itertools::Itertools;
 use strum::Display;
 
@@ -55,7 +59,7 @@ impl DefaultCompassMarkerColor {
                 saturation,
                 value,
             } => {
-                let marker_label = marker.label.unwrap_or("");
+                let marker_label = marker.label.as_deref().unwrap_or("");
                 let hue_raw = marker_label.pearson_hash() as f32 / 255.0;
                 let hue = (hue_raw + hue_phase).rem_euclid(1.0);
                 Color32::from(Hsva::new(hue, saturation, value, 1.0))
@@ -67,15 +71,21 @@ impl DefaultCompassMarkerColor {
 // ----------------------------------------------------------------------------
 
 #[must_use = "You should put this marker into a compass with `compass.markers(&[markers]);`"]
-pub struct CompassMarker<'a> {
+#[derive(Clone, Debug)]
+pub struct CompassMarker {
     pub(crate) angle: f32,
     pub(crate) distance: Option<f32>,
     pub(crate) shape: Option<CompassMarkerShape>,
-    pub(crate) label: Option<&'a str>,
+    pub(crate) label: Option<String>,
     pub(crate) color: Option<Color32>,
+    pub(crate) id: Option<Id>,
+    pub(crate) icon: Option<TextureId>,
+    pub(crate) tooltip: Option<String>,
+    pub(crate) altitude: Option<f32>,
+    pub(crate) velocity: Option<(f32, f32)>,
 }
 
-impl<'a> CompassMarker<'a> {
+impl CompassMarker {
     pub fn new(angle: f32) -> Self {
         Self {
             angle: normalized_angle_unsigned_excl(angle),
@@ -83,6 +93,11 @@ impl<'a> CompassMarker<'a> {
             shape: None,
             label: None,
             color: None,
+            id: None,
+            icon: None,
+            tooltip: None,
+            altitude: None,
+            velocity: None,
         }
     }
 
@@ -96,8 +111,8 @@ impl<'a> CompassMarker<'a> {
         self
     }
 
-    pub fn label(mut self, label: &'a str) -> Self {
-        self.label = Some(label);
+    pub fn label(mut self, label: impl ToString) -> Self {
+        self.label = Some(label.to_string());
         self
     }
 
@@ -105,6 +120,86 @@ impl<'a> CompassMarker<'a> {
         self.color = Some(color);
         self
     }
+
+    /// Stable identifier reported back by the compasses, eg. when the marker
+    /// is hovered or selected. Markers without an ID are identified by their
+    /// index in the marker slice instead.
+    pub fn id(mut self, id_source: impl Hash) -> Self {
+        self.id = Some(Id::new(id_source));
+        self
+    }
+
+    /// Image drawn in place of the marker shape.
+    pub fn icon(mut self, icon: TextureId) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    /// Additional text shown when hovering the marker.
+    pub fn tooltip(mut self, tooltip: impl ToString) -> Self {
+        self.tooltip = Some(tooltip.to_string());
+        self
+    }
+
+    pub fn altitude(mut self, altitude: f32) -> Self {
+        self.altitude = Some(altitude);
+        self
+    }
+
+    /// Speed in distance units per second, and course in the same angular
+    /// units as the marker bearing. Used for the velocity vectors of
+    /// [`PolarCompass`](crate::compasses::PolarCompass) instead of estimating
+    /// the velocity from the recent marker positions.
+    pub fn velocity(mut self, speed: f32, course: f32) -> Self {
+        self.velocity = Some((speed, normalized_angle_unsigned_excl(course)));
+        self
+    }
+
+    pub(crate) fn id_or_index(&self, index: usize) -> Id {
+        self.id
+            .unwrap_or_else(|| Id::new(("compass_marker", index)))
+    }
+
+    pub(crate) fn show_tooltip(&self, ui: &Ui, id: Id) {
+        show_tooltip_at_pointer(ui.ctx(), id, |ui| {
+            if let Some(label) = &self.label {
+                ui.strong(label);
+            }
+
+            if let Some(tooltip) = &self.tooltip {
+                ui.label(tooltip);
+            }
+
+            ui.label(format!("Bearing: {:.1}°", self.angle.to_degrees()));
+
+            if let Some(distance) = self.distance {
+                ui.label(format!("Distance: {distance:.1}"));
+            }
+
+            if let Some(altitude) = self.altitude {
+                ui.label(format!("Altitude: {altitude:.1}"));
+            }
+
+            if let Some((speed, course)) = self.velocity {
+                ui.label(format!("Speed: {speed:.1}"));
+                ui.label(format!("Course: {:.1}°", course.to_degrees()));
+            }
+        });
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Position a marker is being dragged to. Compasses don't move markers by
+/// themselves, the new position has to be applied to wherever the markers are
+/// built from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CompassMarkerDrag {
+    /// Index of the marker in the slice passed to the compass.
+    pub index: usize,
+    pub id: Id,
+    pub angle: f32,
+    pub distance: Option<f32>,
 }
 
 // ----------------------------------------------------------------------------
@@ -141,6 +236,27 @@ pub enum CompassMarkerShape {
 }
 
 impl CompassMarkerShape {
+    /// Paints `icon` instead of the shape when given.
+    pub(crate) fn paint_or_icon(
+        &self,
+        ui: &mut Ui,
+        rect: Rect,
+        icon: Option<TextureId>,
+        fill: Color32,
+        stroke: Stroke,
+    ) {
+        if let Some(icon) = icon {
+            ui.painter().image(
+                icon,
+                rect,
+                Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0)),
+                Color32::WHITE,
+            );
+        } else {
+            self.paint(ui, rect, fill, stroke);
+        }
+    }
+
     pub(crate) fn paint(&self, ui: &mut Ui, rect: Rect, fill: Color32, stroke: Stroke) {
         match *self {
             CompassMarkerShape::Square => {
@@ -0,0 +1,72 @@
+use std::f32::consts::TAU;
+use std::ops::RangeInclusive;
+
+use egui::{Color32, Ui};
+
+use crate::common::normalized_angle_unsigned_excl;
+
+// ----------------------------------------------------------------------------
+
+/// Shaded angular range drawn under the markers of a compass, eg. a field of
+/// view, a blind arc or the variance of a bearing.
+#[must_use = "You should put this sector into a compass with `compass.sectors(&[sectors]);`"]
+#[derive(Clone, Debug)]
+pub struct CompassSector {
+    pub(crate) start: f32,
+    pub(crate) span: f32,
+    pub(crate) distance_range: Option<RangeInclusive<f32>>,
+    pub(crate) label: Option<String>,
+    pub(crate) color: Option<Color32>,
+}
+
+impl CompassSector {
+    /// Sector going from `start` to `end` in the direction of increasing
+    /// angles, wrapping around north when `end` is smaller than `start`.
+    pub fn new(start: f32, end: f32) -> Self {
+        let span = if (end - start).abs() >= TAU {
+            TAU
+        } else {
+            (end - start).rem_euclid(TAU)
+        };
+
+        Self {
+            start: normalized_angle_unsigned_excl(start),
+            span,
+            distance_range: None,
+            label: None,
+            color: None,
+        }
+    }
+
+    /// Sector spanning `width` centered around the `bearing`.
+    pub fn centered(bearing: f32, width: f32) -> Self {
+        Self::new(bearing - width / 2.0, bearing + width / 2.0)
+    }
+
+    /// Limits the sector to an annular wedge on a `PolarCompass`, in the same
+    /// units as the marker distances. Defaults to the whole radius.
+    pub fn distance_range(mut self, distance_range: RangeInclusive<f32>) -> Self {
+        self.distance_range = Some(distance_range);
+        self
+    }
+
+    pub fn label(mut self, label: impl ToString) -> Self {
+        self.label = Some(label.to_string());
+        self
+    }
+
+    pub fn color(mut self, color: Color32) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub(crate) fn fill_color(&self, ui: &Ui) -> Color32 {
+        self.color
+            .unwrap_or_else(|| ui.style().visuals.selection.bg_fill.linear_multiply(0.25))
+    }
+
+    /// Angle halfway through the sector.
+    pub(crate) fn middle(&self) -> f32 {
+        self.start + self.span / 2.0
+    }
+}
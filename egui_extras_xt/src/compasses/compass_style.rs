@@ -0,0 +1,153 @@
+use egui::{Color32, FontFamily, Response, Stroke, Ui};
+use strum::{Display, EnumIter};
+
+// ----------------------------------------------------------------------------
+
+#[derive(Clone, Debug)]
+pub struct CompassStyle {
+    pub background_color: Color32,
+    pub outline_stroke: Stroke,
+    pub ring_stroke: Stroke,
+    pub axis_stroke: Stroke,
+    pub tick_stroke: Stroke,
+    pub cursor_color: Color32,
+    pub cursor_stroke: Stroke,
+    pub text_color: Color32,
+    pub label_font_family: FontFamily,
+
+    /// Upper half of the [`crate::compasses::AttitudeIndicator`].
+    pub sky_color: Color32,
+
+    /// Lower half of the [`crate::compasses::AttitudeIndicator`].
+    pub ground_color: Color32,
+}
+
+impl CompassStyle {
+    /// Style derived from the current egui theme, used by the compasses when
+    /// no style is set.
+    #[must_use]
+    pub fn system_style(ui: &Ui) -> Self {
+        let visuals = &ui.style().visuals;
+
+        CompassStyle {
+            background_color: visuals.extreme_bg_color,
+            outline_stroke: visuals.widgets.noninteractive.fg_stroke,
+            ring_stroke: visuals.widgets.noninteractive.bg_stroke,
+            axis_stroke: visuals.widgets.noninteractive.fg_stroke,
+            tick_stroke: visuals.widgets.noninteractive.fg_stroke,
+            cursor_color: visuals.widgets.inactive.bg_fill,
+            cursor_stroke: visuals.widgets.inactive.fg_stroke,
+            text_color: visuals.text_color(),
+            label_font_family: FontFamily::Proportional,
+            sky_color: Color32::from_rgb(0x3A, 0x7B, 0xD5),
+            ground_color: Color32::from_rgb(0x8B, 0x5A, 0x2B),
+        }
+    }
+
+    /// [`Self::system_style`] with the outline and the cursor following the
+    /// interaction state of `response`, eg. highlighted while hovered.
+    pub(crate) fn interactive_system_style(ui: &Ui, response: &Response) -> Self {
+        let visuals = ui.style().interact(response);
+
+        CompassStyle {
+            outline_stroke: visuals.fg_stroke,
+            cursor_color: visuals.bg_fill,
+            cursor_stroke: visuals.fg_stroke,
+            ..Self::system_style(ui)
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Display, EnumIter, Eq, PartialEq)]
+pub enum CompassStylePreset {
+    #[strum(to_string = "Light")]
+    Light,
+
+    #[strum(to_string = "Dark")]
+    Dark,
+
+    #[strum(to_string = "Aviation")]
+    Aviation,
+
+    #[strum(to_string = "Marine")]
+    Marine,
+
+    #[strum(to_string = "Night Vision")]
+    NightVision,
+}
+
+impl CompassStylePreset {
+    #[must_use]
+    pub fn style(&self) -> CompassStyle {
+        match *self {
+            CompassStylePreset::Light => CompassStyle {
+                background_color: Color32::from_rgb(0xFF, 0xFF, 0xFF),
+                outline_stroke: Stroke::new(1.0, Color32::from_rgb(0x40, 0x40, 0x40)),
+                ring_stroke: Stroke::new(1.0, Color32::from_rgb(0xC8, 0xC8, 0xC8)),
+                axis_stroke: Stroke::new(1.0, Color32::from_rgb(0x40, 0x40, 0x40)),
+                tick_stroke: Stroke::new(1.0, Color32::from_rgb(0x80, 0x80, 0x80)),
+                cursor_color: Color32::from_rgb(0xE0, 0x40, 0x40),
+                cursor_stroke: Stroke::new(1.0, Color32::from_rgb(0x80, 0x00, 0x00)),
+                text_color: Color32::from_rgb(0x20, 0x20, 0x20),
+                label_font_family: FontFamily::Proportional,
+                sky_color: Color32::from_rgb(0x8C, 0xC4, 0xF0),
+                ground_color: Color32::from_rgb(0xC8, 0x9B, 0x6A),
+            },
+            CompassStylePreset::Dark => CompassStyle {
+                background_color: Color32::from_rgb(0x1B, 0x1B, 0x1B),
+                outline_stroke: Stroke::new(1.0, Color32::from_rgb(0xA0, 0xA0, 0xA0)),
+                ring_stroke: Stroke::new(1.0, Color32::from_rgb(0x3C, 0x3C, 0x3C)),
+                axis_stroke: Stroke::new(1.0, Color32::from_rgb(0xA0, 0xA0, 0xA0)),
+                tick_stroke: Stroke::new(1.0, Color32::from_rgb(0x70, 0x70, 0x70)),
+                cursor_color: Color32::from_rgb(0xFF, 0xB0, 0x00),
+                cursor_stroke: Stroke::new(1.0, Color32::from_rgb(0xFF, 0xD0, 0x60)),
+                text_color: Color32::from_rgb(0xE0, 0xE0, 0xE0),
+                label_font_family: FontFamily::Proportional,
+                sky_color: Color32::from_rgb(0x1F, 0x4E, 0x8C),
+                ground_color: Color32::from_rgb(0x5C, 0x3A, 0x1C),
+            },
+            CompassStylePreset::Aviation => CompassStyle {
+                background_color: Color32::from_rgb(0x00, 0x00, 0x00),
+                outline_stroke: Stroke::new(1.0, Color32::from_rgb(0xFF, 0xFF, 0xFF)),
+                ring_stroke: Stroke::new(1.0, Color32::from_rgb(0x40, 0x40, 0x40)),
+                axis_stroke: Stroke::new(1.0, Color32::from_rgb(0xFF, 0xFF, 0xFF)),
+                tick_stroke: Stroke::new(1.0, Color32::from_rgb(0xFF, 0xFF, 0xFF)),
+                cursor_color: Color32::from_rgb(0xFF, 0xFF, 0x00),
+                cursor_stroke: Stroke::new(1.0, Color32::from_rgb(0xFF, 0xFF, 0x00)),
+                text_color: Color32::from_rgb(0xFF, 0xFF, 0xFF),
+                label_font_family: FontFamily::Monospace,
+                sky_color: Color32::from_rgb(0x00, 0x7B, 0xD5),
+                ground_color: Color32::from_rgb(0x8B, 0x5A, 0x2B),
+            },
+            CompassStylePreset::Marine => CompassStyle {
+                background_color: Color32::from_rgb(0xF2, 0xE8, 0xCF),
+                outline_stroke: Stroke::new(1.0, Color32::from_rgb(0x5C, 0x40, 0x33)),
+                ring_stroke: Stroke::new(1.0, Color32::from_rgb(0xCB, 0xBF, 0xA2)),
+                axis_stroke: Stroke::new(1.0, Color32::from_rgb(0x5C, 0x40, 0x33)),
+                tick_stroke: Stroke::new(1.0, Color32::from_rgb(0x8B, 0x73, 0x55)),
+                cursor_color: Color32::from_rgb(0xB2, 0x22, 0x22),
+                cursor_stroke: Stroke::new(1.0, Color32::from_rgb(0x5C, 0x10, 0x10)),
+                text_color: Color32::from_rgb(0x3B, 0x2A, 0x1E),
+                label_font_family: FontFamily::Proportional,
+                sky_color: Color32::from_rgb(0x9D, 0xC4, 0xD8),
+                ground_color: Color32::from_rgb(0xA6, 0x7B, 0x4F),
+            },
+            CompassStylePreset::NightVision => CompassStyle {
+                background_color: Color32::from_rgb(0x10, 0x00, 0x00),
+                outline_stroke: Stroke::new(1.0, Color32::from_rgb(0xA0, 0x00, 0x00)),
+                ring_stroke: Stroke::new(1.0, Color32::from_rgb(0x40, 0x00, 0x00)),
+                axis_stroke: Stroke::new(1.0, Color32::from_rgb(0xA0, 0x00, 0x00)),
+                tick_stroke: Stroke::new(1.0, Color32::from_rgb(0x70, 0x00, 0x00)),
+                cursor_color: Color32::from_rgb(0xFF, 0x20, 0x20),
+                cursor_stroke: Stroke::new(1.0, Color32::from_rgb(0xFF, 0x20, 0x20)),
+                text_color: Color32::from_rgb(0xC0, 0x00, 0x00),
+                label_font_family: FontFamily::Proportional,
+                sky_color: Color32::from_rgb(0x30, 0x00, 0x00),
+                ground_color: Color32::from_rgb(0x18, 0x00, 0x00),
+            },
+        }
+    }
+}
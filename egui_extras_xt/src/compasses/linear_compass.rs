@@ -1,13 +1,18 @@
 use std::f32::consts::TAU;
 
 use ecolor::tint_color_towards;
-use egui::{self, Response, Sense, Ui, Widget};
+use egui::{self, CursorIcon, Id, Pos2, Response, Sense, Ui, Widget};
 use emath::{normalized_angle, pos2, vec2, Align2, Rect, Vec2};
-use epaint::{Color32, FontFamily, FontId, Stroke};
+use epaint::{Color32, FontId, Stroke, TextureId};
 
-use crate::common::{normalized_angle_unsigned_incl, Winding, WrapMode};
+use crate::common::{
+    normalized_angle_unsigned_excl, normalized_angle_unsigned_incl, LinearOrientation, Winding,
+    WrapMode,
+};
+use crate::compasses::tape_scale::TapeScale;
 use crate::compasses::{
-    CompassAxisLabels, CompassMarker, CompassMarkerShape, DefaultCompassMarkerColor,
+    CompassAxisLabels, CompassBug, CompassMarker, CompassMarkerDrag, CompassMarkerShape,
+    CompassSector, CompassStyle, CompassStylePreset, DefaultCompassMarkerColor,
 };
 
 // ----------------------------------------------------------------------------
@@ -44,9 +49,17 @@ pub struct LinearCompass<'a> {
     show_cursor: bool,
     show_ticks: bool,
     show_axes: bool,
-    markers: &'a [CompassMarker<'a>],
+    show_marker_tooltips: bool,
+    style: Option<CompassStyle>,
+    markers: &'a [CompassMarker],
+    sectors: &'a [CompassSector],
+    bugs: Vec<CompassBug<'a>>,
     default_marker_color: DefaultCompassMarkerColor,
     default_marker_shape: CompassMarkerShape,
+    hovered_marker: Option<&'a mut Option<Id>>,
+    clicked_marker: Option<&'a mut Option<Id>>,
+    selected_marker: Option<&'a mut Option<Id>>,
+    dragged_marker: Option<&'a mut Option<CompassMarkerDrag>>,
 }
 
 impl<'a> LinearCompass<'a> {
@@ -77,13 +90,21 @@ impl<'a> LinearCompass<'a> {
             show_cursor: true,
             show_ticks: true,
             show_axes: true,
+            show_marker_tooltips: true,
+            style: None,
             markers: &[],
+            sectors: &[],
+            bugs: Vec::new(),
             default_marker_color: DefaultCompassMarkerColor::HsvByAngle {
                 hue_phase: 0.0,
                 saturation: 1.0,
                 value: 1.0,
             },
             default_marker_shape: CompassMarkerShape::Square,
+            hovered_marker: None,
+            clicked_marker: None,
+            selected_marker: None,
+            dragged_marker: None,
         }
     }
 
@@ -162,11 +183,41 @@ impl<'a> LinearCompass<'a> {
         self
     }
 
+    pub fn show_marker_tooltips(mut self, show_marker_tooltips: bool) -> Self {
+        self.show_marker_tooltips = show_marker_tooltips;
+        self
+    }
+
+    /// Uses [`CompassStyle::system_style`] when `None`, with the outline and
+    /// the cursor highlighted on interaction.
+    pub fn style(mut self, style: Option<CompassStyle>) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn style_preset(mut self, preset: CompassStylePreset) -> Self {
+        self.style = Some(preset.style());
+        self
+    }
+
     pub fn markers(mut self, markers: &'a [CompassMarker]) -> Self {
         self.markers = markers;
         self
     }
 
+    /// Shaded spans drawn under the markers.
+    pub fn sectors(mut self, sectors: &'a [CompassSector]) -> Self {
+        self.sectors = sectors;
+        self
+    }
+
+    /// Adds a bearing indicator on the top edge, bound to its own value.
+    /// Interactive bugs can be dragged along the compass.
+    pub fn bug(mut self, bug: CompassBug<'a>) -> Self {
+        self.bugs.push(bug);
+        self
+    }
+
     pub fn default_marker_color(mut self, default_marker_color: DefaultCompassMarkerColor) -> Self {
         self.default_marker_color = default_marker_color;
         self
@@ -176,6 +227,98 @@ impl<'a> LinearCompass<'a> {
         self.default_marker_shape = default_marker_shape;
         self
     }
+
+    /// ID of the marker under the mouse pointer.
+    pub fn hovered_marker(mut self, hovered_marker: &'a mut Option<Id>) -> Self {
+        self.hovered_marker = Some(hovered_marker);
+        self
+    }
+
+    /// ID of the marker clicked this frame.
+    pub fn clicked_marker(mut self, clicked_marker: &'a mut Option<Id>) -> Self {
+        self.clicked_marker = Some(clicked_marker);
+        self
+    }
+
+    /// ID of the highlighted marker, changed by clicking on a marker or
+    /// cleared by clicking elsewhere on the compass.
+    pub fn selected_marker(mut self, selected_marker: &'a mut Option<Id>) -> Self {
+        self.selected_marker = Some(selected_marker);
+        self
+    }
+
+    /// Makes markers draggable, reporting the new angle of the marker being
+    /// dragged this frame.
+    pub fn dragged_marker(mut self, dragged_marker: &'a mut Option<CompassMarkerDrag>) -> Self {
+        self.dragged_marker = Some(dragged_marker);
+        self
+    }
+
+    fn scale(&self, rect: Rect, value: f32) -> TapeScale {
+        TapeScale::new(
+            rect,
+            LinearOrientation::Horizontal,
+            value,
+            self.spread * self.winding.to_float(),
+        )
+    }
+
+    fn map_angle_to_screen(&self, rect: Rect, value: f32, angle: f32) -> f32 {
+        self.scale(rect, value).value_to_screen(angle)
+    }
+
+    fn map_screen_to_angle(&self, rect: Rect, value: f32, pos: Pos2) -> f32 {
+        self.scale(rect, value).screen_to_value(pos)
+    }
+
+    fn marker_rect(&self, rect: Rect, value: f32, angle: f32) -> Rect {
+        let center = pos2(
+            self.map_angle_to_screen(rect, value, angle),
+            rect.top() + self.height * 0.375,
+        );
+        Rect::from_center_size(center, Vec2::splat(self.height * 0.25))
+    }
+
+    /// Screen outline of a bug, pegged to the sides of the widget when the bug
+    /// is outside of the visible range.
+    fn bug_outline(&self, rect: Rect, value: f32, bug: &CompassBug, bug_angle: f32) -> Vec<Pos2> {
+        let bug_size = self.height * 0.25;
+
+        let bug_x = self
+            .map_angle_to_screen(rect, value, value + normalized_angle(bug_angle - value))
+            .clamp(rect.left() + bug_size / 2.0, rect.right() - bug_size / 2.0);
+
+        bug.outline(bug_size, |point| {
+            pos2(bug_x + point.x, rect.top() + point.y)
+        })
+    }
+
+    /// Index of the topmost interactive bug at `pos`.
+    fn bug_at(&self, rect: Rect, value: f32, bug_angles: &[f32], pos: Pos2) -> Option<usize> {
+        self.bugs
+            .iter()
+            .zip(bug_angles)
+            .rposition(|(bug, &bug_angle)| {
+                bug.interactive
+                    && Rect::from_points(&self.bug_outline(rect, value, bug, bug_angle))
+                        .expand(2.0)
+                        .contains(pos)
+            })
+    }
+
+    /// Index of the topmost marker at `pos`, checking every turn visible in
+    /// the widget.
+    fn marker_at(&self, rect: Rect, value: f32, pos: Pos2) -> Option<usize> {
+        let start_tau = ((value - (self.spread.abs() / 2.0)) / TAU).floor() as isize;
+        let end_tau = ((value + (self.spread.abs() / 2.0)) / TAU).ceil() as isize;
+
+        (start_tau..=end_tau).rev().find_map(|tau| {
+            self.markers.iter().rposition(|marker| {
+                self.marker_rect(rect, value, (tau as f32 * TAU) + marker.angle)
+                    .contains(pos)
+            })
+        })
+    }
 }
 
 impl<'a> Widget for LinearCompass<'a> {
@@ -194,6 +337,95 @@ impl<'a> Widget for LinearCompass<'a> {
         let mut child_ui = ui.child_ui(rect, *ui.layout());
         child_ui.set_clip_rect(child_ui.clip_rect().intersect(rect));
 
+        let current_value = get(&mut self.get_set_value);
+        let marker_drag_id = response.id.with("marker_drag");
+        let bug_drag_id = response.id.with("bug_drag");
+
+        let mut bug_angles = self
+            .bugs
+            .iter_mut()
+            .map(|bug| bug.get())
+            .collect::<Vec<_>>();
+
+        let hovered_bug_index = response
+            .hover_pos()
+            .and_then(|pos| self.bug_at(rect, current_value, &bug_angles, pos));
+
+        let hovered_marker_index = response
+            .hover_pos()
+            .and_then(|pos| self.marker_at(rect, current_value, pos));
+
+        if response.drag_started() {
+            let press_origin = ui.input(|input| input.pointer.press_origin().unwrap());
+
+            if let Some(bug_index) = self.bug_at(rect, current_value, &bug_angles, press_origin) {
+                ui.memory_mut(|memory| memory.data.insert_temp(bug_drag_id, bug_index));
+            }
+        }
+
+        let bug_drag = ui.memory_mut(|memory| memory.data.get_temp::<usize>(bug_drag_id));
+
+        if response.drag_started() && bug_drag.is_none() && self.dragged_marker.is_some() {
+            let press_origin = ui.input(|input| input.pointer.press_origin().unwrap());
+
+            if let Some(marker_index) = self.marker_at(rect, current_value, press_origin) {
+                // Keep the marker at the same offset from the pointer it was grabbed at,
+                // wrapped since the grabbed marker may be drawn on any visible turn
+                let grab_offset = normalized_angle(
+                    self.map_screen_to_angle(rect, current_value, press_origin)
+                        - self.markers[marker_index].angle,
+                );
+
+                ui.memory_mut(|memory| {
+                    memory
+                        .data
+                        .insert_temp(marker_drag_id, (marker_index, grab_offset))
+                });
+            }
+        }
+
+        let marker_drag =
+            ui.memory_mut(|memory| memory.data.get_temp::<(usize, f32)>(marker_drag_id));
+
+        let hovered_marker_id = hovered_marker_index
+            .map(|marker_index| self.markers[marker_index].id_or_index(marker_index));
+
+        if let Some(hovered_marker) = self.hovered_marker.as_mut() {
+            **hovered_marker = hovered_marker_id;
+        }
+
+        if let Some(clicked_marker) = self.clicked_marker.as_mut() {
+            **clicked_marker = if response.clicked() {
+                hovered_marker_id
+            } else {
+                None
+            };
+        }
+
+        if let Some(selected_marker) = self.selected_marker.as_mut() {
+            if response.clicked() {
+                **selected_marker = hovered_marker_id;
+            }
+        }
+
+        let selected_marker_id = self.selected_marker.as_deref().copied().flatten();
+
+        if let Some(dragged_marker) = self.dragged_marker.as_mut() {
+            **dragged_marker = None;
+
+            if marker_drag.is_some() && response.dragged() {
+                ui.output_mut(|output| output.cursor_icon = CursorIcon::Grabbing);
+            } else if hovered_marker_index.is_some() {
+                ui.output_mut(|output| output.cursor_icon = CursorIcon::Grab);
+            }
+        }
+
+        if bug_drag.is_some() && response.dragged() {
+            ui.output_mut(|output| output.cursor_icon = CursorIcon::Grabbing);
+        } else if hovered_bug_index.is_some() {
+            ui.output_mut(|output| output.cursor_icon = CursorIcon::Grab);
+        }
+
         let constrain_value = |mut value| {
             if self.wrap == WrapMode::Signed {
                 // Animations require inclusive normalization bounds (-PI..=PI)
@@ -216,14 +448,49 @@ impl<'a> Widget for LinearCompass<'a> {
             value
         };
 
-        if response.dragged() {
+        if let (true, Some(bug_index)) = (response.dragged(), bug_drag) {
+            let bug_angle = normalized_angle_unsigned_excl(self.map_screen_to_angle(
+                rect,
+                current_value,
+                response.interact_pointer_pos().unwrap(),
+            ));
+
+            self.bugs[bug_index].set(bug_angle);
+            bug_angles[bug_index] = bug_angle;
+
+            response.mark_changed();
+        } else if let (true, Some((marker_index, grab_offset))) = (response.dragged(), marker_drag)
+        {
+            let angle = self.map_screen_to_angle(
+                rect,
+                current_value,
+                response.interact_pointer_pos().unwrap(),
+            ) - grab_offset;
+
+            if let Some(dragged_marker) = self.dragged_marker.as_mut() {
+                **dragged_marker = Some(CompassMarkerDrag {
+                    index: marker_index,
+                    id: self.markers[marker_index].id_or_index(marker_index),
+                    angle: normalized_angle_unsigned_excl(angle),
+                    distance: self.markers[marker_index].distance,
+                });
+            }
+
+            response.mark_changed();
+        } else if response.dragged() {
             let new_value = get(&mut self.get_set_value)
-                - response.drag_delta().x / rect.width() * (self.spread * self.winding.to_float());
+                + self
+                    .scale(rect, current_value)
+                    .drag_delta_to_value(response.drag_delta());
             set(&mut self.get_set_value, constrain_value(new_value));
             response.mark_changed();
         }
 
-        if response.drag_released() {
+        if response.drag_released() && bug_drag.is_some() {
+            ui.memory_mut(|memory| memory.data.remove::<usize>(bug_drag_id));
+        } else if response.drag_released() && marker_drag.is_some() {
+            ui.memory_mut(|memory| memory.data.remove::<(usize, f32)>(marker_drag_id));
+        } else if response.drag_released() {
             if self.animated {
                 child_ui.ctx().clear_animations();
                 child_ui.ctx().animate_value_with_time(
@@ -250,6 +517,10 @@ impl<'a> Widget for LinearCompass<'a> {
 
         if child_ui.is_rect_visible(rect) {
             let visuals = *child_ui.style().interact(&response);
+            let style = self
+                .style
+                .clone()
+                .unwrap_or_else(|| CompassStyle::interactive_system_style(&child_ui, &response));
 
             let value = if self.animated && !response.dragged() {
                 child_ui.ctx().animate_value_with_time(
@@ -261,25 +532,67 @@ impl<'a> Widget for LinearCompass<'a> {
                 get(&mut self.get_set_value)
             };
 
-            let map_angle_to_screen = |angle: f32| {
-                rect.center().x
-                    - (value - angle) * (rect.width() / (self.spread * self.winding.to_float()))
-            };
+            let map_angle_to_screen = |angle: f32| self.map_angle_to_screen(rect, value, angle);
 
             // Draw the widget background without clipping to avoid truncated outline strokes
             ui.painter().rect(
                 rect,
                 visuals.rounding,
-                ui.style().visuals.extreme_bg_color,
-                ui.style().visuals.noninteractive().fg_stroke,
+                style.background_color,
+                style.outline_stroke,
             );
 
+            {
+                // Sectors may start one turn before the visible range and still reach into it
+                let start_tau = ((value - (self.spread.abs() / 2.0)) / TAU).floor() as isize - 1;
+                let end_tau = ((value + (self.spread.abs() / 2.0)) / TAU).ceil() as isize;
+
+                for tau in start_tau..=end_tau {
+                    for sector in self.sectors {
+                        let turn_offset = tau as f32 * TAU;
+
+                        let sector_rect = Rect::from_two_pos(
+                            pos2(map_angle_to_screen(turn_offset + sector.start), rect.top()),
+                            pos2(
+                                map_angle_to_screen(turn_offset + sector.start + sector.span),
+                                rect.bottom(),
+                            ),
+                        );
+
+                        if !sector_rect.intersects(rect) {
+                            continue;
+                        }
+
+                        child_ui.painter().rect_filled(
+                            sector_rect,
+                            0.0,
+                            sector.fill_color(&child_ui),
+                        );
+
+                        if let Some(sector_label) = &sector.label {
+                            child_ui.painter().text(
+                                pos2(
+                                    map_angle_to_screen(turn_offset + sector.middle()),
+                                    rect.top() + self.height * 0.125,
+                                ),
+                                Align2::CENTER_CENTER,
+                                sector_label,
+                                FontId::new(self.height / 5.0, style.label_font_family.clone()),
+                                style.text_color,
+                            );
+                        }
+                    }
+                }
+            }
+
             {
                 let paint_marker = |child_ui: &mut Ui,
                                     angle: f32,
                                     label: Option<&str>,
                                     text_color: Color32,
                                     shape: CompassMarkerShape,
+                                    icon: Option<TextureId>,
+                                    selected: bool,
                                     fill: Color32,
                                     stroke: Stroke| {
                     // Early exit when the marker is outside of the bounds of the widget,
@@ -295,13 +608,16 @@ impl<'a> Widget for LinearCompass<'a> {
 
                     // Draw marker shape
                     {
-                        let marker_rect = {
-                            let center =
-                                pos2(map_angle_to_screen(angle), rect.top() + self.height * 0.375);
-                            Rect::from_center_size(center, Vec2::splat(self.height * 0.25))
-                        };
-
-                        shape.paint(child_ui, marker_rect, fill, stroke);
+                        let marker_rect = self.marker_rect(rect, value, angle);
+                        shape.paint_or_icon(child_ui, marker_rect, icon, fill, stroke);
+
+                        if selected {
+                            child_ui.painter().rect_stroke(
+                                marker_rect.expand(2.0),
+                                2.0,
+                                child_ui.style().visuals.selection.stroke,
+                            );
+                        }
                     }
 
                     // Draw marker text label
@@ -314,7 +630,7 @@ impl<'a> Widget for LinearCompass<'a> {
                                 label_center,
                                 Align2::CENTER_CENTER,
                                 label,
-                                FontId::new(self.height / 4.0, FontFamily::Proportional),
+                                FontId::new(self.height / 4.0, style.label_font_family.clone()),
                                 text_color,
                             );
                         }
@@ -325,17 +641,18 @@ impl<'a> Widget for LinearCompass<'a> {
                 let end_tau = ((value + (self.spread.abs() / 2.0)) / TAU).ceil() as isize;
 
                 for tau in start_tau..=end_tau {
-                    for marker in self.markers.iter() {
+                    for (marker_index, marker) in self.markers.iter().enumerate() {
+                        let marker_highlighted = (hovered_marker_index == Some(marker_index))
+                            || marker_drag
+                                .map_or(false, |(dragged_index, _)| dragged_index == marker_index);
+
                         let marker_color = marker
                             .color
                             .unwrap_or_else(|| self.default_marker_color.color(&child_ui, marker));
 
                         let marker_stroke = {
-                            let stroke_color = tint_color_towards(
-                                marker_color,
-                                child_ui.style().visuals.text_color(),
-                            );
-                            Stroke::new(1.0, stroke_color)
+                            let stroke_color = tint_color_towards(marker_color, style.text_color);
+                            Stroke::new(if marker_highlighted { 2.0 } else { 1.0 }, stroke_color)
                         };
 
                         let marker_shape = marker.shape.unwrap_or(self.default_marker_shape);
@@ -343,9 +660,11 @@ impl<'a> Widget for LinearCompass<'a> {
                         paint_marker(
                             &mut child_ui,
                             (tau as f32 * TAU) + marker.angle,
-                            marker.label,
+                            marker.label.as_deref(),
                             marker_color,
                             marker_shape,
+                            marker.icon,
+                            selected_marker_id == Some(marker.id_or_index(marker_index)),
                             marker_color,
                             marker_stroke,
                         );
@@ -357,26 +676,24 @@ impl<'a> Widget for LinearCompass<'a> {
                         &mut child_ui,
                         value,
                         Some(&format!("{:.0}°", value.to_degrees())),
-                        visuals.text_color(),
+                        style.text_color,
                         CompassMarkerShape::DownArrow,
-                        visuals.bg_fill,
-                        visuals.fg_stroke,
+                        None,
+                        false,
+                        style.cursor_color,
+                        style.cursor_stroke,
                     );
                 }
             }
 
             {
-                let round_bounds_to = 10.0;
-
-                let start_degrees =
-                    (((value - (self.spread.abs() / 2.0)).to_degrees() / round_bounds_to).floor()
-                        * round_bounds_to) as isize;
+                let tick_step_degrees = 5;
 
-                let end_degrees =
-                    (((value + (self.spread.abs() / 2.0)).to_degrees() / round_bounds_to).ceil()
-                        * round_bounds_to) as isize;
-
-                for degree in (start_degrees..=end_degrees).step_by(5) {
+                for tick_index in self
+                    .scale(rect, value)
+                    .tick_indices((tick_step_degrees as f32).to_radians())
+                {
+                    let degree = tick_index * tick_step_degrees;
                     let tick_x = map_angle_to_screen((degree as f32).to_radians());
 
                     let tick_position = pos2(tick_x, rect.top() + (self.height * 0.5));
@@ -400,7 +717,11 @@ impl<'a> Widget for LinearCompass<'a> {
                     if self.show_ticks || (self.show_axes && is_axis_tick) {
                         child_ui.painter().line_segment(
                             [tick_position, tick_position + tick_size * tick_scale],
-                            child_ui.style().visuals.noninteractive().fg_stroke,
+                            if is_axis_tick {
+                                style.axis_stroke
+                            } else {
+                                style.tick_stroke
+                            },
                         );
                     }
 
@@ -410,8 +731,8 @@ impl<'a> Widget for LinearCompass<'a> {
                                 tick_label_center,
                                 Align2::CENTER_CENTER,
                                 tick_label,
-                                FontId::new(self.height / 4.0, FontFamily::Proportional),
-                                child_ui.style().visuals.text_color(),
+                                FontId::new(self.height / 4.0, style.label_font_family.clone()),
+                                style.text_color,
                             );
                         }
                     }
@@ -424,7 +745,7 @@ impl<'a> Widget for LinearCompass<'a> {
 
                     child_ui.painter().line_segment(
                         [pos2(stop_x, rect.top()), pos2(stop_x, rect.bottom())],
-                        child_ui.style().visuals.noninteractive().fg_stroke,
+                        style.axis_stroke,
                     );
                 };
 
@@ -436,6 +757,26 @@ impl<'a> Widget for LinearCompass<'a> {
                     paint_stop(max);
                 }
             }
+
+            for (bug_index, (bug, &bug_angle)) in self.bugs.iter().zip(&bug_angles).enumerate() {
+                let bug_highlighted =
+                    (hovered_bug_index == Some(bug_index)) || (bug_drag == Some(bug_index));
+
+                bug.paint(
+                    &child_ui,
+                    self.bug_outline(rect, value, bug, bug_angle),
+                    style.background_color,
+                    bug_highlighted,
+                );
+            }
+        }
+
+        if let (true, false, Some(marker_index)) = (
+            self.show_marker_tooltips,
+            response.dragged(),
+            hovered_marker_index,
+        ) {
+            self.markers[marker_index].show_tooltip(ui, response.id.with("marker_tooltip"));
         }
 
         response
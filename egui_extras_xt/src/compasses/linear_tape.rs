@@ -0,0 +1,646 @@
+use std::collections::VecDeque;
+
+use egui::{self, CursorIcon, Id, Pos2, Response, Sense, Ui, Widget};
+use emath::{Align2, Rect, Vec2};
+use epaint::{FontId, Shape, Stroke};
+
+use crate::common::LinearOrientation;
+use crate::compasses::tape_scale::TapeScale;
+use crate::compasses::{CompassBug, CompassStyle, CompassStylePreset};
+
+// ----------------------------------------------------------------------------
+
+/// Combined into one function (rather than two) to make it easier
+/// for the borrow checker.
+type GetSetValue<'a> = Box<dyn 'a + FnMut(Option<f32>) -> f32>;
+
+fn get(get_set_value: &mut GetSetValue<'_>) -> f32 {
+    (get_set_value)(None)
+}
+
+fn set(get_set_value: &mut GetSetValue<'_>, value: f32) {
+    (get_set_value)(Some(value));
+}
+
+// ----------------------------------------------------------------------------
+
+/// Seconds of values kept in memory for estimating the trend.
+const TREND_HISTORY_LENGTH: f64 = 1.0;
+const MAX_TREND_SAMPLES: usize = 32;
+
+type TrendSamples = VecDeque<(f64, f32)>;
+
+// ----------------------------------------------------------------------------
+
+/// Non-wrapping value tape, like the altitude and airspeed tapes of a primary
+/// flight display. The scale scrolls past a readout box in the middle of the
+/// widget.
+#[must_use = "You should put this widget in an ui with `ui.add(widget);`"]
+pub struct LinearTape<'a> {
+    get_set_value: GetSetValue<'a>,
+    interactive: bool,
+    orientation: LinearOrientation,
+    width: f32,
+    height: f32,
+    spread: f32,
+    inverted: bool,
+    mirrored: bool,
+    major_tick_step: f32,
+    minor_tick_step: f32,
+    min: Option<f32>,
+    max: Option<f32>,
+    animated: bool,
+    show_ticks: bool,
+    show_labels: bool,
+    show_readout: bool,
+    readout_rolling_digits: usize,
+    trend_vector: Option<f32>,
+    style: Option<CompassStyle>,
+    bugs: Vec<CompassBug<'a>>,
+}
+
+impl<'a> LinearTape<'a> {
+    pub fn new(value: &'a mut f32) -> Self {
+        Self::from_get_set(move |v: Option<f32>| {
+            if let Some(v) = v {
+                *value = v;
+            }
+            *value
+        })
+    }
+
+    pub fn from_get_set(get_set_value: impl 'a + FnMut(Option<f32>) -> f32) -> Self {
+        Self {
+            get_set_value: Box::new(get_set_value),
+            interactive: true,
+            orientation: LinearOrientation::Vertical,
+            width: 64.0,
+            height: 256.0,
+            spread: 100.0,
+            inverted: false,
+            mirrored: false,
+            major_tick_step: 20.0,
+            minor_tick_step: 5.0,
+            min: None,
+            max: None,
+            animated: false,
+            show_ticks: true,
+            show_labels: true,
+            show_readout: true,
+            readout_rolling_digits: 1,
+            trend_vector: None,
+            style: None,
+            bugs: Vec::new(),
+        }
+    }
+
+    pub fn interactive(mut self, interactive: bool) -> Self {
+        self.interactive = interactive;
+        self
+    }
+
+    pub fn orientation(mut self, orientation: LinearOrientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    pub fn width(mut self, width: impl Into<f32>) -> Self {
+        self.width = width.into();
+        self
+    }
+
+    pub fn height(mut self, height: impl Into<f32>) -> Self {
+        self.height = height.into();
+        self
+    }
+
+    /// Range of values visible along the tape.
+    pub fn spread(mut self, spread: impl Into<f32>) -> Self {
+        let spread = spread.into();
+        assert!(spread > 0.0);
+        self.spread = spread;
+        self
+    }
+
+    /// Values increase downwards or to the left, eg. for depth.
+    pub fn inverted(mut self, inverted: bool) -> Self {
+        self.inverted = inverted;
+        self
+    }
+
+    /// Puts the ticks and bugs on the right or bottom edge instead of the
+    /// left or top edge.
+    pub fn mirrored(mut self, mirrored: bool) -> Self {
+        self.mirrored = mirrored;
+        self
+    }
+
+    /// Labeled ticks, should be a multiple of the minor tick step.
+    pub fn major_tick_step(mut self, major_tick_step: impl Into<f32>) -> Self {
+        let major_tick_step = major_tick_step.into();
+        assert!(major_tick_step > 0.0);
+        self.major_tick_step = major_tick_step;
+        self
+    }
+
+    pub fn minor_tick_step(mut self, minor_tick_step: impl Into<f32>) -> Self {
+        let minor_tick_step = minor_tick_step.into();
+        assert!(minor_tick_step > 0.0);
+        self.minor_tick_step = minor_tick_step;
+        self
+    }
+
+    pub fn min(mut self, min: Option<f32>) -> Self {
+        self.min = min;
+        self
+    }
+
+    pub fn max(mut self, max: Option<f32>) -> Self {
+        self.max = max;
+        self
+    }
+
+    pub fn animated(mut self, animated: bool) -> Self {
+        self.animated = animated;
+        self
+    }
+
+    pub fn show_ticks(mut self, show_ticks: bool) -> Self {
+        self.show_ticks = show_ticks;
+        self
+    }
+
+    pub fn show_labels(mut self, show_labels: bool) -> Self {
+        self.show_labels = show_labels;
+        self
+    }
+
+    pub fn show_readout(mut self, show_readout: bool) -> Self {
+        self.show_readout = show_readout;
+        self
+    }
+
+    /// Number of the lowest digits of the readout rolling like a drum
+    /// counter, the rest of the digits change in steps. Between 1 and 18.
+    pub fn readout_rolling_digits(mut self, readout_rolling_digits: usize) -> Self {
+        assert!((1..=18).contains(&readout_rolling_digits));
+        self.readout_rolling_digits = readout_rolling_digits;
+        self
+    }
+
+    /// Draws an arrow pointing to where the value will be after the given
+    /// number of seconds, estimated from its recent changes.
+    pub fn trend_vector(mut self, trend_vector: Option<f32>) -> Self {
+        self.trend_vector = trend_vector;
+        self
+    }
+
+    /// Uses [`CompassStyle::system_style`] when `None`, with the outline and
+    /// the cursor highlighted on interaction.
+    pub fn style(mut self, style: Option<CompassStyle>) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn style_preset(mut self, preset: CompassStylePreset) -> Self {
+        self.style = Some(preset.style());
+        self
+    }
+
+    /// Adds a target value indicator on the tick edge, bound to its own
+    /// value. Interactive bugs can be dragged along the tape.
+    pub fn bug(mut self, bug: CompassBug<'a>) -> Self {
+        self.bugs.push(bug);
+        self
+    }
+
+    fn scale(&self, rect: Rect, value: f32) -> TapeScale {
+        TapeScale::new(
+            rect,
+            self.orientation,
+            value,
+            if self.inverted {
+                -self.spread
+            } else {
+                self.spread
+            },
+        )
+    }
+
+    /// Size of the widget across the tape.
+    fn thickness(&self) -> f32 {
+        match self.orientation {
+            LinearOrientation::Horizontal => self.height,
+            LinearOrientation::Vertical => self.width,
+        }
+    }
+
+    /// Unit vector pointing from the tick edge into the tape.
+    fn inward(&self) -> Vec2 {
+        match (self.orientation, self.mirrored) {
+            (LinearOrientation::Horizontal, false) => Vec2::DOWN,
+            (LinearOrientation::Horizontal, true) => Vec2::UP,
+            (LinearOrientation::Vertical, false) => Vec2::RIGHT,
+            (LinearOrientation::Vertical, true) => Vec2::LEFT,
+        }
+    }
+
+    /// Screen position at the coordinate `along` the tape, `depth` screen
+    /// units away from the tick edge.
+    fn edge_point(&self, scale: &TapeScale, along: f32, depth: f32) -> Pos2 {
+        let edge = if self.mirrored { 1.0 } else { 0.0 };
+        scale.point(along, edge) + self.inward() * depth
+    }
+
+    /// Screen coordinates along the tape between the ends of the widget.
+    fn along_bounds(&self, rect: Rect) -> (f32, f32) {
+        match self.orientation {
+            LinearOrientation::Horizontal => (rect.left(), rect.right()),
+            LinearOrientation::Vertical => (rect.top(), rect.bottom()),
+        }
+    }
+
+    fn constrain_value(&self, mut value: f32) -> f32 {
+        if let Some(min) = self.min {
+            value = value.max(min);
+        }
+
+        if let Some(max) = self.max {
+            value = value.min(max);
+        }
+
+        value
+    }
+
+    /// Screen outline of a bug, pegged to the ends of the widget when the bug
+    /// is outside of the visible range.
+    fn bug_outline(&self, rect: Rect, value: f32, bug: &CompassBug, bug_value: f32) -> Vec<Pos2> {
+        let scale = self.scale(rect, value);
+        let bug_size = self.thickness() * 0.25;
+        let (along_start, along_end) = self.along_bounds(rect);
+
+        let bug_along = scale
+            .value_to_screen(bug_value)
+            .clamp(along_start + bug_size / 2.0, along_end - bug_size / 2.0);
+
+        let bug_origin = self.edge_point(&scale, bug_along, 0.0);
+
+        bug.outline(bug_size, |point| {
+            bug_origin + scale.direction() * point.x + self.inward() * point.y
+        })
+    }
+
+    /// Index of the topmost interactive bug at `pos`.
+    fn bug_at(&self, rect: Rect, value: f32, bug_values: &[f32], pos: Pos2) -> Option<usize> {
+        self.bugs
+            .iter()
+            .zip(bug_values)
+            .rposition(|(bug, &bug_value)| {
+                bug.interactive
+                    && Rect::from_points(&self.bug_outline(rect, value, bug, bug_value))
+                        .expand(2.0)
+                        .contains(pos)
+            })
+    }
+
+    /// Records the current value into the samples kept in memory, dropping
+    /// samples older than the history length. Returns the estimated rate of
+    /// change per second and whether the value changed within that time.
+    fn update_trend(&self, ui: &Ui, trend_id: Id, value: f32) -> (f32, bool) {
+        let now = ui.input(|input| input.time);
+        let sample_interval = TREND_HISTORY_LENGTH / MAX_TREND_SAMPLES as f64;
+
+        let mut samples = ui
+            .memory_mut(|memory| memory.data.get_temp::<TrendSamples>(trend_id))
+            .unwrap_or_default();
+
+        while samples
+            .front()
+            .map_or(false, |&(time, _)| now - time > TREND_HISTORY_LENGTH)
+        {
+            samples.pop_front();
+        }
+
+        if !samples
+            .back()
+            .map_or(false, |&(time, _)| now - time < sample_interval)
+        {
+            samples.push_back((now, value));
+        }
+
+        let value_changing = samples
+            .iter()
+            .any(|&(_, sample_value)| sample_value != value);
+
+        // Estimate the rate over the whole history to smooth out values updated
+        // less often than every frame
+        let rate = match samples.front() {
+            Some(&(time, sample_value)) if now > time => {
+                (value - sample_value) / (now - time) as f32
+            }
+            _ => 0.0,
+        };
+
+        ui.memory_mut(|memory| memory.data.insert_temp(trend_id, samples));
+
+        (rate, value_changing)
+    }
+}
+
+impl<'a> Widget for LinearTape<'a> {
+    fn ui(mut self, ui: &mut Ui) -> Response {
+        let desired_size = egui::vec2(self.width, self.height);
+
+        let (rect, mut response) = ui.allocate_exact_size(
+            desired_size,
+            if self.interactive {
+                Sense::click_and_drag()
+            } else {
+                Sense::hover()
+            },
+        );
+
+        let mut child_ui = ui.child_ui(rect, *ui.layout());
+        child_ui.set_clip_rect(child_ui.clip_rect().intersect(rect));
+
+        let current_value = get(&mut self.get_set_value);
+        let bug_drag_id = response.id.with("bug_drag");
+
+        let mut bug_values = self
+            .bugs
+            .iter_mut()
+            .map(|bug| bug.get())
+            .collect::<Vec<_>>();
+
+        let hovered_bug_index = response
+            .hover_pos()
+            .and_then(|pos| self.bug_at(rect, current_value, &bug_values, pos));
+
+        if response.drag_started() {
+            let press_origin = ui.input(|input| input.pointer.press_origin().unwrap());
+
+            if let Some(bug_index) = self.bug_at(rect, current_value, &bug_values, press_origin) {
+                ui.memory_mut(|memory| memory.data.insert_temp(bug_drag_id, bug_index));
+            }
+        }
+
+        let bug_drag = ui.memory_mut(|memory| memory.data.get_temp::<usize>(bug_drag_id));
+
+        if bug_drag.is_some() && response.dragged() {
+            ui.output_mut(|output| output.cursor_icon = CursorIcon::Grabbing);
+        } else if hovered_bug_index.is_some() {
+            ui.output_mut(|output| output.cursor_icon = CursorIcon::Grab);
+        }
+
+        if let (true, Some(bug_index)) = (response.dragged(), bug_drag) {
+            let bug_value = self.constrain_value(
+                self.scale(rect, current_value)
+                    .screen_to_value(response.interact_pointer_pos().unwrap()),
+            );
+
+            self.bugs[bug_index].set(bug_value);
+            bug_values[bug_index] = bug_value;
+
+            response.mark_changed();
+        } else if response.dragged() {
+            let new_value = get(&mut self.get_set_value)
+                + self
+                    .scale(rect, current_value)
+                    .drag_delta_to_value(response.drag_delta());
+            let new_value = self.constrain_value(new_value);
+            set(&mut self.get_set_value, new_value);
+            response.mark_changed();
+        }
+
+        if response.drag_released() && bug_drag.is_some() {
+            ui.memory_mut(|memory| memory.data.remove::<usize>(bug_drag_id));
+        } else if response.drag_released() && self.animated {
+            child_ui.ctx().clear_animations();
+            child_ui
+                .ctx()
+                .animate_value_with_time(response.id, get(&mut self.get_set_value), 0.1);
+        }
+
+        let trend_rate = if self.trend_vector.is_some() {
+            let new_value = get(&mut self.get_set_value);
+            let (trend_rate, value_changing) =
+                self.update_trend(ui, response.id.with("trend"), new_value);
+
+            if value_changing {
+                ui.ctx().request_repaint();
+            }
+
+            trend_rate
+        } else {
+            0.0
+        };
+
+        if child_ui.is_rect_visible(rect) {
+            let visuals = *child_ui.style().interact(&response);
+            let style = self
+                .style
+                .clone()
+                .unwrap_or_else(|| CompassStyle::interactive_system_style(&child_ui, &response));
+
+            let value = if self.animated && !response.dragged() {
+                child_ui.ctx().animate_value_with_time(
+                    response.id,
+                    get(&mut self.get_set_value),
+                    child_ui.style().animation_time,
+                )
+            } else {
+                get(&mut self.get_set_value)
+            };
+
+            let scale = self.scale(rect, value);
+            let thickness = self.thickness();
+            let (along_start, along_end) = self.along_bounds(rect);
+
+            // Draw the widget background without clipping to avoid truncated outline strokes
+            ui.painter().rect(
+                rect,
+                visuals.rounding,
+                style.background_color,
+                style.outline_stroke,
+            );
+
+            {
+                let major_tick_every =
+                    ((self.major_tick_step / self.minor_tick_step).round() as isize).max(1);
+
+                for tick_index in scale.tick_indices(self.minor_tick_step) {
+                    let tick_value = tick_index as f32 * self.minor_tick_step;
+                    let tick_along = scale.value_to_screen(tick_value);
+                    let is_major_tick = tick_index.rem_euclid(major_tick_every) == 0;
+
+                    if self.show_ticks {
+                        let tick_length = thickness * if is_major_tick { 0.3 } else { 0.15 };
+
+                        child_ui.painter().line_segment(
+                            [
+                                self.edge_point(&scale, tick_along, 0.0),
+                                self.edge_point(&scale, tick_along, tick_length),
+                            ],
+                            if is_major_tick {
+                                style.axis_stroke
+                            } else {
+                                style.tick_stroke
+                            },
+                        );
+                    }
+
+                    if self.show_labels && is_major_tick {
+                        child_ui.painter().text(
+                            self.edge_point(&scale, tick_along, thickness * 0.65),
+                            Align2::CENTER_CENTER,
+                            format!("{tick_value:.0}"),
+                            FontId::new(thickness * 0.2, style.label_font_family.clone()),
+                            style.text_color,
+                        );
+                    }
+                }
+            }
+
+            {
+                let paint_stop = |stop_value: f32| {
+                    let stop_along = scale.value_to_screen(stop_value);
+
+                    child_ui.painter().line_segment(
+                        [
+                            self.edge_point(&scale, stop_along, 0.0),
+                            self.edge_point(&scale, stop_along, thickness),
+                        ],
+                        style.axis_stroke,
+                    );
+                };
+
+                if let Some(min) = self.min {
+                    paint_stop(min);
+                }
+
+                if let Some(max) = self.max {
+                    paint_stop(max);
+                }
+            }
+
+            if let Some(trend_vector) = self.trend_vector {
+                let trend_depth = thickness * 0.35;
+                let predicted_along = scale
+                    .value_to_screen(value + trend_rate * trend_vector)
+                    .clamp(along_start, along_end);
+
+                let from = self.edge_point(&scale, scale.value_to_screen(value), trend_depth);
+                let to = self.edge_point(&scale, predicted_along, trend_depth);
+
+                if from.distance(to) >= 1.0 {
+                    child_ui
+                        .painter()
+                        .arrow(from, to - from, Stroke::new(2.0, style.cursor_color));
+                }
+            }
+
+            if self.show_readout {
+                let font_id = FontId::monospace(thickness * 0.25);
+                let digit_width = child_ui.fonts(|fonts| fonts.glyph_width(&font_id, '0'));
+                let line_height = font_id.size * 1.2;
+
+                // Split the magnitude into the digits changing in steps and the rolling digits
+                let rolling_modulus = 10i64.pow(self.readout_rolling_digits as u32);
+                let rolling_value = value.abs().rem_euclid(rolling_modulus as f32);
+                let stepped_value =
+                    ((value.abs() - rolling_value) / rolling_modulus as f32).round() as i64;
+
+                let stepped_text = format!(
+                    "{}{}",
+                    if value < 0.0 { "-" } else { "" },
+                    if stepped_value > 0 {
+                        stepped_value.to_string()
+                    } else {
+                        String::new()
+                    },
+                );
+
+                let text_width = digit_width
+                    * (stepped_text.chars().count() + self.readout_rolling_digits) as f32;
+
+                let readout_size = Vec2::new(text_width + 8.0, line_height * 1.25);
+
+                // Half extents of the readout box along the tape and across it
+                let along = scale.direction().abs();
+                let inward = self.inward();
+                let half_along = along.dot(readout_size) / 2.0;
+                let half_across = inward.abs().dot(readout_size) / 2.0;
+
+                let readout_center = self.edge_point(
+                    &scale,
+                    (along_start + along_end) / 2.0,
+                    thickness - half_across - 2.0,
+                );
+
+                let readout_pointer = readout_center - inward * (half_across + thickness * 0.1);
+
+                child_ui.painter().add(Shape::convex_polygon(
+                    vec![
+                        readout_center + inward * half_across + along * half_along,
+                        readout_center - inward * half_across + along * half_along,
+                        readout_pointer,
+                        readout_center - inward * half_across - along * half_along,
+                        readout_center + inward * half_across - along * half_along,
+                    ],
+                    style.background_color,
+                    style.cursor_stroke,
+                ));
+
+                let text_left = readout_center.x - text_width / 2.0;
+
+                child_ui.painter().text(
+                    Pos2::new(text_left, readout_center.y),
+                    Align2::LEFT_CENTER,
+                    &stepped_text,
+                    font_id.clone(),
+                    style.text_color,
+                );
+
+                // The drum always rolls vertically to keep the digits readable
+                let drum_painter = child_ui.painter().with_clip_rect(
+                    Rect::from_center_size(readout_center, readout_size)
+                        .shrink(1.0)
+                        .intersect(child_ui.clip_rect()),
+                );
+
+                let drum_left = text_left + digit_width * stepped_text.chars().count() as f32;
+                let drum_base = rolling_value.floor() as i64;
+                let drum_offset = rolling_value.fract();
+
+                for drum_step in 0..=1 {
+                    let drum_digits = (drum_base + drum_step).rem_euclid(rolling_modulus);
+
+                    drum_painter.text(
+                        Pos2::new(
+                            drum_left,
+                            readout_center.y - (drum_step as f32 - drum_offset) * line_height,
+                        ),
+                        Align2::LEFT_CENTER,
+                        format!("{drum_digits:0width$}", width = self.readout_rolling_digits),
+                        font_id.clone(),
+                        style.text_color,
+                    );
+                }
+            }
+
+            for (bug_index, (bug, &bug_value)) in self.bugs.iter().zip(&bug_values).enumerate() {
+                let bug_highlighted =
+                    (hovered_bug_index == Some(bug_index)) || (bug_drag == Some(bug_index));
+
+                bug.paint(
+                    &child_ui,
+                    self.bug_outline(rect, value, bug, bug_value),
+                    style.background_color,
+                    bug_highlighted,
+                );
+            }
+        }
+
+        response
+    }
+}
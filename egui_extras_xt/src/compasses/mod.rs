@@ -1,9 +1,25 @@
+mod attitude_indicator;
 mod compass_axis_labels;
+mod compass_bug;
 mod compass_marker;
+mod compass_sector;
+mod compass_style;
 mod linear_compass;
+mod linear_tape;
 mod polar_compass;
+mod tape_scale;
 
+pub use crate::display_style::{DisplayStyle, DisplayStylePreset};
+pub use attitude_indicator::AttitudeIndicator;
 pub use compass_axis_labels::CompassAxisLabels;
-pub use compass_marker::{CompassMarker, CompassMarkerShape, DefaultCompassMarkerColor};
+pub use compass_bug::{CompassBug, CompassBugKind};
+pub use compass_marker::{
+    CompassMarker, CompassMarkerDrag, CompassMarkerShape, DefaultCompassMarkerColor,
+};
+pub use compass_sector::CompassSector;
+pub use compass_style::{CompassStyle, CompassStylePreset};
 pub use linear_compass::LinearCompass;
-pub use polar_compass::{PolarCompass, PolarCompassOverflow};
+pub use linear_tape::LinearTape;
+pub use polar_compass::{
+    PolarCompass, PolarCompassLabelLayout, PolarCompassMode, PolarCompassOverflow,
+};
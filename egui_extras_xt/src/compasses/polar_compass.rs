@@ -1,19 +1,26 @@
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::f32::consts::TAU;
 
 use ecolor::tint_color_towards;
 use egui::{
-    lerp, Align2, FontFamily, FontId, Pos2, Rect, Response, Sense, Shape, Stroke, Ui, Vec2, Widget,
+    lerp, show_tooltip_at_pointer, vec2, Align2, CursorIcon, FontId, Id, Pos2, Rect, Response,
+    Sense, Shape, Stroke, Ui, Vec2, Widget,
 };
-use emath::normalized_angle;
+use emath::{normalized_angle, Rot2};
+use epaint::Mesh;
 
+use itertools::Itertools;
 use strum::{Display, EnumIter};
 
 use crate::common::{
-    snap_wrap_constrain_angle, Orientation, RotatedText, SymLog, Winding, WrapMode,
+    normalized_angle_unsigned_excl, snap_wrap_constrain_angle, Orientation, RotatedText, SymLog,
+    WidgetShape, Winding, WrapMode,
 };
 use crate::compasses::{
-    CompassAxisLabels, CompassMarker, CompassMarkerShape, DefaultCompassMarkerColor,
+    CompassAxisLabels, CompassBug, CompassMarker, CompassMarkerDrag, CompassMarkerShape,
+    CompassSector, CompassStyle, CompassStylePreset, DefaultCompassMarkerColor,
 };
+use crate::display_style::{DisplayStyle, DisplayStylePreset};
 
 // ----------------------------------------------------------------------------
 
@@ -42,12 +49,62 @@ pub enum PolarCompassOverflow {
 
 // ----------------------------------------------------------------------------
 
+#[derive(Clone, Copy, Debug, Display, EnumIter, Eq, PartialEq)]
+pub enum PolarCompassLabelLayout {
+    /// Labels are always drawn below their markers, even when overlapping.
+    #[strum(to_string = "Overlap")]
+    Overlap,
+
+    /// Overlapping labels are moved to the first free side of their marker.
+    #[strum(to_string = "Offset")]
+    Offset,
+
+    /// Overlapping labels are stacked below their marker.
+    #[strum(to_string = "Stack")]
+    Stack,
+
+    /// Overlapping labels are pushed outwards and connected to their marker.
+    #[strum(to_string = "Leader lines")]
+    LeaderLines,
+}
+
+// ----------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, Display, EnumIter, Eq, PartialEq)]
+pub enum PolarCompassMode {
+    /// The dial rotates with the value, keeping the heading fixed.
+    #[strum(to_string = "Heading up")]
+    HeadingUp,
+
+    /// The dial is fixed with north up, the cursor rotates with the value.
+    #[strum(to_string = "North up")]
+    NorthUp,
+}
+
+// ----------------------------------------------------------------------------
+
+/// Position of a marker at some point in time.
+#[derive(Clone, Copy, Debug)]
+struct MarkerTrailSample {
+    time: f64,
+    angle: f32,
+    distance: f32,
+}
+
+/// Recent positions of the markers keyed by their IDs, oldest first.
+type MarkerTrails = HashMap<Id, VecDeque<MarkerTrailSample>>;
+
+const MAX_MARKER_TRAIL_SAMPLES: usize = 32;
+
+// ----------------------------------------------------------------------------
+
 #[must_use = "You should put this widget in an ui with `ui.add(widget);`"]
 pub struct PolarCompass<'a> {
     get_set_value: GetSetValue<'a>,
     interactive: bool,
     orientation: Orientation,
     winding: Winding,
+    mode: PolarCompassMode,
     overflow: PolarCompassOverflow,
     diameter: f32,
     wrap: WrapMode,
@@ -68,9 +125,24 @@ pub struct PolarCompass<'a> {
     show_cursor: bool,
     show_marker_labels: bool,
     show_marker_lines: bool,
-    markers: &'a [CompassMarker<'a>],
+    show_marker_tooltips: bool,
+    label_layout: PolarCompassLabelLayout,
+    marker_clustering: Option<f32>,
+    marker_trail_length: Option<f32>,
+    marker_velocity_vectors: Option<f32>,
+    radar_sweep: Option<f32>,
+    radar_afterglow: f32,
+    radar_style: DisplayStyle,
+    style: Option<CompassStyle>,
+    markers: &'a [CompassMarker],
+    sectors: &'a [CompassSector],
+    bugs: Vec<CompassBug<'a>>,
     default_marker_color: DefaultCompassMarkerColor,
     default_marker_shape: CompassMarkerShape,
+    hovered_marker: Option<&'a mut Option<Id>>,
+    clicked_marker: Option<&'a mut Option<Id>>,
+    selected_marker: Option<&'a mut Option<Id>>,
+    dragged_marker: Option<&'a mut Option<CompassMarkerDrag>>,
 }
 
 impl<'a> PolarCompass<'a> {
@@ -89,6 +161,7 @@ impl<'a> PolarCompass<'a> {
             interactive: true,
             orientation: Orientation::Top,
             winding: Winding::Clockwise,
+            mode: PolarCompassMode::HeadingUp,
             overflow: PolarCompassOverflow::Saturate,
             diameter: 256.0,
             wrap: WrapMode::Unsigned,
@@ -109,13 +182,28 @@ impl<'a> PolarCompass<'a> {
             show_cursor: true,
             show_marker_labels: true,
             show_marker_lines: true,
+            show_marker_tooltips: true,
+            label_layout: PolarCompassLabelLayout::Offset,
+            marker_clustering: None,
+            marker_trail_length: None,
+            marker_velocity_vectors: None,
+            radar_sweep: None,
+            radar_afterglow: 0.75,
+            radar_style: DisplayStylePreset::DeLoreanGreen.style(),
+            style: None,
             markers: &[],
+            sectors: &[],
+            bugs: Vec::new(),
             default_marker_color: DefaultCompassMarkerColor::HsvByAngle {
                 hue_phase: 0.0,
                 saturation: 1.0,
                 value: 1.0,
             },
             default_marker_shape: CompassMarkerShape::Square,
+            hovered_marker: None,
+            clicked_marker: None,
+            selected_marker: None,
+            dragged_marker: None,
         }
     }
 
@@ -134,6 +222,11 @@ impl<'a> PolarCompass<'a> {
         self
     }
 
+    pub fn mode(mut self, mode: PolarCompassMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
     pub fn overflow(mut self, overflow: PolarCompassOverflow) -> Self {
         self.overflow = overflow;
         self
@@ -248,11 +341,94 @@ impl<'a> PolarCompass<'a> {
         self
     }
 
-    pub fn markers(mut self, markers: &'a [CompassMarker<'a>]) -> Self {
+    pub fn show_marker_tooltips(mut self, show_marker_tooltips: bool) -> Self {
+        self.show_marker_tooltips = show_marker_tooltips;
+        self
+    }
+
+    pub fn label_layout(mut self, label_layout: PolarCompassLabelLayout) -> Self {
+        self.label_layout = label_layout;
+        self
+    }
+
+    /// Draws markers closer to each other than the given distance in points as
+    /// a single badge showing their count.
+    pub fn marker_clustering(mut self, marker_clustering: Option<f32>) -> Self {
+        self.marker_clustering = marker_clustering;
+        self
+    }
+
+    /// Draws the positions of the markers over the given number of seconds
+    /// as fading trails. The history is kept in memory keyed by the marker
+    /// IDs, markers without IDs are told apart by their indices.
+    pub fn marker_trail_length(mut self, marker_trail_length: Option<f32>) -> Self {
+        self.marker_trail_length = marker_trail_length;
+        self
+    }
+
+    /// Draws arrows pointing to where the markers will be after the given
+    /// number of seconds, following their velocity when set or estimated from
+    /// their recent positions otherwise.
+    pub fn marker_velocity_vectors(mut self, marker_velocity_vectors: Option<f32>) -> Self {
+        self.marker_velocity_vectors = marker_velocity_vectors;
+        self
+    }
+
+    /// Turns the compass into a radar plan-position indicator with a sweep
+    /// line completing a turn in the given number of seconds. Markers light
+    /// up when the sweep passes them and fade out along with its afterglow.
+    pub fn radar_sweep(mut self, radar_sweep: Option<f32>) -> Self {
+        self.radar_sweep = radar_sweep;
+        self
+    }
+
+    /// Length of the sweep afterglow as a fraction of a turn.
+    pub fn radar_afterglow(mut self, radar_afterglow: f32) -> Self {
+        assert!(radar_afterglow > 0.0 && radar_afterglow <= 1.0);
+        self.radar_afterglow = radar_afterglow;
+        self
+    }
+
+    pub fn radar_style(mut self, radar_style: DisplayStyle) -> Self {
+        self.radar_style = radar_style;
+        self
+    }
+
+    pub fn radar_style_preset(mut self, preset: DisplayStylePreset) -> Self {
+        self.radar_style = preset.style();
+        self
+    }
+
+    /// Uses [`CompassStyle::system_style`] when `None`, with the outline and
+    /// the cursor highlighted on interaction.
+    pub fn style(mut self, style: Option<CompassStyle>) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn style_preset(mut self, preset: CompassStylePreset) -> Self {
+        self.style = Some(preset.style());
+        self
+    }
+
+    pub fn markers(mut self, markers: &'a [CompassMarker]) -> Self {
         self.markers = markers;
         self
     }
 
+    /// Shaded sectors drawn under the markers.
+    pub fn sectors(mut self, sectors: &'a [CompassSector]) -> Self {
+        self.sectors = sectors;
+        self
+    }
+
+    /// Adds a bearing indicator on the edge of the dial, bound to its own
+    /// value. Interactive bugs can be dragged around the dial.
+    pub fn bug(mut self, bug: CompassBug<'a>) -> Self {
+        self.bugs.push(bug);
+        self
+    }
+
     pub fn default_marker_color(mut self, default_marker_color: DefaultCompassMarkerColor) -> Self {
         self.default_marker_color = default_marker_color;
         self
@@ -262,6 +438,368 @@ impl<'a> PolarCompass<'a> {
         self.default_marker_shape = default_marker_shape;
         self
     }
+
+    /// ID of the marker under the mouse pointer.
+    pub fn hovered_marker(mut self, hovered_marker: &'a mut Option<Id>) -> Self {
+        self.hovered_marker = Some(hovered_marker);
+        self
+    }
+
+    /// ID of the marker clicked this frame.
+    pub fn clicked_marker(mut self, clicked_marker: &'a mut Option<Id>) -> Self {
+        self.clicked_marker = Some(clicked_marker);
+        self
+    }
+
+    /// ID of the highlighted marker, changed by clicking on a marker or
+    /// cleared by clicking elsewhere on the compass.
+    pub fn selected_marker(mut self, selected_marker: &'a mut Option<Id>) -> Self {
+        self.selected_marker = Some(selected_marker);
+        self
+    }
+
+    /// Makes markers draggable, reporting the new angle and distance of the
+    /// marker being dragged this frame.
+    pub fn dragged_marker(mut self, dragged_marker: &'a mut Option<CompassMarkerDrag>) -> Self {
+        self.dragged_marker = Some(dragged_marker);
+        self
+    }
+
+    fn max_log(&self) -> f32 {
+        (self.max_distance / self.scale_log_mult).symlog(self.scale_log_base)
+    }
+
+    /// Rotation of the dial for the given value.
+    fn view_angle(&self, value: f32) -> f32 {
+        match self.mode {
+            PolarCompassMode::HeadingUp => value,
+            PolarCompassMode::NorthUp => 0.0,
+        }
+    }
+
+    fn angle_to_direction(&self, value: f32, angle: f32) -> Vec2 {
+        self.orientation.rot2()
+            * Vec2::angled((angle - self.view_angle(value)) * self.winding.to_float())
+    }
+
+    /// Screen outline of a bug on the edge of the dial.
+    fn bug_outline(&self, rect: Rect, value: f32, bug: &CompassBug, bug_angle: f32) -> Vec<Pos2> {
+        let direction = self.angle_to_direction(value, bug_angle);
+        let edge = rect.center() + direction * (self.diameter / 2.0);
+
+        bug.outline(self.axis_label_height, |point| {
+            edge + direction.rot90() * point.x - direction * point.y
+        })
+    }
+
+    /// Index of the topmost interactive bug at `pos`.
+    fn bug_at(&self, rect: Rect, value: f32, bug_angles: &[f32], pos: Pos2) -> Option<usize> {
+        self.bugs
+            .iter()
+            .zip(bug_angles)
+            .rposition(|(bug, &bug_angle)| {
+                bug.interactive
+                    && Rect::from_points(&self.bug_outline(rect, value, bug, bug_angle))
+                        .expand(2.0)
+                        .contains(pos)
+            })
+    }
+
+    /// Fraction of the radius at which the given distance is drawn.
+    fn distance_to_t(&self, distance: f32) -> f32 {
+        let distance_log = (distance / self.scale_log_mult).symlog(self.scale_log_base);
+        (distance_log / self.max_log()).clamp(0.0, 1.0)
+    }
+
+    /// Screen position of the given angle and distance, `None` if the
+    /// position is clipped.
+    fn position_to_screen(
+        &self,
+        rect: Rect,
+        value: f32,
+        angle: f32,
+        distance: f32,
+    ) -> Option<Pos2> {
+        if (distance > self.max_distance) && (self.overflow == PolarCompassOverflow::Clip) {
+            return None;
+        }
+
+        Some(
+            rect.center()
+                + self.angle_to_direction(value, angle)
+                    * (self.diameter / 2.0 * self.distance_to_t(distance)),
+        )
+    }
+
+    /// Screen rectangle of a marker, `None` if the marker is clipped.
+    fn marker_rect(&self, rect: Rect, value: f32, marker: &CompassMarker) -> Option<Rect> {
+        let marker_distance = marker.distance.expect("marker has no distance");
+
+        let marker_center = self.position_to_screen(rect, value, marker.angle, marker_distance)?;
+        let marker_size = lerp(
+            self.marker_near_size..=self.marker_far_size,
+            self.distance_to_t(marker_distance),
+        );
+
+        Some(Rect::from_center_size(
+            marker_center,
+            Vec2::splat(marker_size),
+        ))
+    }
+
+    /// Brightness of a marker at the given angle, fading from 1.0 right after
+    /// the radar sweep passes it to 0.0 at the end of the afterglow. Always
+    /// 1.0 when the radar sweep is disabled.
+    fn radar_brightness(&self, sweep_angle: Option<f32>, angle: f32) -> f32 {
+        match sweep_angle {
+            Some(sweep_angle) => {
+                let turns_since_sweep = (sweep_angle - angle).rem_euclid(TAU) / TAU;
+                (1.0 - turns_since_sweep / self.radar_afterglow).clamp(0.0, 1.0)
+            }
+            None => 1.0,
+        }
+    }
+
+    /// Seconds of marker positions kept in memory.
+    fn marker_history_length(&self) -> f64 {
+        self.marker_trail_length.unwrap_or(1.0) as f64
+    }
+
+    /// Records the current marker positions into the trails kept in memory,
+    /// dropping samples older than the history length. Returns the updated
+    /// trails, taken out of memory until the caller puts them back, and
+    /// whether any of the markers moved within that time.
+    fn update_marker_trails(&self, ui: &Ui, trails_id: Id) -> (MarkerTrails, bool) {
+        let now = ui.input(|input| input.time);
+        let history_length = self.marker_history_length();
+        let sample_interval = history_length / MAX_MARKER_TRAIL_SAMPLES as f64;
+
+        let mut marker_trails = ui.memory_mut(|memory| {
+            std::mem::take(
+                memory
+                    .data
+                    .get_temp_mut_or_default::<MarkerTrails>(trails_id),
+            )
+        });
+
+        let marker_ids = self
+            .markers
+            .iter()
+            .enumerate()
+            .map(|(marker_index, marker)| marker.id_or_index(marker_index))
+            .collect_vec();
+
+        // Forget the markers no longer shown
+        let live_marker_ids = marker_ids.iter().copied().collect::<HashSet<_>>();
+        marker_trails.retain(|marker_id, _| live_marker_ids.contains(marker_id));
+
+        let mut markers_moving = false;
+
+        for (marker, marker_id) in self.markers.iter().zip(marker_ids) {
+            let marker_distance = match marker.distance {
+                Some(marker_distance) => marker_distance,
+                None => continue,
+            };
+
+            let samples = marker_trails.entry(marker_id).or_default();
+
+            while samples
+                .front()
+                .map_or(false, |sample| now - sample.time > history_length)
+            {
+                samples.pop_front();
+            }
+
+            if !samples
+                .back()
+                .map_or(false, |sample| now - sample.time < sample_interval)
+            {
+                samples.push_back(MarkerTrailSample {
+                    time: now,
+                    angle: marker.angle,
+                    distance: marker_distance,
+                });
+            }
+
+            markers_moving |= samples.iter().any(|sample| {
+                (sample.angle != marker.angle) || (sample.distance != marker_distance)
+            });
+        }
+
+        (marker_trails, markers_moving)
+    }
+
+    /// Groups of marker indices closer to each other than the clustering
+    /// distance, single markers form groups of their own. Clipped markers are
+    /// left out. Rotating the compass doesn't change the groups, so they're
+    /// computed once per frame.
+    fn cluster_markers(&self, rect: Rect, value: f32) -> Vec<Vec<usize>> {
+        let mut clusters: Vec<(Pos2, Vec<usize>)> = Vec::new();
+
+        for (marker_index, marker) in self.markers.iter().enumerate() {
+            let marker_center = match self.marker_rect(rect, value, marker) {
+                Some(marker_rect) => marker_rect.center(),
+                None => continue,
+            };
+
+            let cluster = self.marker_clustering.and_then(|clustering_distance| {
+                clusters.iter_mut().find(|(cluster_center, _)| {
+                    cluster_center.distance(marker_center) <= clustering_distance
+                })
+            });
+
+            match cluster {
+                Some((_, cluster_markers)) => cluster_markers.push(marker_index),
+                None => clusters.push((marker_center, vec![marker_index])),
+            }
+        }
+
+        clusters
+            .into_iter()
+            .map(|(_, cluster_markers)| cluster_markers)
+            .collect()
+    }
+
+    /// Count badge drawn in place of a cluster of markers.
+    fn cluster_badge_rect(&self, rect: Rect, value: f32, cluster_markers: &[usize]) -> Rect {
+        let cluster_center = cluster_markers
+            .iter()
+            .filter_map(|&marker_index| self.marker_rect(rect, value, &self.markers[marker_index]))
+            .fold(Vec2::ZERO, |sum, marker_rect| {
+                sum + marker_rect.center().to_vec2()
+            })
+            / cluster_markers.len() as f32;
+
+        Rect::from_center_size(cluster_center.to_pos2(), Vec2::splat(self.marker_near_size))
+    }
+
+    /// Index of the topmost unclustered marker at `pos`.
+    fn marker_at(
+        &self,
+        rect: Rect,
+        value: f32,
+        marker_clusters: &[Vec<usize>],
+        pos: Pos2,
+    ) -> Option<usize> {
+        marker_clusters
+            .iter()
+            .filter(|cluster_markers| cluster_markers.len() == 1)
+            .map(|cluster_markers| cluster_markers[0])
+            .filter(|&marker_index| {
+                self.marker_rect(rect, value, &self.markers[marker_index])
+                    .map_or(false, |marker_rect| marker_rect.contains(pos))
+            })
+            .max()
+    }
+
+    /// Places labels of the given sizes next to their markers, returning
+    /// their rectangles and whether they need a leader line.
+    fn layout_labels(
+        &self,
+        rect: Rect,
+        labels: &[(Rect, Vec2)],
+        obstacles: &[Rect],
+    ) -> Vec<(Rect, bool)> {
+        // Upper bound of alternative positions tried for a single label
+        const MAX_ATTEMPTS: usize = 16;
+
+        let mut placed_labels: Vec<Rect> = Vec::with_capacity(labels.len());
+
+        labels
+            .iter()
+            .map(|&(marker_rect, label_size)| {
+                let marker_center = marker_rect.center();
+                let marker_size = marker_rect.width();
+
+                let default_rect =
+                    Rect::from_center_size(marker_center + Vec2::DOWN * marker_size, label_size);
+
+                let is_free = |label_rect: &Rect| {
+                    !placed_labels
+                        .iter()
+                        .chain(
+                            obstacles
+                                .iter()
+                                .filter(|&&obstacle| obstacle != marker_rect),
+                        )
+                        .any(|other_rect| other_rect.intersects(*label_rect))
+                };
+
+                let candidates: Vec<(Rect, bool)> = match self.label_layout {
+                    PolarCompassLabelLayout::Overlap => vec![],
+                    PolarCompassLabelLayout::Offset => {
+                        let horizontal = (marker_size + label_size.x) / 2.0 + 2.0;
+                        let vertical = marker_size;
+
+                        [
+                            vec2(0.0, vertical),
+                            vec2(0.0, -vertical),
+                            vec2(horizontal, 0.0),
+                            vec2(-horizontal, 0.0),
+                            vec2(horizontal, vertical),
+                            vec2(-horizontal, vertical),
+                            vec2(horizontal, -vertical),
+                            vec2(-horizontal, -vertical),
+                        ]
+                        .into_iter()
+                        .map(|offset| {
+                            (
+                                Rect::from_center_size(marker_center + offset, label_size),
+                                false,
+                            )
+                        })
+                        .collect()
+                    }
+                    PolarCompassLabelLayout::Stack => (0..MAX_ATTEMPTS)
+                        .map(|row| {
+                            (
+                                default_rect.translate(Vec2::DOWN * (label_size.y * row as f32)),
+                                false,
+                            )
+                        })
+                        .collect(),
+                    PolarCompassLabelLayout::LeaderLines => {
+                        let direction = Some(marker_center - rect.center())
+                            .filter(|offset| offset.length() > 0.0)
+                            .map_or(Vec2::DOWN, Vec2::normalized);
+
+                        std::iter::once((default_rect, false))
+                            .chain((1..MAX_ATTEMPTS).map(|step| {
+                                let label_distance =
+                                    marker_size + step as f32 * (label_size.y + 2.0);
+                                let label_center = marker_center
+                                    + direction * label_distance
+                                    + direction * (label_size / 2.0);
+                                (Rect::from_center_size(label_center, label_size), true)
+                            }))
+                            .collect()
+                    }
+                };
+
+                let label_placement = candidates
+                    .into_iter()
+                    .find(|(label_rect, _)| is_free(label_rect))
+                    .unwrap_or((default_rect, false));
+
+                placed_labels.push(label_placement.0);
+                label_placement
+            })
+            .collect()
+    }
+
+    /// Angle and distance of a marker placed at `pos`.
+    fn marker_position_at(&self, rect: Rect, value: f32, pos: Pos2) -> (f32, f32) {
+        let offset = pos - rect.center();
+
+        let angle = (self.orientation.rot2().inverse() * offset).angle() * self.winding.to_float()
+            + self.view_angle(value);
+
+        let marker_t = (offset.length() / (self.diameter / 2.0)).clamp(0.0, 1.0);
+        let distance =
+            (marker_t * self.max_log()).symexp(self.scale_log_base) * self.scale_log_mult;
+
+        (normalized_angle_unsigned_excl(angle), distance)
+    }
 }
 
 impl<'a> Widget for PolarCompass<'a> {
@@ -279,26 +817,158 @@ impl<'a> Widget for PolarCompass<'a> {
 
         let rotation_matrix = self.orientation.rot2();
 
+        let current_value = get(&mut self.get_set_value);
+        let marker_drag_id = response.id.with("marker_drag");
+        let bug_drag_id = response.id.with("bug_drag");
+
+        let mut bug_angles = self.bugs.iter_mut().map(|bug| bug.get()).collect_vec();
+
+        let hovered_bug_index = response
+            .hover_pos()
+            .and_then(|pos| self.bug_at(rect, current_value, &bug_angles, pos));
+
+        let marker_clusters = self.cluster_markers(rect, current_value);
+
+        let hovered_marker_index = response
+            .hover_pos()
+            .and_then(|pos| self.marker_at(rect, current_value, &marker_clusters, pos));
+
+        let hovered_cluster = response
+            .hover_pos()
+            .filter(|_| self.marker_clustering.is_some())
+            .and_then(|pos| {
+                marker_clusters
+                    .iter()
+                    .filter(|cluster_markers| cluster_markers.len() > 1)
+                    .find(|cluster_markers| {
+                        self.cluster_badge_rect(rect, current_value, cluster_markers)
+                            .contains(pos)
+                    })
+            });
+
         if response.drag_started() {
-            let value_before_drag = get(&mut self.get_set_value);
+            let value_before_drag = current_value;
             ui.memory_mut(|memory| memory.data.insert_temp(response.id, value_before_drag));
+
+            let press_origin = ui.input(|input| input.pointer.press_origin().unwrap());
+
+            if let Some(bug_index) = self.bug_at(rect, current_value, &bug_angles, press_origin) {
+                ui.memory_mut(|memory| memory.data.insert_temp(bug_drag_id, bug_index));
+            } else if self.dragged_marker.is_some() {
+                if let Some(marker_index) =
+                    self.marker_at(rect, current_value, &marker_clusters, press_origin)
+                {
+                    // Keep the marker at the same offset from the pointer it was grabbed at
+                    let marker_rect = self
+                        .marker_rect(rect, current_value, &self.markers[marker_index])
+                        .unwrap();
+                    let grab_offset = press_origin - marker_rect.center();
+
+                    ui.memory_mut(|memory| {
+                        memory
+                            .data
+                            .insert_temp(marker_drag_id, (marker_index, grab_offset))
+                    });
+                }
+            }
         }
 
+        let marker_drag =
+            ui.memory_mut(|memory| memory.data.get_temp::<(usize, Vec2)>(marker_drag_id));
+        let bug_drag = ui.memory_mut(|memory| memory.data.get_temp::<usize>(bug_drag_id));
+
         if response.drag_released() {
             ui.memory_mut(|memory| memory.data.remove::<f32>(response.id));
+            ui.memory_mut(|memory| memory.data.remove::<(usize, Vec2)>(marker_drag_id));
+            ui.memory_mut(|memory| memory.data.remove::<usize>(bug_drag_id));
+        }
+
+        let hovered_marker_id = hovered_marker_index
+            .map(|marker_index| self.markers[marker_index].id_or_index(marker_index));
+
+        if let Some(hovered_marker) = self.hovered_marker.as_mut() {
+            **hovered_marker = hovered_marker_id;
+        }
+
+        if let Some(clicked_marker) = self.clicked_marker.as_mut() {
+            **clicked_marker = if response.clicked() {
+                hovered_marker_id
+            } else {
+                None
+            };
+        }
+
+        if let Some(selected_marker) = self.selected_marker.as_mut() {
+            if response.clicked() {
+                **selected_marker = hovered_marker_id;
+            }
+        }
+
+        let selected_marker_id = self.selected_marker.as_deref().copied().flatten();
+
+        if let Some(dragged_marker) = self.dragged_marker.as_mut() {
+            **dragged_marker = None;
+
+            if marker_drag.is_some() && response.dragged() {
+                ui.output_mut(|output| output.cursor_icon = CursorIcon::Grabbing);
+            } else if hovered_marker_index.is_some() {
+                ui.output_mut(|output| output.cursor_icon = CursorIcon::Grab);
+            }
         }
 
-        if response.dragged() {
+        if bug_drag.is_some() && response.dragged() {
+            ui.output_mut(|output| output.cursor_icon = CursorIcon::Grabbing);
+        } else if hovered_bug_index.is_some() {
+            ui.output_mut(|output| output.cursor_icon = CursorIcon::Grab);
+        }
+
+        if let (true, Some(bug_index)) = (response.dragged(), bug_drag) {
+            let (bug_angle, _) = self.marker_position_at(
+                rect,
+                current_value,
+                response.interact_pointer_pos().unwrap(),
+            );
+
+            self.bugs[bug_index].set(bug_angle);
+            bug_angles[bug_index] = bug_angle;
+
+            response.mark_changed();
+        } else if let (true, Some((marker_index, grab_offset))) = (response.dragged(), marker_drag)
+        {
+            let (angle, distance) = self.marker_position_at(
+                rect,
+                current_value,
+                response.interact_pointer_pos().unwrap() - grab_offset,
+            );
+
+            if let Some(dragged_marker) = self.dragged_marker.as_mut() {
+                **dragged_marker = Some(CompassMarkerDrag {
+                    index: marker_index,
+                    id: self.markers[marker_index].id_or_index(marker_index),
+                    angle,
+                    distance: Some(distance),
+                });
+            }
+
+            response.mark_changed();
+        } else if response.dragged() {
             let screen_pos_to_angle = |pos: Pos2| {
                 -(rotation_matrix * (rect.center() - pos)).angle() * self.winding.to_float()
             };
 
+            // Heading-up dials follow the pointer, north-up cursors follow the pointer
+            let drag_direction = match self.mode {
+                PolarCompassMode::HeadingUp => 1.0,
+                PolarCompassMode::NorthUp => -1.0,
+            };
+
             let value_before_drag = ui.memory_mut(|memory| memory.data.get_temp::<f32>(response.id).unwrap());
             let prev_value = get(&mut self.get_set_value);
 
             let mut new_value = normalized_angle(
-                screen_pos_to_angle(response.interact_pointer_pos().unwrap())
-                    - screen_pos_to_angle(ui.input(|input| input.pointer.press_origin().unwrap()))
+                (screen_pos_to_angle(response.interact_pointer_pos().unwrap())
+                    - screen_pos_to_angle(ui.input(|input| input.pointer.press_origin().unwrap())))
+                    * drag_direction
                     + value_before_drag,
             );
 
@@ -319,8 +989,31 @@ impl<'a> Widget for PolarCompass<'a> {
             response.mark_changed();
         }
 
+        let marker_trails_id = response.id.with("marker_trails");
+        let marker_trails = if self.marker_trail_length.is_some()
+            || self.marker_velocity_vectors.is_some()
+        {
+            let (marker_trails, markers_moving) = self.update_marker_trails(ui, marker_trails_id);
+
+            if markers_moving {
+                ui.ctx().request_repaint();
+            }
+
+            Some(marker_trails)
+        } else {
+            None
+        };
+
+        let sweep_angle = self.radar_sweep.map(|radar_sweep| {
+            ui.ctx().request_repaint();
+            (ui.input(|input| input.time) / radar_sweep as f64).fract() as f32 * TAU
+        });
+
         if ui.is_rect_visible(rect) {
-            let visuals = *ui.style().interact(&response);
+            let style = self
+                .style
+                .clone()
+                .unwrap_or_else(|| CompassStyle::interactive_system_style(ui, &response));
             let radius = self.diameter / 2.0;
 
             let value = if self.animated {
@@ -337,8 +1030,12 @@ impl<'a> Widget for PolarCompass<'a> {
                 ui.painter().circle(
                     rect.center(),
                     radius,
-                    ui.style().visuals.extreme_bg_color, // TODO: Semantically correct color
-                    visuals.fg_stroke,                   // TODO: Semantically correct color
+                    if sweep_angle.is_some() {
+                        self.radar_style.background_color
+                    } else {
+                        style.background_color
+                    },
+                    style.outline_stroke,
                 );
             }
 
@@ -352,22 +1049,108 @@ impl<'a> Widget for PolarCompass<'a> {
                     ui.painter().circle_stroke(
                         rect.center(),
                         radius * (i as f32 / max_log),
-                        ui.style().visuals.noninteractive().fg_stroke, // TODO: Semantically correct color
+                        if sweep_angle.is_some() {
+                            self.radar_style.inactive_foreground_stroke
+                        } else {
+                            style.ring_stroke
+                        },
                     );
                 }
             }
 
             let angle_to_direction = |angle: f32| {
-                rotation_matrix * Vec2::angled((angle - value) * self.winding.to_float())
+                rotation_matrix
+                    * Vec2::angled((angle - self.view_angle(value)) * self.winding.to_float())
             };
 
+            for sector in self.sectors {
+                let (inner_radius, outer_radius) = match &sector.distance_range {
+                    Some(distance_range) => (
+                        radius * self.distance_to_t(*distance_range.start()),
+                        radius * self.distance_to_t(*distance_range.end()),
+                    ),
+                    None => (0.0, radius),
+                };
+
+                let sector_color = sector.fill_color(ui);
+                let start_angle = angle_to_direction(sector.start).angle();
+
+                WidgetShape::Circle.paint_arc(
+                    ui,
+                    rect.center(),
+                    inner_radius,
+                    outer_radius,
+                    start_angle,
+                    start_angle + sector.span * self.winding.to_float(),
+                    sector_color,
+                    Stroke::new(1.0, sector_color),
+                    Rot2::IDENTITY,
+                );
+
+                if let Some(sector_label) = &sector.label {
+                    ui.painter().text(
+                        rect.center()
+                            + angle_to_direction(sector.middle())
+                                * ((inner_radius + outer_radius) / 2.0),
+                        Align2::CENTER_CENTER,
+                        sector_label,
+                        FontId::new(
+                            self.axis_label_height * 0.75,
+                            style.label_font_family.clone(),
+                        ),
+                        style.text_color,
+                    );
+                }
+            }
+
+            if let Some(sweep_angle) = sweep_angle {
+                const AFTERGLOW_STEPS: usize = 64;
+
+                let afterglow_color = |t: f32| {
+                    self.radar_style
+                        .active_foreground_color
+                        .linear_multiply(0.5 * (1.0 - t))
+                };
+
+                let afterglow_point = |t: f32| {
+                    let afterglow_angle = sweep_angle - t * self.radar_afterglow * TAU;
+                    rect.center() + angle_to_direction(afterglow_angle) * radius
+                };
+
+                // Drawn as a mesh rather than arcs to get a smooth gradient
+                let mut afterglow_mesh = Mesh::default();
+
+                for step in 0..AFTERGLOW_STEPS {
+                    let (t0, t1) = (
+                        step as f32 / AFTERGLOW_STEPS as f32,
+                        (step + 1) as f32 / AFTERGLOW_STEPS as f32,
+                    );
+
+                    let vertex_index = afterglow_mesh.vertices.len() as u32;
+                    afterglow_mesh.colored_vertex(rect.center(), afterglow_color(t0));
+                    afterglow_mesh.colored_vertex(afterglow_point(t0), afterglow_color(t0));
+                    afterglow_mesh.colored_vertex(afterglow_point(t1), afterglow_color(t1));
+                    afterglow_mesh.add_triangle(vertex_index, vertex_index + 1, vertex_index + 2);
+                }
+
+                ui.painter().add(afterglow_mesh);
+
+                ui.painter().line_segment(
+                    [
+                        rect.center(),
+                        rect.center() + angle_to_direction(sweep_angle) * radius,
+                    ],
+                    self.radar_style.active_foreground_stroke,
+                );
+            }
+
             if self.show_cursor {
                 ui.painter().add(Shape::dashed_line(
                     &[
                         rect.center(),
-                        rect.center() + rotation_matrix * Vec2::RIGHT * radius,
+                        rect.center() + angle_to_direction(value) * radius,
                     ],
-                    ui.style().visuals.noninteractive().fg_stroke, // TODO: Semantically correct color
+                    style.cursor_stroke,
                     2.0,
                     2.0,
                 ));
@@ -383,7 +1166,7 @@ impl<'a> Widget for PolarCompass<'a> {
                             rect.center(),
                             rect.center() + angle_to_direction(axis_angle) * radius,
                         ],
-                        visuals.fg_stroke, // TODO: Semantically correct color
+                        style.axis_stroke,
                     ));
 
                     ui.painter().rotated_text(
@@ -392,73 +1175,264 @@ impl<'a> Widget for PolarCompass<'a> {
                                 * (radius + self.axis_label_height / 2.0),
                         Align2::CENTER_CENTER,
                         axis_label,
-                        FontId::new(self.axis_label_height, FontFamily::Proportional),
-                        visuals.text_color(), // TODO: Semantically correct color
+                        FontId::new(self.axis_label_height, style.label_font_family.clone()),
+                        style.text_color,
                         angle_to_direction(axis_angle).angle() + (TAU / 4.0),
                     );
                 }
             }
 
-            for marker in self.markers {
-                let marker_distance = marker.distance.expect("marker has no distance");
+            {
+                let now = ui.input(|input| input.time);
+                let sample_interval =
+                    self.marker_history_length() / MAX_MARKER_TRAIL_SAMPLES as f64;
+
+                for (marker_index, marker) in self.markers.iter().enumerate() {
+                    let (marker_distance, samples) = match (
+                        marker.distance,
+                        marker_trails.as_ref().and_then(|marker_trails| {
+                            marker_trails.get(&marker.id_or_index(marker_index))
+                        }),
+                    ) {
+                        (Some(marker_distance), Some(samples)) => (marker_distance, samples),
+                        _ => continue,
+                    };
+
+                    let marker_color = marker
+                        .color
+                        .unwrap_or_else(|| self.default_marker_color.color(ui, marker));
+
+                    if let Some(marker_trail_length) = self.marker_trail_length {
+                        let trail_points = samples
+                            .iter()
+                            .map(|sample| {
+                                let sample_pos = self.position_to_screen(
+                                    rect,
+                                    value,
+                                    sample.angle,
+                                    sample.distance,
+                                );
+                                ((now - sample.time) as f32, sample_pos)
+                            })
+                            .chain([(
+                                0.0,
+                                self.position_to_screen(rect, value, marker.angle, marker_distance),
+                            )]);
+
+                        for ((age, from), (_, to)) in trail_points.tuple_windows() {
+                            if let (Some(from), Some(to)) = (from, to) {
+                                let fade = 1.0 - (age / marker_trail_length).clamp(0.0, 1.0);
+
+                                ui.painter().line_segment(
+                                    [from, to],
+                                    Stroke::new(1.0, marker_color.linear_multiply(fade)),
+                                );
+                            }
+                        }
+                    }
 
-                if (marker_distance > self.max_distance)
-                    && (self.overflow == PolarCompassOverflow::Clip)
-                {
-                    continue;
+                    if let Some(marker_velocity_vectors) = self.marker_velocity_vectors {
+                        let marker_vec = Vec2::angled(marker.angle) * marker_distance;
+
+                        let velocity = match marker.velocity {
+                            Some((speed, course)) => Vec2::angled(course) * speed,
+                            None => {
+                                // Estimate the velocity over at least one sample interval to
+                                // smooth out positions updated less often than every frame
+                                let reference_sample = match samples
+                                    .iter()
+                                    .rev()
+                                    .find(|sample| now - sample.time >= sample_interval)
+                                {
+                                    Some(reference_sample) => reference_sample,
+                                    None => continue,
+                                };
+
+                                let reference_vec = Vec2::angled(reference_sample.angle)
+                                    * reference_sample.distance;
+
+                                (marker_vec - reference_vec) / (now - reference_sample.time) as f32
+                            }
+                        };
+                        let predicted_vec = marker_vec + velocity * marker_velocity_vectors;
+
+                        if let (Some(from), Some(to)) = (
+                            self.position_to_screen(rect, value, marker.angle, marker_distance),
+                            self.position_to_screen(
+                                rect,
+                                value,
+                                predicted_vec.angle(),
+                                predicted_vec.length(),
+                            ),
+                        ) {
+                            if from.distance(to) >= 1.0 {
+                                ui.painter()
+                                    .arrow(from, to - from, Stroke::new(1.0, marker_color));
+                            }
+                        }
+                    }
                 }
+            }
 
-                let marker_color = marker
-                    .color
-                    .unwrap_or_else(|| self.default_marker_color.color(ui, marker));
+            let mut marker_labels = Vec::new();
+            let mut label_obstacles = Vec::new();
 
-                let marker_stroke = {
-                    let stroke_color =
-                        tint_color_towards(marker_color, ui.style().visuals.text_color());
-                    Stroke::new(1.0, stroke_color)
-                };
+            for cluster_markers in &marker_clusters {
+                if let [marker_index] = cluster_markers[..] {
+                    let marker = &self.markers[marker_index];
+                    let marker_rect = self.marker_rect(rect, value, marker).unwrap();
 
-                let max_log = (self.max_distance / self.scale_log_mult).symlog(self.scale_log_base);
-                let marker_log =
-                    (marker_distance / self.scale_log_mult).symlog(self.scale_log_base);
-                let marker_t = (marker_log / max_log).clamp(0.0, 1.0);
+                    let marker_highlighted = (hovered_marker_index == Some(marker_index))
+                        || marker_drag
+                            .map_or(false, |(dragged_index, _)| dragged_index == marker_index);
 
-                let marker_center =
-                    rect.center() + angle_to_direction(marker.angle) * (radius * marker_t);
-                let marker_size = lerp(self.marker_near_size..=self.marker_far_size, marker_t);
+                    let marker_color = marker
+                        .color
+                        .unwrap_or_else(|| self.default_marker_color.color(ui, marker));
 
-                if self.show_marker_lines {
-                    ui.painter().add(Shape::dashed_line(
-                        &[rect.center(), marker_center],
-                        marker_stroke,
-                        2.0,
-                        4.0,
-                    ));
-                }
+                    let marker_brightness = self.radar_brightness(sweep_angle, marker.angle);
 
-                let marker_shape = marker.shape.unwrap_or(self.default_marker_shape);
+                    let marker_stroke = {
+                        let stroke_color = tint_color_towards(marker_color, style.text_color)
+                            .linear_multiply(marker_brightness);
+                        Stroke::new(if marker_highlighted { 2.0 } else { 1.0 }, stroke_color)
+                    };
 
-                marker_shape.paint(
-                    ui,
-                    Rect::from_center_size(marker_center, Vec2::splat(marker_size)),
-                    marker_color,
-                    marker_stroke,
-                );
+                    let marker_color = marker_color.linear_multiply(marker_brightness);
+
+                    if self.show_marker_lines {
+                        ui.painter().add(Shape::dashed_line(
+                            &[rect.center(), marker_rect.center()],
+                            marker_stroke,
+                            2.0,
+                            4.0,
+                        ));
+                    }
+
+                    let marker_shape = marker.shape.unwrap_or(self.default_marker_shape);
+
+                    marker_shape.paint_or_icon(
+                        ui,
+                        marker_rect,
+                        marker.icon,
+                        marker_color,
+                        marker_stroke,
+                    );
 
-                if self.show_marker_labels {
-                    let label_center = marker_center + Vec2::DOWN * marker_size;
+                    if selected_marker_id == Some(marker.id_or_index(marker_index)) {
+                        ui.painter().rect_stroke(
+                            marker_rect.expand(2.0),
+                            2.0,
+                            ui.style().visuals.selection.stroke,
+                        );
+                    }
 
-                    if let Some(marker_label) = marker.label {
-                        ui.painter().text(
-                            label_center,
-                            Align2::CENTER_CENTER,
-                            marker_label,
-                            FontId::new(marker_size, FontFamily::Proportional),
+                    label_obstacles.push(marker_rect);
+
+                    if let (true, Some(marker_label)) = (self.show_marker_labels, &marker.label) {
+                        let label_galley = ui.painter().layout_no_wrap(
+                            marker_label.clone(),
+                            FontId::new(marker_rect.height(), style.label_font_family.clone()),
                             marker_color,
                         );
+
+                        marker_labels.push((marker_rect, label_galley, marker_color));
                     }
+                } else {
+                    let badge_rect = self.cluster_badge_rect(rect, value, cluster_markers);
+
+                    if self.show_marker_lines {
+                        ui.painter().add(Shape::dashed_line(
+                            &[rect.center(), badge_rect.center()],
+                            style.axis_stroke,
+                            2.0,
+                            4.0,
+                        ));
+                    }
+
+                    ui.painter().circle(
+                        badge_rect.center(),
+                        badge_rect.width() / 2.0,
+                        style.background_color,
+                        style.outline_stroke,
+                    );
+
+                    ui.painter().text(
+                        badge_rect.center(),
+                        Align2::CENTER_CENTER,
+                        cluster_markers.len(),
+                        FontId::new(badge_rect.height() * 0.75, style.label_font_family.clone()),
+                        style.text_color,
+                    );
+
+                    label_obstacles.push(badge_rect);
                 }
             }
+
+            let label_placements = self.layout_labels(
+                rect,
+                &marker_labels
+                    .iter()
+                    .map(|(marker_rect, label_galley, _)| (*marker_rect, label_galley.size()))
+                    .collect::<Vec<_>>(),
+                &label_obstacles,
+            );
+
+            for ((marker_rect, label_galley, label_color), (label_rect, leader_line)) in
+                marker_labels.into_iter().zip(label_placements)
+            {
+                if leader_line {
+                    let label_anchor = label_rect.clamp(marker_rect.center());
+                    let leader_start = marker_rect.center()
+                        + (label_anchor - marker_rect.center()).normalized()
+                            * (marker_rect.width() / 2.0);
+
+                    ui.painter()
+                        .line_segment([leader_start, label_anchor], Stroke::new(1.0, label_color));
+                }
+
+                ui.painter().galley(label_rect.min, label_galley);
+            }
+
+            for (bug_index, (bug, &bug_angle)) in self.bugs.iter().zip(&bug_angles).enumerate() {
+                let bug_highlighted =
+                    (hovered_bug_index == Some(bug_index)) || (bug_drag == Some(bug_index));
+
+                bug.paint(
+                    ui,
+                    self.bug_outline(rect, value, bug, bug_angle),
+                    style.background_color,
+                    bug_highlighted,
+                );
+            }
+        }
+
+        if let (true, false, Some(cluster_markers)) = (
+            self.show_marker_tooltips,
+            response.dragged(),
+            hovered_cluster,
+        ) {
+            show_tooltip_at_pointer(ui.ctx(), response.id.with("marker_tooltip"), |ui| {
+                ui.strong(format!("{} markers", cluster_markers.len()));
+
+                for &marker_index in cluster_markers {
+                    if let Some(marker_label) = &self.markers[marker_index].label {
+                        ui.label(marker_label);
+                    }
+                }
+            });
+        }
+
+        if let (true, false, Some(marker_index)) = (
+            self.show_marker_tooltips,
+            response.dragged(),
+            hovered_marker_index,
+        ) {
+            self.markers[marker_index].show_tooltip(ui, response.id.with("marker_tooltip"));
+        }
+
+        if let Some(marker_trails) = marker_trails {
+            ui.memory_mut(|memory| memory.data.insert_temp(marker_trails_id, marker_trails));
         }
 
         response
@@ -0,0 +1,213 @@
+use std::ops::RangeInclusive;
+
+use emath::{lerp, pos2, Pos2, Rect, Vec2};
+
+use crate::common::LinearOrientation;
+
+// ----------------------------------------------------------------------------
+
+/// Linear mapping between values and screen positions along a tape, shared
+/// by the widgets drawing a scale scrolling past a fixed cursor in the
+/// middle of their rect.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct TapeScale {
+    rect: Rect,
+    orientation: LinearOrientation,
+    value: f32,
+    spread: f32,
+}
+
+impl TapeScale {
+    /// Scale centered on `value`, with `spread` worth of values visible along
+    /// the tape. Values increase to the right or upwards, or the other way
+    /// around for negative spreads.
+    pub(crate) fn new(rect: Rect, orientation: LinearOrientation, value: f32, spread: f32) -> Self {
+        Self {
+            rect,
+            orientation,
+            value,
+            spread,
+        }
+    }
+
+    /// Length of the tape in screen units.
+    pub(crate) fn length(&self) -> f32 {
+        match self.orientation {
+            LinearOrientation::Horizontal => self.rect.width(),
+            LinearOrientation::Vertical => self.rect.height(),
+        }
+    }
+
+    /// Unit vector pointing towards increasing values.
+    pub(crate) fn direction(&self) -> Vec2 {
+        let direction = match self.orientation {
+            LinearOrientation::Horizontal => Vec2::RIGHT,
+            LinearOrientation::Vertical => Vec2::UP,
+        };
+
+        direction * self.spread.signum()
+    }
+
+    /// Screen coordinate of `value` along the tape.
+    pub(crate) fn value_to_screen(&self, value: f32) -> f32 {
+        let offset = (value - self.value) * (self.length() / self.spread);
+
+        match self.orientation {
+            LinearOrientation::Horizontal => self.rect.center().x + offset,
+            LinearOrientation::Vertical => self.rect.center().y - offset,
+        }
+    }
+
+    /// Value under the screen position `pos`, ignoring where it lies across
+    /// the tape.
+    pub(crate) fn screen_to_value(&self, pos: Pos2) -> f32 {
+        let offset = match self.orientation {
+            LinearOrientation::Horizontal => pos.x - self.rect.center().x,
+            LinearOrientation::Vertical => self.rect.center().y - pos.y,
+        };
+
+        self.value + offset * (self.spread / self.length())
+    }
+
+    /// Change of the value scrolling the tape along with a pointer dragged
+    /// by `delta`.
+    pub(crate) fn drag_delta_to_value(&self, delta: Vec2) -> f32 {
+        let offset = match self.orientation {
+            LinearOrientation::Horizontal => delta.x,
+            LinearOrientation::Vertical => -delta.y,
+        };
+
+        -offset * (self.spread / self.length())
+    }
+
+    /// Screen position at the coordinate `along` the tape, `across` going
+    /// from the left or top edge (0.0) to the right or bottom edge (1.0).
+    pub(crate) fn point(&self, along: f32, across: f32) -> Pos2 {
+        match self.orientation {
+            LinearOrientation::Horizontal => pos2(along, lerp(self.rect.y_range(), across)),
+            LinearOrientation::Vertical => pos2(lerp(self.rect.x_range(), across), along),
+        }
+    }
+
+    /// Range of values visible on the tape.
+    pub(crate) fn visible_range(&self) -> RangeInclusive<f32> {
+        let half_spread = self.spread.abs() / 2.0;
+        (self.value - half_spread)..=(self.value + half_spread)
+    }
+
+    /// Indices of the multiples of `step` within the visible range, rounded
+    /// outwards to include the ticks partially scrolled into view. The step
+    /// must be positive, checked by the widgets when it's set.
+    pub(crate) fn tick_indices(&self, step: f32) -> RangeInclusive<isize> {
+        let visible_range = self.visible_range();
+        let start = (visible_range.start() / step).floor() as isize;
+        let end = (visible_range.end() / step).ceil() as isize;
+
+        start..=end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use emath::{pos2, vec2, Rect, Vec2};
+
+    use super::TapeScale;
+    use crate::common::LinearOrientation;
+
+    const EPSILON: f32 = 1e-3;
+
+    fn horizontal_scale(value: f32, spread: f32) -> TapeScale {
+        TapeScale::new(
+            Rect::from_min_size(pos2(10.0, 20.0), vec2(200.0, 40.0)),
+            LinearOrientation::Horizontal,
+            value,
+            spread,
+        )
+    }
+
+    fn vertical_scale(value: f32, spread: f32) -> TapeScale {
+        TapeScale::new(
+            Rect::from_min_size(pos2(10.0, 20.0), vec2(40.0, 400.0)),
+            LinearOrientation::Vertical,
+            value,
+            spread,
+        )
+    }
+
+    fn assert_round_trip(scale: &TapeScale) {
+        for value in [-1000.0, -12.5, 0.0, 37.0, 50.0, 99.9, 2500.0] {
+            let along = scale.value_to_screen(value);
+            let pos = scale.point(along, 0.5);
+            let round_trip = scale.screen_to_value(pos);
+
+            assert!(
+                (round_trip - value).abs() < EPSILON,
+                "{} != {}",
+                round_trip,
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn values_round_trip_through_the_screen() {
+        for spread in [100.0, -100.0, 0.5] {
+            assert_round_trip(&horizontal_scale(50.0, spread));
+            assert_round_trip(&vertical_scale(50.0, spread));
+        }
+    }
+
+    #[test]
+    fn current_value_is_centered() {
+        let horizontal = horizontal_scale(50.0, 100.0);
+        assert_eq!(horizontal.value_to_screen(50.0), 110.0);
+
+        let vertical = vertical_scale(50.0, 100.0);
+        assert_eq!(vertical.value_to_screen(50.0), 220.0);
+    }
+
+    #[test]
+    fn values_increase_towards_the_direction() {
+        let horizontal = horizontal_scale(50.0, 100.0);
+        assert_eq!(horizontal.direction(), Vec2::RIGHT);
+        assert_eq!(horizontal.value_to_screen(60.0), 130.0);
+        assert_eq!(horizontal.value_to_screen(0.0), 10.0);
+
+        let vertical = vertical_scale(50.0, 100.0);
+        assert_eq!(vertical.direction(), Vec2::UP);
+        assert_eq!(vertical.value_to_screen(60.0), 180.0);
+        assert_eq!(vertical.value_to_screen(0.0), 420.0);
+
+        let inverted = vertical_scale(50.0, -100.0);
+        assert_eq!(inverted.direction(), Vec2::DOWN);
+        assert_eq!(inverted.value_to_screen(60.0), 260.0);
+    }
+
+    #[test]
+    fn dragging_scrolls_the_tape_with_the_pointer() {
+        let horizontal = horizontal_scale(50.0, 100.0);
+        assert_eq!(horizontal.drag_delta_to_value(vec2(20.0, 5.0)), -10.0);
+
+        let vertical = vertical_scale(50.0, 100.0);
+        assert_eq!(vertical.drag_delta_to_value(vec2(5.0, 20.0)), 5.0);
+
+        let inverted = vertical_scale(50.0, -100.0);
+        assert_eq!(inverted.drag_delta_to_value(vec2(5.0, 20.0)), -5.0);
+    }
+
+    #[test]
+    fn tick_indices_cover_the_visible_range() {
+        assert_eq!(horizontal_scale(50.0, 100.0).tick_indices(20.0), 0..=5);
+        assert_eq!(horizontal_scale(55.0, 100.0).tick_indices(20.0), 0..=6);
+        assert_eq!(horizontal_scale(-50.0, 30.0).tick_indices(10.0), -7..=-3);
+        assert_eq!(vertical_scale(0.0, 25.0).tick_indices(10.0), -2..=2);
+    }
+
+    #[test]
+    fn tick_indices_ignore_the_direction() {
+        assert_eq!(
+            vertical_scale(55.0, -100.0).tick_indices(20.0),
+            vertical_scale(55.0, 100.0).tick_indices(20.0)
+        );
+    }
+}
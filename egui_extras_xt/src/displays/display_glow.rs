@@ -0,0 +1,79 @@
+use egui::{Color32, Painter, Pos2, Shape, Stroke};
+
+// ----------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DisplayGlow {
+    pub radius: f32,
+    pub intensity: f32,
+    /// Number of stacked translucent layers, zero disables the glow.
+    pub layers: usize,
+}
+
+impl Default for DisplayGlow {
+    fn default() -> Self {
+        Self {
+            radius: 6.0,
+            intensity: 0.3,
+            layers: 4,
+        }
+    }
+}
+
+impl DisplayGlow {
+    // Glow is faked by stacking progressively narrower translucent layers,
+    // the overlapping layers add up to a soft falloff towards the lit shape.
+    fn layers(&self, value: f32) -> impl Iterator<Item = (f32, f32)> {
+        // Zero layers yield an empty iterator, disabling the glow
+        let layer_count = self.layers;
+        let layer_alpha = (self.intensity * value).clamp(0.0, 1.0) / layer_count.max(1) as f32;
+        let radius = self.radius;
+
+        (0..layer_count).rev().map(move |layer| {
+            (
+                radius * (layer + 1) as f32 / layer_count as f32,
+                layer_alpha,
+            )
+        })
+    }
+
+    pub(crate) fn paint_polygon(
+        &self,
+        painter: &Painter,
+        points: &[Pos2],
+        color: Color32,
+        value: f32,
+    ) {
+        if value <= 0.0 {
+            return;
+        }
+
+        for (layer_radius, layer_alpha) in self.layers(value) {
+            painter.add(Shape::closed_line(
+                points.to_vec(),
+                Stroke::new(layer_radius * 2.0, color.linear_multiply(layer_alpha)),
+            ));
+        }
+    }
+
+    pub(crate) fn paint_circle(
+        &self,
+        painter: &Painter,
+        center: Pos2,
+        radius: f32,
+        color: Color32,
+        value: f32,
+    ) {
+        if value <= 0.0 {
+            return;
+        }
+
+        for (layer_radius, layer_alpha) in self.layers(value) {
+            painter.circle_filled(
+                center,
+                radius + layer_radius,
+                color.linear_multiply(layer_alpha),
+            );
+        }
+    }
+}
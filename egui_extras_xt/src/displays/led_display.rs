@@ -1,10 +1,88 @@
 use std::ops::RangeInclusive;
 
 use egui::{self, remap_clamp, Response, Sense, Ui, Widget};
-use emath::Vec2;
-use epaint::Stroke;
+use emath::{Pos2, Rot2, Vec2};
+use epaint::{Color32, Stroke};
 
-use crate::displays::{DisplayStyle, DisplayStylePreset};
+use crate::common::WidgetShape;
+use crate::displays::{DisplayGlow, DisplayStyle, DisplayStylePreset};
+
+// ----------------------------------------------------------------------------
+
+/// Paints the glow of a single LED. Widgets with multiple LEDs paint all the
+/// glows first, so they never bleed over the neighboring LEDs.
+pub(crate) fn paint_led_glow(
+    ui: &mut Ui,
+    center: Pos2,
+    diameter: f32,
+    shape: &WidgetShape,
+    color: Color32,
+    glow: DisplayGlow,
+    value: f32,
+) {
+    let radius = diameter / 2.0;
+
+    match shape {
+        WidgetShape::Circle => glow.paint_circle(ui.painter(), center, radius, color, value),
+        _ => glow.paint_polygon(
+            ui.painter(),
+            &shape.outline_points(center, radius, Rot2::IDENTITY),
+            color,
+            value,
+        ),
+    }
+}
+
+/// Paints a single LED, optionally overriding the active color of the style.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn paint_led(
+    ui: &mut Ui,
+    center: Pos2,
+    diameter: f32,
+    shape: &WidgetShape,
+    style: &DisplayStyle,
+    color: Option<Color32>,
+    glow: Option<DisplayGlow>,
+    value: f32,
+) {
+    let style = DisplayStyle {
+        active_foreground_color: color.unwrap_or(style.active_foreground_color),
+        ..*style
+    };
+
+    let radius = diameter / 2.0;
+
+    if let Some(glow) = glow {
+        paint_led_glow(
+            ui,
+            center,
+            diameter,
+            shape,
+            style.active_foreground_color,
+            glow,
+            value,
+        );
+    }
+
+    match shape {
+        WidgetShape::Circle => ui.painter().circle(
+            center,
+            radius,
+            style.foreground_color_blend(value),
+            style.foreground_stroke_blend(value),
+        ),
+        _ => shape.paint_shape(
+            ui,
+            center,
+            radius,
+            style.foreground_color_blend(value),
+            style.foreground_stroke_blend(value),
+            Rot2::IDENTITY,
+        ),
+    }
+}
+
+// ----------------------------------------------------------------------------
 
 #[must_use = "You should put this widget in an ui with `ui.add(widget);`"]
 pub struct LedDisplay {
@@ -12,7 +90,10 @@ pub struct LedDisplay {
     diameter: f32,
     padding: f32,
     range: RangeInclusive<f32>,
+    shape: WidgetShape,
+    color: Option<Color32>,
     style: DisplayStyle,
+    glow: Option<DisplayGlow>,
     animated: bool,
 }
 
@@ -23,7 +104,10 @@ impl LedDisplay {
             diameter: 16.0,
             padding: 0.25,
             range: 0.0..=1.0,
+            shape: WidgetShape::Circle,
+            color: None,
             style: DisplayStylePreset::Default.style(),
+            glow: None,
             animated: true,
         }
     }
@@ -32,6 +116,10 @@ impl LedDisplay {
         Self::new(if value { 1.0 } else { 0.0 })
     }
 
+    pub fn from_color(color: Color32) -> Self {
+        Self::new(1.0).color(Some(color))
+    }
+
     pub fn diameter(mut self, diameter: impl Into<f32>) -> Self {
         self.diameter = diameter.into();
         self
@@ -47,6 +135,17 @@ impl LedDisplay {
         self
     }
 
+    pub fn shape(mut self, shape: WidgetShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    /// Overrides the active color of the style, for multi-color LEDs.
+    pub fn color(mut self, color: Option<Color32>) -> Self {
+        self.color = color;
+        self
+    }
+
     pub fn style(mut self, style: DisplayStyle) -> Self {
         self.style = style;
         self
@@ -57,6 +156,11 @@ impl LedDisplay {
         self
     }
 
+    pub fn glow(mut self, glow: Option<DisplayGlow>) -> Self {
+        self.glow = glow;
+        self
+    }
+
     pub fn animated(mut self, animated: bool) -> Self {
         self.animated = animated;
         self
@@ -88,11 +192,15 @@ impl Widget for LedDisplay {
                 Stroke::NONE,
             );
 
-            ui.painter().circle(
+            paint_led(
+                ui,
                 rect.center(),
-                self.diameter / 2.0,
-                self.style.foreground_color_blend(value),
-                self.style.foreground_stroke_blend(value),
+                self.diameter,
+                &self.shape,
+                &self.style,
+                self.color,
+                self.glow,
+                value,
             );
         }
 
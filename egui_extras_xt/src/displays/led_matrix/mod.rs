@@ -0,0 +1,228 @@
+mod font;
+
+use egui::{vec2, Response, Sense, Ui, Widget};
+use emath::Vec2;
+use epaint::{Color32, Stroke};
+use itertools::Itertools;
+
+use crate::common::WidgetShape;
+use crate::displays::led_display::{paint_led, paint_led_glow};
+use crate::displays::{DisplayGlow, DisplayStyle, DisplayStylePreset};
+
+use self::font::{glyph, GLYPH_HEIGHT, GLYPH_WIDTH};
+
+// ----------------------------------------------------------------------------
+
+#[must_use = "You should put this widget in an ui with `ui.add(widget);`"]
+pub struct LedMatrix {
+    width: usize,
+    height: usize,
+    values: Vec<f32>,
+    colors: Option<Vec<Color32>>,
+    led_diameter: f32,
+    led_spacing: f32,
+    padding: f32,
+    shape: WidgetShape,
+    style: DisplayStyle,
+    glow: Option<DisplayGlow>,
+}
+
+impl LedMatrix {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            values: vec![0.0; width * height],
+            colors: None,
+            led_diameter: 8.0,
+            led_spacing: 2.0,
+            padding: 4.0,
+            shape: WidgetShape::Circle,
+            style: DisplayStylePreset::Default.style(),
+            glow: None,
+        }
+    }
+
+    /// Row-major buffer of LED intensities in the `0.0..=1.0` range.
+    pub fn from_intensities(width: usize, height: usize, values: &[f32]) -> Self {
+        assert_eq!(values.len(), width * height, "buffer size mismatch");
+
+        Self {
+            values: values.to_vec(),
+            ..Self::new(width, height)
+        }
+    }
+
+    /// Row-major buffer of LED colors, the alpha channel of the colors is
+    /// used as the LED intensity.
+    pub fn from_colors(width: usize, height: usize, colors: &[Color32]) -> Self {
+        assert_eq!(colors.len(), width * height, "buffer size mismatch");
+
+        Self {
+            values: colors
+                .iter()
+                .map(|color| color.a() as f32 / 255.0)
+                .collect(),
+            colors: Some(
+                colors
+                    .iter()
+                    .map(|color| {
+                        let [r, g, b, _] = color.to_srgba_unmultiplied();
+                        Color32::from_rgb(r, g, b)
+                    })
+                    .collect(),
+            ),
+            ..Self::new(width, height)
+        }
+    }
+
+    /// Row-major buffer of lit and unlit LEDs.
+    pub fn from_bitmap(width: usize, height: usize, bitmap: &[bool]) -> Self {
+        assert_eq!(bitmap.len(), width * height, "buffer size mismatch");
+
+        Self {
+            values: bitmap
+                .iter()
+                .map(|&lit| if lit { 1.0 } else { 0.0 })
+                .collect(),
+            ..Self::new(width, height)
+        }
+    }
+
+    /// Renders `text` with the built-in 5x7 font into a matrix `width` LEDs
+    /// wide, starting at column `offset`. The text wraps around after
+    /// scrolling out of the matrix, the length of a full scroll cycle is
+    /// `LedMatrix::text_width(text) + width` columns.
+    pub fn from_text(text: &str, width: usize, offset: isize) -> Self {
+        let columns = text
+            .chars()
+            .flat_map(|c| glyph(c).into_iter().chain([0x00]))
+            .collect::<Vec<_>>();
+
+        let cycle_length = (columns.len() + width) as isize;
+
+        let mut values = vec![0.0; width * GLYPH_HEIGHT];
+        for x in 0..width {
+            let column_index = (x as isize + offset).rem_euclid(cycle_length) as usize;
+
+            if let Some(column) = columns.get(column_index) {
+                for y in 0..GLYPH_HEIGHT {
+                    if (column >> y) & 1 != 0 {
+                        values[y * width + x] = 1.0;
+                    }
+                }
+            }
+        }
+
+        Self::from_intensities(width, GLYPH_HEIGHT, &values)
+    }
+
+    /// Width of `text` in columns when rendered with the built-in font.
+    pub fn text_width(text: &str) -> usize {
+        text.chars().count() * (GLYPH_WIDTH + 1)
+    }
+
+    pub fn led_diameter(mut self, led_diameter: impl Into<f32>) -> Self {
+        self.led_diameter = led_diameter.into();
+        self
+    }
+
+    pub fn led_spacing(mut self, led_spacing: impl Into<f32>) -> Self {
+        self.led_spacing = led_spacing.into();
+        self
+    }
+
+    pub fn padding(mut self, padding: impl Into<f32>) -> Self {
+        self.padding = padding.into();
+        self
+    }
+
+    pub fn shape(mut self, shape: WidgetShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    pub fn style(mut self, style: DisplayStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn style_preset(mut self, preset: DisplayStylePreset) -> Self {
+        self.style = preset.style();
+        self
+    }
+
+    pub fn glow(mut self, glow: Option<DisplayGlow>) -> Self {
+        self.glow = glow;
+        self
+    }
+}
+
+impl Widget for LedMatrix {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let led_pitch = self.led_diameter + self.led_spacing;
+
+        let desired_size = vec2(
+            led_pitch * self.width as f32 - self.led_spacing,
+            led_pitch * self.height as f32 - self.led_spacing,
+        )
+        .max(Vec2::ZERO)
+            + Vec2::splat(self.padding * 2.0);
+
+        let (rect, response) = ui.allocate_exact_size(desired_size, Sense::hover());
+
+        if ui.is_rect_visible(rect) {
+            ui.painter().rect(
+                rect,
+                ui.style().visuals.noninteractive().rounding,
+                self.style.background_color,
+                Stroke::NONE,
+            );
+
+            let origin = rect.left_top() + Vec2::splat(self.padding + self.led_diameter / 2.0);
+
+            let leds = (0..self.height)
+                .cartesian_product(0..self.width)
+                .map(|(y, x)| {
+                    let index = y * self.width + x;
+                    (
+                        origin + vec2(x as f32, y as f32) * led_pitch,
+                        self.colors.as_ref().map(|colors| colors[index]),
+                        self.values[index].clamp(0.0, 1.0),
+                    )
+                })
+                .collect_vec();
+
+            // Glows are painted in a separate pass underneath all LEDs, so they
+            // never bleed over the neighboring LEDs.
+            if let Some(glow) = self.glow {
+                for &(center, color, value) in &leds {
+                    paint_led_glow(
+                        ui,
+                        center,
+                        self.led_diameter,
+                        &self.shape,
+                        color.unwrap_or(self.style.active_foreground_color),
+                        glow,
+                        value,
+                    );
+                }
+            }
+
+            for (center, color, value) in leds {
+                paint_led(
+                    ui,
+                    center,
+                    self.led_diameter,
+                    &self.shape,
+                    &self.style,
+                    color,
+                    None,
+                    value,
+                );
+            }
+        }
+
+        response
+    }
+}
@@ -0,0 +1,296 @@
+use std::ops::RangeInclusive;
+
+use egui::{self, remap_clamp, vec2, Rect, Response, Sense, Ui, Widget};
+use emath::Vec2;
+use epaint::Stroke;
+use itertools::Itertools;
+
+use crate::common::LinearOrientation;
+use crate::displays::peak_hold::update_peaks;
+use crate::displays::{DisplayGlow, DisplayStyle, DisplayStylePreset};
+
+// ----------------------------------------------------------------------------
+
+#[must_use = "You should put this widget in an ui with `ui.add(widget);`"]
+pub struct LedMeter {
+    values: Vec<f32>,
+    orientation: LinearOrientation,
+    led_count: usize,
+    led_length: f32,
+    led_thickness: f32,
+    led_spacing: f32,
+    channel_spacing: f32,
+    padding: f32,
+    range: RangeInclusive<f32>,
+    decibels: bool,
+    zones: Vec<(f32, DisplayStyle)>,
+    peak_hold: Option<f32>,
+    peak_decay_time: f32,
+    style: DisplayStyle,
+    glow: Option<DisplayGlow>,
+}
+
+impl LedMeter {
+    pub fn new(value: f32) -> Self {
+        Self::from_values(&[value])
+    }
+
+    pub fn stereo(left: f32, right: f32) -> Self {
+        Self::from_values(&[left, right])
+    }
+
+    pub fn from_values(values: &[f32]) -> Self {
+        Self {
+            values: values.to_vec(),
+            orientation: LinearOrientation::Vertical,
+            led_count: 16,
+            led_length: 6.0,
+            led_thickness: 16.0,
+            led_spacing: 2.0,
+            channel_spacing: 4.0,
+            padding: 4.0,
+            range: 0.0..=1.0,
+            decibels: false,
+            zones: Vec::new(),
+            peak_hold: Some(1.0),
+            peak_decay_time: 2.0,
+            style: DisplayStylePreset::Default.style(),
+            glow: None,
+        }
+    }
+
+    pub fn orientation(mut self, orientation: LinearOrientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    pub fn led_count(mut self, led_count: usize) -> Self {
+        assert!(led_count > 0);
+        self.led_count = led_count;
+        self
+    }
+
+    pub fn led_length(mut self, led_length: impl Into<f32>) -> Self {
+        self.led_length = led_length.into();
+        self
+    }
+
+    pub fn led_thickness(mut self, led_thickness: impl Into<f32>) -> Self {
+        self.led_thickness = led_thickness.into();
+        self
+    }
+
+    pub fn led_spacing(mut self, led_spacing: impl Into<f32>) -> Self {
+        self.led_spacing = led_spacing.into();
+        self
+    }
+
+    pub fn channel_spacing(mut self, channel_spacing: impl Into<f32>) -> Self {
+        self.channel_spacing = channel_spacing.into();
+        self
+    }
+
+    pub fn padding(mut self, padding: impl Into<f32>) -> Self {
+        self.padding = padding.into();
+        self
+    }
+
+    /// Displayed range, in decibels when the decibel scale is enabled.
+    pub fn range(mut self, range: RangeInclusive<f32>) -> Self {
+        self.range = range;
+        self
+    }
+
+    /// Interpret the values as linear amplitudes and display them in decibels.
+    pub fn decibels(mut self, decibels: bool) -> Self {
+        self.decibels = decibels;
+        self
+    }
+
+    /// Adds a colored zone starting at `start`, measured in the units of the
+    /// displayed range. Without any zones added, the meter gets the usual
+    /// green, amber and red zones at 0%, 70% and 90% of the range.
+    pub fn zone(mut self, start: f32, style: DisplayStyle) -> Self {
+        self.zones.push((start, style));
+        self
+    }
+
+    /// Time in seconds the peak markers are held before decaying.
+    pub fn peak_hold(mut self, peak_hold: Option<f32>) -> Self {
+        self.peak_hold = peak_hold;
+        self
+    }
+
+    /// Time in seconds the peak markers take to decay through the whole range.
+    pub fn peak_decay_time(mut self, peak_decay_time: impl Into<f32>) -> Self {
+        self.peak_decay_time = peak_decay_time.into();
+        self
+    }
+
+    pub fn style(mut self, style: DisplayStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn style_preset(mut self, preset: DisplayStylePreset) -> Self {
+        self.style = preset.style();
+        self
+    }
+
+    pub fn glow(mut self, glow: Option<DisplayGlow>) -> Self {
+        self.glow = glow;
+        self
+    }
+}
+
+impl Widget for LedMeter {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let channel_count = self.values.len();
+
+        let bar_length = (self.led_length * self.led_count as f32)
+            + (self.led_spacing * self.led_count.saturating_sub(1) as f32);
+        let bars_thickness = (self.led_thickness * channel_count as f32)
+            + (self.channel_spacing * channel_count.saturating_sub(1) as f32);
+
+        let desired_size = match self.orientation {
+            LinearOrientation::Horizontal => vec2(bar_length, bars_thickness),
+            LinearOrientation::Vertical => vec2(bars_thickness, bar_length),
+        } + Vec2::splat(self.padding * 2.0);
+
+        let (rect, response) = ui.allocate_exact_size(desired_size, Sense::hover());
+
+        // Normalized bar positions of the values and their held peaks
+        let levels = self
+            .values
+            .iter()
+            .map(|&value| {
+                let value = if self.decibels {
+                    20.0 * value.abs().max(f32::MIN_POSITIVE).log10()
+                } else {
+                    value
+                };
+
+                remap_clamp(value, self.range.clone(), 0.0..=1.0)
+            })
+            .collect_vec();
+
+        let peaks = match self.peak_hold {
+            Some(peak_hold) => {
+                update_peaks(ui, response.id, &levels, peak_hold, self.peak_decay_time)
+                    .into_iter()
+                    .map(Some)
+                    .collect_vec()
+            }
+            None => vec![None; levels.len()],
+        };
+
+        if ui.is_rect_visible(rect) {
+            ui.painter().rect(
+                rect,
+                ui.style().visuals.noninteractive().rounding,
+                self.style.background_color,
+                Stroke::NONE,
+            );
+
+            let zones = if self.zones.is_empty() {
+                let range_value = |t| egui::lerp(self.range.clone(), t);
+                vec![
+                    (range_value(0.0), DisplayStylePreset::DeLoreanGreen.style()),
+                    (range_value(0.7), DisplayStylePreset::DeLoreanAmber.style()),
+                    (range_value(0.9), DisplayStylePreset::DeLoreanRed.style()),
+                ]
+            } else {
+                self.zones
+                    .iter()
+                    .copied()
+                    .sorted_by(|(a, _), (b, _)| a.total_cmp(b))
+                    .collect_vec()
+            };
+
+            let led_style = |led_index: usize| {
+                let led_value =
+                    egui::lerp(self.range.clone(), led_index as f32 / self.led_count as f32);
+
+                zones
+                    .iter()
+                    .rev()
+                    .find(|(start, _)| *start <= led_value)
+                    .map_or(self.style, |(_, style)| *style)
+            };
+
+            let led_rect = |channel_index: usize, led_index: usize| {
+                let along = (self.led_length + self.led_spacing) * led_index as f32;
+                let across = (self.led_thickness + self.channel_spacing) * channel_index as f32;
+
+                let inner_rect = rect.shrink(self.padding);
+
+                match self.orientation {
+                    LinearOrientation::Horizontal => Rect::from_min_size(
+                        inner_rect.left_top() + vec2(along, across),
+                        vec2(self.led_length, self.led_thickness),
+                    ),
+                    LinearOrientation::Vertical => Rect::from_min_size(
+                        inner_rect.left_bottom() + vec2(across, -along - self.led_length),
+                        vec2(self.led_thickness, self.led_length),
+                    ),
+                }
+            };
+
+            let leds = levels
+                .iter()
+                .zip(peaks.iter())
+                .enumerate()
+                .flat_map(|(channel_index, (level, peak))| {
+                    let peak_led_index = peak.filter(|&peak| peak > 0.0).map(|peak| {
+                        ((peak * self.led_count as f32).ceil() as usize).clamp(1, self.led_count)
+                            - 1
+                    });
+
+                    (0..self.led_count).map(move |led_index| {
+                        let mut value =
+                            (level * self.led_count as f32 - led_index as f32).clamp(0.0, 1.0);
+
+                        if peak_led_index == Some(led_index) {
+                            value = 1.0;
+                        }
+
+                        (
+                            led_rect(channel_index, led_index),
+                            led_style(led_index),
+                            value,
+                        )
+                    })
+                })
+                .collect_vec();
+
+            // Glows are painted in a separate pass underneath all LEDs, so they
+            // never bleed over the neighboring LEDs.
+            if let Some(glow) = self.glow {
+                for (led_rect, style, value) in &leds {
+                    glow.paint_polygon(
+                        ui.painter(),
+                        &[
+                            led_rect.left_top(),
+                            led_rect.right_top(),
+                            led_rect.right_bottom(),
+                            led_rect.left_bottom(),
+                        ],
+                        style.active_foreground_color,
+                        *value,
+                    );
+                }
+            }
+
+            for (led_rect, style, value) in leds {
+                ui.painter().rect(
+                    led_rect,
+                    1.0,
+                    style.foreground_color_blend(value),
+                    style.foreground_stroke_blend(value),
+                );
+            }
+        }
+
+        response
+    }
+}
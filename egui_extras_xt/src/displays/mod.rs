@@ -1,12 +1,42 @@
-mod display_style;
+mod display_glow;
 mod indicator_button;
 mod led_display;
+mod led_matrix;
+mod led_meter;
+mod panel_meter;
+mod peak_hold;
+mod sample_format;
+mod spectrogram;
+mod spectrum;
+mod spectrum_analyzer;
 mod waveform_display;
+mod waveform_ring_buffer;
+mod waveform_summary;
+
+#[cfg(feature = "wav")]
+mod wav_file;
 
 pub mod segmented_display;
 
-pub use display_style::{DisplayStyle, DisplayStylePreset};
+pub use crate::display_style::{DisplayStyle, DisplayStylePreset};
+pub use display_glow::DisplayGlow;
 pub use indicator_button::{IndicatorButton, IndicatorButtonBehavior};
 pub use led_display::LedDisplay;
-pub use segmented_display::{DisplayKind, DisplayMetrics, SegmentedDisplayWidget};
-pub use waveform_display::{BufferLayout, SignalEdge, WaveformDisplayWidget};
+pub use led_matrix::LedMatrix;
+pub use led_meter::LedMeter;
+pub use panel_meter::{PanelMeter, PanelMeterBallistics, PanelMeterLabels};
+pub use sample_format::{Endianness, SampleFormat};
+pub use segmented_display::{
+    DisplayKind, DisplayMetrics, EditableSegmentedDisplayWidget, SegmentedDisplayWidget,
+};
+pub use spectrogram::SpectrogramWidget;
+pub use spectrum_analyzer::{SpectrumAnalyzerWidget, SpectrumMode};
+pub use waveform_display::{
+    BufferLayout, ChannelLayout, SampleRange, SignalEdge, TriggerEdge, TriggerMode,
+    WaveformDisplayMode, WaveformDisplayWidget,
+};
+pub use waveform_ring_buffer::{AtomicSample, WaveformRingBuffer};
+pub use waveform_summary::WaveformSummary;
+
+#[cfg(feature = "wav")]
+pub use wav_file::{WavError, WavFile};
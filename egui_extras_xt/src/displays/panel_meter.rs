@@ -0,0 +1,423 @@
+use std::f32::consts::{PI, TAU};
+use std::ops::RangeInclusive;
+
+use egui::{
+    self, lerp, remap_clamp, vec2, Align2, Color32, FontFamily, FontId, Id, Pos2, Response, Sense,
+    Shape, Stroke, Ui, Vec2, Widget,
+};
+use emath::Rot2;
+use itertools::Itertools;
+use strum::{Display, EnumIter};
+
+use crate::common::{RotatedText, WidgetShape};
+use crate::displays::led_display::paint_led;
+use crate::displays::{DisplayGlow, DisplayStyle, DisplayStylePreset};
+
+// ----------------------------------------------------------------------------
+
+pub struct PanelMeterLabels {
+    pub(crate) inner: Vec<String>,
+}
+
+impl<const N: usize> From<[&str; N]> for PanelMeterLabels {
+    fn from(source: [&str; N]) -> Self {
+        PanelMeterLabels {
+            inner: source.map(String::from).to_vec(),
+        }
+    }
+}
+
+impl PanelMeterLabels {
+    pub fn from_slice<T>(source: &[T]) -> PanelMeterLabels
+    where
+        T: ToString,
+    {
+        PanelMeterLabels {
+            inner: source.iter().map(T::to_string).collect(),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, Display, EnumIter, Eq, PartialEq)]
+pub enum PanelMeterBallistics {
+    /// The needle follows the value immediately.
+    #[strum(to_string = "Instant")]
+    Instant,
+
+    /// Volume unit meter, rising and falling in about 300 ms.
+    #[strum(to_string = "VU")]
+    Vu,
+
+    /// Peak programme meter, rising almost immediately and falling slowly.
+    #[strum(to_string = "PPM")]
+    Ppm,
+}
+
+impl PanelMeterBallistics {
+    /// Attack and release time constants in seconds.
+    fn time_constants(self) -> (f32, f32) {
+        match self {
+            PanelMeterBallistics::Instant => (0.0, 0.0),
+            PanelMeterBallistics::Vu => (0.065, 0.065),
+            PanelMeterBallistics::Ppm => (0.002, 0.65),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[must_use = "You should put this widget in an ui with `ui.add(widget);`"]
+pub struct PanelMeter {
+    value: f32,
+    range: RangeInclusive<f32>,
+    decibels: bool,
+    radius: f32,
+    sweep: f32,
+    labels: Option<PanelMeterLabels>,
+    label_height: f32,
+    minor_ticks: usize,
+    zones: Vec<(f32, Color32)>,
+    ballistics: PanelMeterBallistics,
+    peak_led: Option<f32>,
+    peak_led_hold: f32,
+    peak_led_style: DisplayStyle,
+    padding: f32,
+    style: DisplayStyle,
+    glow: Option<DisplayGlow>,
+}
+
+impl PanelMeter {
+    pub fn new(value: f32) -> Self {
+        Self {
+            value,
+            range: 0.0..=1.0,
+            decibels: false,
+            radius: 64.0,
+            sweep: TAU / 4.0,
+            labels: None,
+            label_height: 10.0,
+            minor_ticks: 4,
+            zones: Vec::new(),
+            ballistics: PanelMeterBallistics::Vu,
+            peak_led: None,
+            peak_led_hold: 1.0,
+            peak_led_style: DisplayStylePreset::DeLoreanRed.style(),
+            padding: 4.0,
+            style: DisplayStylePreset::Default.style(),
+            glow: None,
+        }
+    }
+
+    /// Displayed range, in decibels when the decibel scale is enabled.
+    pub fn range(mut self, range: RangeInclusive<f32>) -> Self {
+        self.range = range;
+        self
+    }
+
+    /// Interpret the value as a linear amplitude and display it in decibels.
+    pub fn decibels(mut self, decibels: bool) -> Self {
+        self.decibels = decibels;
+        self
+    }
+
+    /// Length of the needle up to the scale arc.
+    pub fn radius(mut self, radius: impl Into<f32>) -> Self {
+        self.radius = radius.into();
+        self
+    }
+
+    /// Angle covered by the scale in radians, centered around the top.
+    pub fn sweep(mut self, sweep: impl Into<f32>) -> Self {
+        let sweep = sweep.into();
+        assert!(sweep > 0.0 && sweep <= TAU * 0.75);
+        self.sweep = sweep;
+        self
+    }
+
+    /// Labels of the major ticks, evenly spaced along the scale. Defaults to
+    /// five labels showing the values of the range.
+    pub fn labels(mut self, labels: PanelMeterLabels) -> Self {
+        self.labels = Some(labels);
+        self
+    }
+
+    pub fn label_height(mut self, label_height: impl Into<f32>) -> Self {
+        self.label_height = label_height.into();
+        self
+    }
+
+    /// Number of minor ticks between two major ticks.
+    pub fn minor_ticks(mut self, minor_ticks: usize) -> Self {
+        self.minor_ticks = minor_ticks;
+        self
+    }
+
+    /// Adds a colored zone along the scale starting at `start`, measured in
+    /// the units of the displayed range. Without any zones added, the last
+    /// fifth of the scale is marked red.
+    pub fn zone(mut self, start: f32, color: Color32) -> Self {
+        self.zones.push((start, color));
+        self
+    }
+
+    pub fn ballistics(mut self, ballistics: PanelMeterBallistics) -> Self {
+        self.ballistics = ballistics;
+        self
+    }
+
+    /// Lights the peak LED when the value reaches `threshold`, measured in
+    /// the units of the displayed range.
+    pub fn peak_led(mut self, threshold: Option<f32>) -> Self {
+        self.peak_led = threshold;
+        self
+    }
+
+    /// Time in seconds the peak LED stays lit after the value falls below
+    /// the threshold.
+    pub fn peak_led_hold(mut self, peak_led_hold: impl Into<f32>) -> Self {
+        self.peak_led_hold = peak_led_hold.into();
+        self
+    }
+
+    pub fn peak_led_style(mut self, peak_led_style: DisplayStyle) -> Self {
+        self.peak_led_style = peak_led_style;
+        self
+    }
+
+    pub fn padding(mut self, padding: impl Into<f32>) -> Self {
+        self.padding = padding.into();
+        self
+    }
+
+    pub fn style(mut self, style: DisplayStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn style_preset(mut self, preset: DisplayStylePreset) -> Self {
+        self.style = preset.style();
+        self
+    }
+
+    pub fn glow(mut self, glow: Option<DisplayGlow>) -> Self {
+        self.glow = glow;
+        self
+    }
+
+    fn update_needle(&self, ui: &Ui, id: Id, level: f32) -> f32 {
+        let dt = ui.input(|input| input.stable_dt);
+
+        // Analog needles start from rest
+        let position = ui
+            .memory_mut(|memory| memory.data.get_temp::<f32>(id))
+            .unwrap_or(0.0);
+
+        let (attack, release) = self.ballistics.time_constants();
+        let time_constant = if level > position { attack } else { release };
+
+        let position = if time_constant > 0.0 {
+            position + (level - position) * (1.0 - (-dt / time_constant).exp())
+        } else {
+            level
+        };
+
+        if (position - level).abs() > 0.0001 {
+            ui.ctx().request_repaint();
+        }
+
+        ui.memory_mut(|memory| memory.data.insert_temp(id, position));
+        position
+    }
+
+    fn update_peak_led(&self, ui: &Ui, id: Id, value: f32, threshold: f32) -> bool {
+        let time = ui.input(|input| input.time);
+
+        let mut over_time = ui.memory_mut(|memory| memory.data.get_temp::<f64>(id));
+
+        if value >= threshold {
+            over_time = Some(time);
+        }
+
+        let lit = over_time.map_or(false, |over_time| {
+            time - over_time < self.peak_led_hold as f64
+        });
+
+        if lit {
+            ui.ctx().request_repaint();
+        }
+
+        if let Some(over_time) = over_time {
+            ui.memory_mut(|memory| memory.data.insert_temp(id, over_time));
+        }
+
+        lit
+    }
+}
+
+impl Widget for PanelMeter {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let major_tick_length = 6.0;
+        let minor_tick_length = 3.0;
+        let pivot_radius = 3.0;
+
+        let half_sweep = self.sweep / 2.0;
+        let outer_radius = self.radius + major_tick_length + self.label_height + 2.0;
+
+        // Scale wider than a half circle reaches below the pivot
+        let horizontal_extent = outer_radius * half_sweep.min(PI / 2.0).sin();
+        let bottom_extent = (-outer_radius * half_sweep.cos()).max(pivot_radius * 2.0);
+
+        let desired_size = vec2(horizontal_extent * 2.0, outer_radius + bottom_extent)
+            + Vec2::splat(self.padding * 2.0);
+
+        let (rect, response) = ui.allocate_exact_size(desired_size, Sense::hover());
+
+        let value = if self.decibels {
+            20.0 * self.value.abs().max(f32::MIN_POSITIVE).log10()
+        } else {
+            self.value
+        };
+
+        let level = remap_clamp(value, self.range.clone(), 0.0..=1.0);
+        let needle_position = self.update_needle(ui, response.id.with("needle"), level);
+
+        let peak_led_lit = self.peak_led.map(|threshold| {
+            self.update_peak_led(ui, response.id.with("peak_led"), value, threshold)
+        });
+
+        if ui.is_rect_visible(rect) {
+            let pivot = Pos2::new(rect.center().x, rect.top() + self.padding + outer_radius);
+
+            // Screen angle of a normalized position along the scale
+            let scale_angle = |t: f32| -PI / 2.0 + lerp(-half_sweep..=half_sweep, t);
+            let scale_point = |t: f32, radius: f32| pivot + Vec2::angled(scale_angle(t)) * radius;
+
+            let foreground_stroke = Stroke::new(1.0, self.style.active_foreground_color);
+
+            ui.painter().rect(
+                rect,
+                ui.style().visuals.noninteractive().rounding,
+                self.style.background_color,
+                Stroke::NONE,
+            );
+
+            // Zones
+            let zones = if self.zones.is_empty() {
+                vec![(lerp(self.range.clone(), 0.8), Color32::RED)]
+            } else {
+                self.zones
+                    .iter()
+                    .copied()
+                    .sorted_by(|(a, _), (b, _)| a.total_cmp(b))
+                    .collect_vec()
+            };
+
+            for (zone_index, &(zone_start, zone_color)) in zones.iter().enumerate() {
+                let zone_end = zones
+                    .get(zone_index + 1)
+                    .map_or(*self.range.end(), |(next_zone_start, _)| *next_zone_start);
+
+                let zone_range = remap_clamp(zone_start, self.range.clone(), 0.0..=1.0)
+                    ..=remap_clamp(zone_end, self.range.clone(), 0.0..=1.0);
+
+                if zone_range.start() < zone_range.end() {
+                    WidgetShape::Circle.paint_arc(
+                        ui,
+                        pivot,
+                        self.radius - minor_tick_length,
+                        self.radius,
+                        scale_angle(*zone_range.start()),
+                        scale_angle(*zone_range.end()),
+                        zone_color,
+                        Stroke::NONE,
+                        Rot2::IDENTITY,
+                    );
+                }
+            }
+
+            // Scale
+            ui.painter().add(Shape::line(
+                (0..=64)
+                    .map(|index| scale_point(index as f32 / 64.0, self.radius))
+                    .collect_vec(),
+                foreground_stroke,
+            ));
+
+            let labels = self.labels.map(|labels| labels.inner).unwrap_or_else(|| {
+                (0..5)
+                    .map(|index| {
+                        let label_value = lerp(self.range.clone(), index as f32 / 4.0);
+                        format!("{}", (label_value * 100.0).round() / 100.0)
+                    })
+                    .collect_vec()
+            });
+
+            let major_intervals = labels.len().saturating_sub(1).max(1);
+            let tick_count = major_intervals * (self.minor_ticks + 1);
+
+            for tick_index in 0..=tick_count {
+                let t = tick_index as f32 / tick_count as f32;
+                let tick_length = if tick_index % (self.minor_ticks + 1) == 0 {
+                    major_tick_length
+                } else {
+                    minor_tick_length
+                };
+
+                ui.painter().line_segment(
+                    [
+                        scale_point(t, self.radius),
+                        scale_point(t, self.radius + tick_length),
+                    ],
+                    foreground_stroke,
+                );
+            }
+
+            for (label_index, label) in labels.iter().enumerate() {
+                let t = label_index as f32 / major_intervals as f32;
+
+                ui.painter().rotated_text(
+                    scale_point(
+                        t,
+                        self.radius + major_tick_length + 1.0 + self.label_height / 2.0,
+                    ),
+                    Align2::CENTER_CENTER,
+                    label,
+                    FontId::new(self.label_height, FontFamily::Proportional),
+                    self.style.active_foreground_color,
+                    scale_angle(t) + (TAU / 4.0),
+                );
+            }
+
+            // Needle
+            ui.painter().line_segment(
+                [
+                    pivot,
+                    scale_point(needle_position, self.radius + major_tick_length),
+                ],
+                Stroke::new(1.5, self.style.active_foreground_color),
+            );
+            ui.painter()
+                .circle_filled(pivot, pivot_radius, self.style.active_foreground_color);
+
+            // Peak LED
+            if let Some(peak_led_lit) = peak_led_lit {
+                let led_diameter = self.label_height;
+
+                paint_led(
+                    ui,
+                    rect.right_top() + vec2(-1.0, 1.0) * (self.padding + led_diameter / 2.0),
+                    led_diameter,
+                    &WidgetShape::Circle,
+                    &self.peak_led_style,
+                    None,
+                    self.glow,
+                    if peak_led_lit { 1.0 } else { 0.0 },
+                );
+            }
+        }
+
+        response
+    }
+}
@@ -0,0 +1,57 @@
+use egui::{Id, Ui};
+
+// ----------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug)]
+struct PeakState {
+    value: f32,
+    time: f64,
+}
+
+/// Follows the normalized `levels` with peaks held in memory under `id`,
+/// returning the peak values. Peaks are held for `peak_hold` seconds, then
+/// take `peak_decay_time` seconds to decay through the whole range.
+pub(crate) fn update_peaks(
+    ui: &Ui,
+    id: Id,
+    levels: &[f32],
+    peak_hold: f32,
+    peak_decay_time: f32,
+) -> Vec<f32> {
+    let (time, dt) = ui.input(|input| (input.time, input.stable_dt));
+
+    let mut peaks = ui
+        .memory_mut(|memory| memory.data.get_temp::<Vec<PeakState>>(id))
+        .filter(|peaks| peaks.len() == levels.len())
+        .unwrap_or_else(|| {
+            levels
+                .iter()
+                .map(|&level| PeakState { value: level, time })
+                .collect()
+        });
+
+    for (peak, &level) in peaks.iter_mut().zip(levels) {
+        if level >= peak.value {
+            *peak = PeakState { value: level, time };
+        } else if time - peak.time > peak_hold as f64 {
+            let decay = if peak_decay_time > 0.0 {
+                dt / peak_decay_time
+            } else {
+                1.0
+            };
+            peak.value = (peak.value - decay).max(level);
+        }
+    }
+
+    if peaks
+        .iter()
+        .zip(levels)
+        .any(|(peak, &level)| peak.value > level)
+    {
+        ui.ctx().request_repaint();
+    }
+
+    let peak_values = peaks.iter().map(|peak| peak.value).collect();
+    ui.memory_mut(|memory| memory.data.insert_temp(id, peaks));
+    peak_values
+}
@@ -0,0 +1,244 @@
+use strum::{Display, EnumIter};
+
+// ----------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, Display, EnumIter, Eq, PartialEq)]
+pub enum Endianness {
+    #[strum(to_string = "Little endian")]
+    Little,
+
+    #[strum(to_string = "Big endian")]
+    Big,
+}
+
+/// Encoding of the samples in a byte buffer, eg. the data chunk of a WAV file.
+#[derive(Clone, Copy, Debug, Display, EnumIter, Eq, PartialEq)]
+pub enum SampleFormat {
+    #[strum(to_string = "8-bit unsigned")]
+    U8,
+
+    #[strum(to_string = "8-bit signed")]
+    I8,
+
+    #[strum(to_string = "16-bit signed")]
+    I16,
+
+    /// Packed into three bytes per sample.
+    #[strum(to_string = "24-bit signed")]
+    I24,
+
+    #[strum(to_string = "32-bit signed")]
+    I32,
+
+    #[strum(to_string = "32-bit float")]
+    F32,
+
+    #[strum(to_string = "64-bit float")]
+    F64,
+}
+
+impl SampleFormat {
+    pub fn bytes_per_sample(self) -> usize {
+        match self {
+            SampleFormat::U8 | SampleFormat::I8 => 1,
+            SampleFormat::I16 => 2,
+            SampleFormat::I24 => 3,
+            SampleFormat::I32 | SampleFormat::F32 => 4,
+            SampleFormat::F64 => 8,
+        }
+    }
+
+    /// Decodes `bytes` into samples in the `-1.0..=1.0` range, keeping their
+    /// channel layout. Trailing bytes not making up a whole sample are ignored.
+    ///
+    /// ```
+    /// use egui_extras_xt::displays::{Endianness, SampleFormat};
+    ///
+    /// let bytes = [0x00, 0x00, 0x80, 0x00, 0x00, 0x40];
+    /// let samples = SampleFormat::I24.decode(&bytes, Endianness::Little);
+    /// assert_eq!(samples, [-1.0, 0.5]);
+    /// ```
+    pub fn decode(self, bytes: &[u8], endianness: Endianness) -> Vec<f32> {
+        bytes
+            .chunks_exact(self.bytes_per_sample())
+            .map(|sample_bytes| {
+                // Most significant byte first
+                let mut sample_bytes = {
+                    let mut tmp = [0; 8];
+                    tmp[..sample_bytes.len()].copy_from_slice(sample_bytes);
+                    tmp
+                };
+
+                if endianness == Endianness::Little {
+                    sample_bytes[..self.bytes_per_sample()].reverse();
+                }
+
+                match self {
+                    SampleFormat::U8 => (sample_bytes[0] as f32 - 128.0) / 128.0,
+                    SampleFormat::I8 => sample_bytes[0] as i8 as f32 / 128.0,
+                    SampleFormat::I16 => {
+                        i16::from_be_bytes([sample_bytes[0], sample_bytes[1]]) as f32 / 32768.0
+                    }
+                    SampleFormat::I24 => {
+                        // Sign extended by shifting down from the top of an i32
+                        (i32::from_be_bytes([sample_bytes[0], sample_bytes[1], sample_bytes[2], 0])
+                            >> 8) as f32
+                            / 8388608.0
+                    }
+                    SampleFormat::I32 => {
+                        i32::from_be_bytes([
+                            sample_bytes[0],
+                            sample_bytes[1],
+                            sample_bytes[2],
+                            sample_bytes[3],
+                        ]) as f32
+                            / 2147483648.0
+                    }
+                    SampleFormat::F32 => f32::from_be_bytes([
+                        sample_bytes[0],
+                        sample_bytes[1],
+                        sample_bytes[2],
+                        sample_bytes[3],
+                    ]),
+                    SampleFormat::F64 => f64::from_be_bytes(sample_bytes) as f32,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator;
+
+    use super::{Endianness, SampleFormat};
+
+    /// Big endian encodings of full scale negative, zero, half scale positive
+    /// and full scale positive samples.
+    fn big_endian_fixture(sample_format: SampleFormat) -> (Vec<u8>, Vec<f32>) {
+        match sample_format {
+            SampleFormat::U8 => (
+                vec![0x00, 0x80, 0xC0, 0xFF],
+                vec![-1.0, 0.0, 0.5, 127.0 / 128.0],
+            ),
+            SampleFormat::I8 => (
+                vec![0x80, 0x00, 0x40, 0x7F],
+                vec![-1.0, 0.0, 0.5, 127.0 / 128.0],
+            ),
+            SampleFormat::I16 => (
+                vec![0x80, 0x00, 0x00, 0x00, 0x40, 0x00, 0x7F, 0xFF],
+                vec![-1.0, 0.0, 0.5, 32767.0 / 32768.0],
+            ),
+            SampleFormat::I24 => (
+                vec![
+                    0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0x00, 0x00, 0x7F, 0xFF, 0xFF,
+                ],
+                vec![-1.0, 0.0, 0.5, 8388607.0 / 8388608.0],
+            ),
+            SampleFormat::I32 => (
+                [
+                    i32::MIN.to_be_bytes(),
+                    0i32.to_be_bytes(),
+                    0x4000_0000i32.to_be_bytes(),
+                    i32::MAX.to_be_bytes(),
+                ]
+                .concat(),
+                vec![-1.0, 0.0, 0.5, 1.0],
+            ),
+            SampleFormat::F32 => (
+                [-1.0f32, 0.0, 0.5, 1.0]
+                    .iter()
+                    .flat_map(|sample| sample.to_be_bytes())
+                    .collect(),
+                vec![-1.0, 0.0, 0.5, 1.0],
+            ),
+            SampleFormat::F64 => (
+                [-1.0f64, 0.0, 0.5, 1.0]
+                    .iter()
+                    .flat_map(|sample| sample.to_be_bytes())
+                    .collect(),
+                vec![-1.0, 0.0, 0.5, 1.0],
+            ),
+        }
+    }
+
+    fn little_endian(bytes: &[u8], sample_format: SampleFormat) -> Vec<u8> {
+        bytes
+            .chunks_exact(sample_format.bytes_per_sample())
+            .flat_map(|sample_bytes| sample_bytes.iter().rev().copied())
+            .collect()
+    }
+
+    #[test]
+    fn every_format_decodes_in_both_endiannesses() {
+        for sample_format in SampleFormat::iter() {
+            let (bytes, samples) = big_endian_fixture(sample_format);
+
+            assert_eq!(
+                sample_format.decode(&bytes, Endianness::Big),
+                samples,
+                "{sample_format}, big endian"
+            );
+            assert_eq!(
+                sample_format.decode(&little_endian(&bytes, sample_format), Endianness::Little),
+                samples,
+                "{sample_format}, little endian"
+            );
+        }
+    }
+
+    #[test]
+    fn unsigned_8_bit_is_offset_binary() {
+        for endianness in Endianness::iter() {
+            assert_eq!(
+                SampleFormat::U8.decode(&[0x00, 0x7F, 0x80, 0x81], endianness),
+                [-1.0, -1.0 / 128.0, 0.0, 1.0 / 128.0]
+            );
+        }
+    }
+
+    #[test]
+    fn signed_formats_reach_full_scale() {
+        assert_eq!(SampleFormat::I8.decode(&[0x80], Endianness::Little), [-1.0]);
+        assert_eq!(
+            SampleFormat::I16.decode(&[0x00, 0x80], Endianness::Little),
+            [-1.0]
+        );
+        assert_eq!(
+            SampleFormat::I32.decode(&[0x00, 0x00, 0x00, 0x80], Endianness::Little),
+            [-1.0]
+        );
+        assert_eq!(
+            SampleFormat::I32.decode(&[0x7F, 0xFF, 0xFF, 0xFF], Endianness::Big),
+            [1.0]
+        );
+    }
+
+    #[test]
+    fn f64_bytes_are_reversed_as_a_whole() {
+        let sample = -0.123_456_789_f64;
+
+        assert_eq!(
+            SampleFormat::F64.decode(&sample.to_le_bytes(), Endianness::Little),
+            [sample as f32]
+        );
+        assert_eq!(
+            SampleFormat::F64.decode(&sample.to_be_bytes(), Endianness::Big),
+            [sample as f32]
+        );
+    }
+
+    #[test]
+    fn trailing_partial_sample_is_ignored() {
+        for sample_format in SampleFormat::iter() {
+            let (mut bytes, samples) = big_endian_fixture(sample_format);
+            bytes.truncate(bytes.len() - 1);
+
+            assert_eq!(
+                sample_format.decode(&bytes, Endianness::Big),
+                samples[..samples.len() - 1],
+                "{sample_format}"
+            );
+        }
+    }
+}
@@ -8,10 +8,12 @@ mod sixteen_segment;
 use strum::{Display, EnumIter};
 
 pub use display_metrics::{DisplayMetrics, DisplayMetricsPreset};
-pub use widget::SegmentedDisplayWidget;
+pub use widget::{EditableSegmentedDisplayWidget, SegmentedDisplayWidget};
 
 use egui::Pos2;
 
+use crate::displays::DisplayStyle;
+
 // ----------------------------------------------------------------------------
 
 pub type DisplayGlyph = u16;
@@ -26,6 +28,62 @@ pub struct DisplayDigit {
 
 // ----------------------------------------------------------------------------
 
+pub const MAX_SEGMENT_COUNT: usize = DisplayGlyph::BITS as usize;
+
+/// Per-segment intensities in `0.0..=1.0`, indexed the same way as the bits
+/// of a [`DisplayGlyph`].
+pub type DisplayGlyphIntensity = [f32; MAX_SEGMENT_COUNT];
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DisplayDigitIntensity {
+    pub glyph: DisplayGlyphIntensity,
+    pub dot: f32,
+    pub colon: f32,
+    pub apostrophe: f32,
+}
+
+impl From<DisplayDigit> for DisplayDigitIntensity {
+    fn from(digit: DisplayDigit) -> Self {
+        let bool_to_intensity = |value: bool| if value { 1.0 } else { 0.0 };
+
+        Self {
+            glyph: std::array::from_fn(|segment_index| {
+                bool_to_intensity(((digit.glyph >> segment_index) & 0x01) != 0x00)
+            }),
+            dot: bool_to_intensity(digit.dot),
+            colon: bool_to_intensity(digit.colon),
+            apostrophe: bool_to_intensity(digit.apostrophe),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Digit with per-segment intensities and an optional style overriding the
+/// style of the display. The background color of the override is not used.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StyledDisplayDigit {
+    pub digit: DisplayDigitIntensity,
+    pub style: Option<DisplayStyle>,
+}
+
+impl From<DisplayDigit> for StyledDisplayDigit {
+    fn from(digit: DisplayDigit) -> Self {
+        Self {
+            digit: digit.into(),
+            style: None,
+        }
+    }
+}
+
+impl From<DisplayDigitIntensity> for StyledDisplayDigit {
+    fn from(digit: DisplayDigitIntensity) -> Self {
+        Self { digit, style: None }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
 #[non_exhaustive]
 #[derive(Clone, Copy, Debug, Display, EnumIter, Eq, PartialEq)]
 pub enum DisplayKind {
@@ -1,18 +1,40 @@
-use egui::{pos2, vec2, Pos2, Response, Sense, Shape, Stroke, Ui, Widget};
+use std::fmt::Display;
+use std::str::FromStr;
+
+use egui::{pos2, vec2, Event, Key, Pos2, Rect, Response, Sense, Shape, Stroke, Ui, Vec2, Widget};
 use itertools::Itertools;
 
 use crate::displays::segmented_display::{
-    DisplayDigit, DisplayKind, DisplayMetrics, DisplayMetricsPreset,
+    DisplayDigit, DisplayDigitIntensity, DisplayKind, DisplayMetrics, DisplayMetricsPreset,
+    StyledDisplayDigit,
 };
-use crate::displays::{DisplayStyle, DisplayStylePreset};
+use crate::displays::{DisplayGlow, DisplayStyle, DisplayStylePreset};
+
+// ----------------------------------------------------------------------------
+
+/// Combined into one function (rather than two) to make it easier
+/// for the borrow checker.
+type GetSetValue<'a> = Box<dyn 'a + FnMut(Option<String>) -> String>;
+
+fn get(get_set_value: &mut GetSetValue<'_>) -> String {
+    (get_set_value)(None)
+}
+
+fn set(get_set_value: &mut GetSetValue<'_>, value: String) {
+    (get_set_value)(Some(value));
+}
+
+// ----------------------------------------------------------------------------
 
 #[must_use = "You should put this widget in an ui with `ui.add(widget);`"]
 pub struct SegmentedDisplayWidget {
     display_kind: DisplayKind,
-    digits: Vec<DisplayDigit>,
+    digits: Vec<StyledDisplayDigit>,
     digit_height: f32,
     metrics: DisplayMetrics,
     style: DisplayStyle,
+    glow: Option<DisplayGlow>,
+    animated: bool,
     show_dots: bool,
     show_colons: bool,
     show_apostrophes: bool,
@@ -26,6 +48,8 @@ impl SegmentedDisplayWidget {
             digit_height: 80.0,
             metrics: DisplayMetrics::default(),
             style: DisplayStylePreset::Default.style(),
+            glow: None,
+            animated: false,
             show_dots: true,
             show_colons: true,
             show_apostrophes: true,
@@ -44,32 +68,108 @@ impl SegmentedDisplayWidget {
         Self::new(DisplayKind::SixteenSegment).push_string(value.as_ref())
     }
 
-    pub fn push_string<T: AsRef<str>>(mut self, value: T) -> Self {
+    /// Turns the display into an editable one bound to `value`, the digits
+    /// are taken from the value instead of the pushed strings.
+    pub fn editable(self, value: &mut String) -> EditableSegmentedDisplayWidget<'_> {
+        self.editable_from_get_set(move |v: Option<String>| {
+            if let Some(v) = v {
+                *value = v;
+            }
+            value.clone()
+        })
+    }
+
+    /// Edits a number zero-padded to `digit_count` characters. Edits that
+    /// would result in an unparseable number are discarded.
+    pub fn editable_number<T>(
+        self,
+        value: &mut T,
+        digit_count: usize,
+    ) -> EditableSegmentedDisplayWidget<'_>
+    where
+        T: Display + FromStr,
+    {
+        self.editable_from_get_set(move |v: Option<String>| {
+            if let Some(v) = v {
+                if let Ok(v) = v.parse() {
+                    *value = v;
+                }
+            }
+            format!("{value:0digit_count$}")
+        })
+    }
+
+    pub fn editable_from_get_set<'a>(
+        self,
+        get_set_value: impl 'a + FnMut(Option<String>) -> String,
+    ) -> EditableSegmentedDisplayWidget<'a> {
+        EditableSegmentedDisplayWidget {
+            display: self,
+            get_set_value: Box::new(get_set_value),
+            edit_charset: ('0'..='9').collect_vec(),
+        }
+    }
+
+    /// Returns the parsed digits along with the character index they
+    /// originate from in `value`.
+    fn string_to_digits(&self, value: &str) -> Vec<(usize, DisplayDigit)> {
         let display_impl = self.display_kind.display_impl();
 
-        self.digits.extend(
-            [None]
-                .into_iter()
-                .chain(value.as_ref().chars().map(Some))
-                .chain([None])
-                .tuple_windows()
-                .filter_map(|(prev, curr, next)| match curr {
-                    Some('.') if self.show_dots => None,
-                    Some(':') if self.show_colons => None,
-                    Some('\'') if self.show_apostrophes => None,
-                    Some(c) if display_impl.glyph(c).is_some() => Some(DisplayDigit {
+        [None]
+            .into_iter()
+            .chain(value.chars().map(Some))
+            .chain([None])
+            .tuple_windows()
+            .enumerate()
+            .filter_map(|(char_index, (prev, curr, next))| match curr {
+                Some('.') if self.show_dots => None,
+                Some(':') if self.show_colons => None,
+                Some('\'') if self.show_apostrophes => None,
+                Some(c) if display_impl.glyph(c).is_some() => Some((
+                    char_index,
+                    DisplayDigit {
                         glyph: display_impl.glyph(c).unwrap(),
                         dot: (next == Some('.')) && self.show_dots,
                         colon: (prev == Some(':')) && self.show_colons,
                         apostrophe: (prev == Some('\'')) && self.show_apostrophes,
-                    }),
-                    _ => None,
-                }),
+                    },
+                )),
+                _ => None,
+            })
+            .collect_vec()
+    }
+
+    pub fn push_string<T: AsRef<str>>(mut self, value: T) -> Self {
+        let digits = self.string_to_digits(value.as_ref());
+        self.digits.extend(
+            digits
+                .into_iter()
+                .map(|(_, digit)| StyledDisplayDigit::from(digit)),
         );
         self
     }
 
+    pub fn push_styled_string<T: AsRef<str>>(mut self, value: T, style: DisplayStyle) -> Self {
+        let digits = self.string_to_digits(value.as_ref());
+        self.digits
+            .extend(digits.into_iter().map(|(_, digit)| StyledDisplayDigit {
+                digit: digit.into(),
+                style: Some(style),
+            }));
+        self
+    }
+
     pub fn push_digit(mut self, digit: DisplayDigit) -> Self {
+        self.digits.push(digit.into());
+        self
+    }
+
+    pub fn push_digit_intensity(mut self, digit: DisplayDigitIntensity) -> Self {
+        self.digits.push(digit.into());
+        self
+    }
+
+    pub fn push_styled_digit(mut self, digit: StyledDisplayDigit) -> Self {
         self.digits.push(digit);
         self
     }
@@ -89,6 +189,16 @@ impl SegmentedDisplayWidget {
         self
     }
 
+    pub fn glow(mut self, glow: Option<DisplayGlow>) -> Self {
+        self.glow = glow;
+        self
+    }
+
+    pub fn animated(mut self, animated: bool) -> Self {
+        self.animated = animated;
+        self
+    }
+
     pub fn metrics(mut self, metrics: DisplayMetrics) -> Self {
         self.metrics = metrics;
         self
@@ -117,8 +227,32 @@ impl SegmentedDisplayWidget {
 
 impl Widget for SegmentedDisplayWidget {
     fn ui(self, ui: &mut Ui) -> Response {
+        self.show(ui, None)
+    }
+}
+
+impl SegmentedDisplayWidget {
+    /// Shared by the static and the editable displays, `editor` holds the
+    /// edited value and the characters its digits cycle through.
+    fn show(
+        mut self,
+        ui: &mut Ui,
+        mut editor: Option<(&mut GetSetValue<'_>, &[char])>,
+    ) -> Response {
         let display_impl = self.display_kind.display_impl();
 
+        // Character indices of the digits in the edited value
+        let mut char_indices = Vec::new();
+
+        if let Some((ref mut get_set_value, _)) = editor {
+            let value = get(get_set_value);
+            (char_indices, self.digits) = self
+                .string_to_digits(&value)
+                .into_iter()
+                .map(|(char_index, digit)| (char_index, StyledDisplayDigit::from(digit)))
+                .unzip();
+        }
+
         let digit_height = self.digit_height;
         let digit_width = digit_height * self.metrics.digit_ratio;
 
@@ -140,7 +274,123 @@ impl Widget for SegmentedDisplayWidget {
             digit_height + (2.0 * margin_vertical),
         );
 
-        let (rect, response) = ui.allocate_exact_size(desired_size, Sense::click());
+        let (rect, mut response) = ui.allocate_exact_size(desired_size, Sense::click());
+
+        let digit_center = |digit_index: usize| {
+            rect.left_center()
+                + vec2(
+                    margin_horizontal
+                        + digit_shearing.abs()
+                        + ((digit_width + digit_spacing) * digit_index as f32)
+                        + (digit_width / 2.0),
+                    0.0,
+                )
+        };
+
+        let mut editing = false;
+
+        if let Some((ref mut get_set_value, edit_charset)) = editor {
+            let digit_count = char_indices.len();
+
+            let mut cursor = ui
+                .memory_mut(|memory| memory.data.get_temp::<usize>(response.id))
+                .unwrap_or(0)
+                .min(digit_count.saturating_sub(1));
+
+            let pointer_digit = |pointer_pos: Pos2| {
+                let x = pointer_pos.x - rect.left() - margin_horizontal - digit_shearing.abs()
+                    + (digit_spacing / 2.0);
+                ((x / (digit_width + digit_spacing)).floor().max(0.0) as usize)
+                    .min(digit_count.saturating_sub(1))
+            };
+
+            if response.clicked() {
+                response.request_focus();
+
+                // Keyboard activation (Space/Enter) clicks without a pointer
+                if let Some(pointer_pos) = response.interact_pointer_pos() {
+                    cursor = pointer_digit(pointer_pos);
+                }
+            }
+
+            editing = response.has_focus();
+
+            let mut step: isize = 0;
+            let mut typed_chars = Vec::new();
+
+            if editing {
+                ui.input(|input| {
+                    if input.key_pressed(Key::ArrowLeft) {
+                        cursor = cursor.saturating_sub(1);
+                    }
+                    if input.key_pressed(Key::ArrowRight) {
+                        cursor = (cursor + 1).min(digit_count.saturating_sub(1));
+                    }
+                    if input.key_pressed(Key::ArrowUp) {
+                        step += 1;
+                    }
+                    if input.key_pressed(Key::ArrowDown) {
+                        step -= 1;
+                    }
+
+                    // Apply every character typed this frame in order
+                    for event in &input.events {
+                        if let Event::Text(text) = event {
+                            typed_chars.extend(text.chars().filter(|c| edit_charset.contains(c)));
+                        }
+                    }
+                });
+
+                if ui.input(|input| input.key_pressed(Key::Escape)) {
+                    response.surrender_focus();
+                }
+
+                if let Some(hover_pos) = response.hover_pos() {
+                    let scroll_delta = ui.input(|input| input.scroll_delta.y);
+
+                    if scroll_delta != 0.0 {
+                        cursor = pointer_digit(hover_pos);
+                        step += scroll_delta.signum() as isize;
+
+                        // Keep the surrounding scroll area from scrolling along
+                        ui.input_mut(|input| input.scroll_delta = Vec2::ZERO);
+                    }
+                }
+            }
+
+            if digit_count > 0 && (step != 0 || !typed_chars.is_empty()) {
+                let mut chars = get(get_set_value).chars().collect_vec();
+
+                if step != 0 {
+                    let current_char = &mut chars[char_indices[cursor]];
+                    let charset_len = edit_charset.len() as isize;
+                    let new_charset_index = edit_charset
+                        .iter()
+                        .position(|c| c == current_char)
+                        .map_or(0, |charset_index| {
+                            (charset_index as isize + step).rem_euclid(charset_len)
+                        });
+                    *current_char = edit_charset[new_charset_index as usize];
+                }
+
+                for typed_char in typed_chars {
+                    chars[char_indices[cursor]] = typed_char;
+                    cursor = (cursor + 1).min(digit_count - 1);
+                }
+
+                set(get_set_value, chars.into_iter().collect());
+                response.mark_changed();
+
+                let new_value = get(get_set_value);
+                self.digits = self
+                    .string_to_digits(&new_value)
+                    .into_iter()
+                    .map(|(_, digit)| StyledDisplayDigit::from(digit))
+                    .collect_vec();
+            }
+
+            ui.memory_mut(|memory| memory.data.insert_temp(response.id, cursor));
+        }
 
         let mut child_ui = ui.child_ui(rect, *ui.layout());
         child_ui.set_clip_rect(child_ui.clip_rect().intersect(rect));
@@ -177,72 +427,180 @@ impl Widget for SegmentedDisplayWidget {
                 pos2( (digit_width / 2.0) + (digit_spacing / 2.0), (digit_height / 2.0) - (segment_thickness / 2.0))
             );
 
-            let paint_digit = |digit: &DisplayDigit, digit_center: Pos2| {
+            // Segment intensities are animated one by one, this is what makes
+            // segments fade in and out instead of switching instantly.
+            let styled_digits = self
+                .digits
+                .iter()
+                .enumerate()
+                .map(|(digit_index, StyledDisplayDigit { digit, style })| {
+                    let style = style.unwrap_or(self.style);
+
+                    if !self.animated {
+                        return (*digit, style);
+                    }
+
+                    let animate = |id_source: (usize, usize), value: f32| {
+                        ui.ctx().animate_value_with_time(
+                            response.id.with(id_source),
+                            value,
+                            ui.style().animation_time,
+                        )
+                    };
+
+                    // Segment indices beyond the glyph size are reserved for the
+                    // dot, colon and apostrophe animations.
+                    let glyph_size = digit.glyph.len();
+
+                    let digit = DisplayDigitIntensity {
+                        glyph: std::array::from_fn(|segment_index| {
+                            animate((digit_index, segment_index), digit.glyph[segment_index])
+                        }),
+                        dot: animate((digit_index, glyph_size), digit.dot),
+                        colon: animate((digit_index, glyph_size + 1), digit.colon),
+                        apostrophe: animate((digit_index, glyph_size + 2), digit.apostrophe),
+                    };
+
+                    (digit, style)
+                })
+                .collect_vec();
+
+            let paint_digit = |digit: &DisplayDigitIntensity,
+                               style: &DisplayStyle,
+                               digit_center: Pos2,
+                               glow_pass: bool| {
                 let transform = |&Pos2 { x, y }| {
                     digit_center + vec2(x, y)
                         - vec2((y / (digit_height / 2.0)) * digit_shearing, 0.0)
                 };
 
-                for (segment_index, segment_points) in segment_geometry.iter().enumerate() {
-                    let segment_active = ((digit.glyph >> segment_index) & 0x01) != 0x00;
+                let paint_polygon = |points: Vec<Pos2>, value: f32| {
+                    if glow_pass {
+                        if let Some(glow) = self.glow {
+                            glow.paint_polygon(
+                                child_ui.painter(),
+                                &points,
+                                style.active_foreground_color,
+                                value,
+                            );
+                        }
+                    } else {
+                        // TODO: concave_polygon
+                        // https://github.com/emilk/egui/issues/513
+                        child_ui.painter().add(Shape::convex_polygon(
+                            points,
+                            style.foreground_color_blend(value),
+                            style.foreground_stroke_blend(value),
+                        ));
+                    }
+                };
+
+                let paint_circle = |center: Pos2, value: f32| {
+                    if glow_pass {
+                        if let Some(glow) = self.glow {
+                            glow.paint_circle(
+                                child_ui.painter(),
+                                center,
+                                segment_thickness / 2.0,
+                                style.active_foreground_color,
+                                value,
+                            );
+                        }
+                    } else {
+                        child_ui.painter().circle(
+                            center,
+                            segment_thickness / 2.0,
+                            style.foreground_color_blend(value),
+                            style.foreground_stroke_blend(value),
+                        );
+                    }
+                };
 
-                    // TODO: concave_polygon
-                    // https://github.com/emilk/egui/issues/513
-                    child_ui.painter().add(Shape::convex_polygon(
+                for (segment_index, segment_points) in segment_geometry.iter().enumerate() {
+                    paint_polygon(
                         segment_points.iter().map(transform).collect_vec(),
-                        self.style.foreground_color(segment_active),
-                        self.style.foreground_stroke(segment_active),
-                    ));
+                        digit.glyph[segment_index],
+                    );
                 }
 
                 if self.show_dots {
-                    child_ui.painter().circle(
-                        transform(&dot_pos),
-                        segment_thickness / 2.0,
-                        self.style.foreground_color(digit.dot),
-                        self.style.foreground_stroke(digit.dot),
-                    );
+                    paint_circle(transform(&dot_pos), digit.dot);
                 }
 
                 if self.show_colons {
-                    child_ui.painter().circle(
-                        transform(&colon_top_pos),
-                        segment_thickness / 2.0,
-                        self.style.foreground_color(digit.colon),
-                        self.style.foreground_stroke(digit.colon),
-                    );
-
-                    child_ui.painter().circle(
-                        transform(&colon_bottom_pos),
-                        segment_thickness / 2.0,
-                        self.style.foreground_color(digit.colon),
-                        self.style.foreground_stroke(digit.colon),
-                    );
+                    paint_circle(transform(&colon_top_pos), digit.colon);
+                    paint_circle(transform(&colon_bottom_pos), digit.colon);
                 }
 
                 if self.show_apostrophes {
-                    child_ui.painter().add(Shape::convex_polygon(
+                    paint_polygon(
                         apostrophe_points.iter().map(transform).collect_vec(),
-                        self.style.foreground_color(digit.apostrophe),
-                        self.style.foreground_stroke(digit.apostrophe),
-                    ));
+                        digit.apostrophe,
+                    );
                 }
             };
 
-            for (digit_index, digit) in self.digits.iter().enumerate() {
-                let digit_center = rect.left_center()
-                    + vec2(
-                        margin_horizontal
-                            + digit_shearing.abs()
-                            + ((digit_width + digit_spacing) * digit_index as f32)
-                            + (digit_width / 2.0),
-                        0.0,
-                    );
+            // Glows are painted in a separate pass underneath all digits, so they
+            // never bleed over the segments of neighboring digits.
+            let passes: &[bool] = if self.glow.is_some() {
+                &[true, false]
+            } else {
+                &[false]
+            };
+
+            for &glow_pass in passes {
+                for (digit_index, (digit, style)) in styled_digits.iter().enumerate() {
+                    paint_digit(digit, style, digit_center(digit_index), glow_pass);
+                }
+            }
 
-                paint_digit(digit, digit_center);
+            if editing && !self.digits.is_empty() {
+                let cursor = ui
+                    .memory_mut(|memory| memory.data.get_temp::<usize>(response.id))
+                    .unwrap_or(0)
+                    .min(self.digits.len() - 1);
+
+                child_ui.painter().rect_stroke(
+                    Rect::from_center_size(
+                        digit_center(cursor),
+                        vec2(digit_width + digit_spacing, digit_height + margin_vertical),
+                    ),
+                    ui.style().visuals.noninteractive().rounding,
+                    ui.style().visuals.selection.stroke,
+                );
             }
         }
 
         response
     }
 }
+
+// ----------------------------------------------------------------------------
+
+/// Segmented display bound to a value, edited by clicking a digit and typing,
+/// with the arrow keys or with the scroll wheel while focused.
+#[must_use = "You should put this widget in an ui with `ui.add(widget);`"]
+pub struct EditableSegmentedDisplayWidget<'a> {
+    display: SegmentedDisplayWidget,
+    get_set_value: GetSetValue<'a>,
+    edit_charset: Vec<char>,
+}
+
+impl<'a> EditableSegmentedDisplayWidget<'a> {
+    /// Characters the digits of an editable display cycle through.
+    pub fn edit_charset(mut self, edit_charset: impl AsRef<str>) -> Self {
+        self.edit_charset = edit_charset.as_ref().chars().collect_vec();
+        assert!(
+            !self.edit_charset.is_empty(),
+            "edit charset must not be empty"
+        );
+        self
+    }
+}
+
+impl<'a> Widget for EditableSegmentedDisplayWidget<'a> {
+    fn ui(mut self, ui: &mut Ui) -> Response {
+        self.display
+            .show(ui, Some((&mut self.get_set_value, &self.edit_charset)))
+    }
+}
@@ -0,0 +1,318 @@
+use std::ops::RangeInclusive;
+
+use egui::{
+    lerp, pos2, remap_clamp, vec2, Color32, ColorImage, Rect, Response, Rgba, Sense, TextureHandle,
+    TextureOptions, Ui, Widget,
+};
+use itertools::Itertools;
+
+use crate::displays::spectrum::{
+    axis_to_frequency, band_magnitude, buffer_spectrum, magnitude_to_decibels,
+};
+use crate::displays::waveform_display::{BufferLayout, SampleRange};
+
+// ----------------------------------------------------------------------------
+
+/// Dark purple to pale yellow, roughly following the "magma" colormap.
+const SPECTROGRAM_COLORMAP: [Color32; 5] = [
+    Color32::from_rgb(0, 0, 4),
+    Color32::from_rgb(81, 18, 124),
+    Color32::from_rgb(183, 55, 121),
+    Color32::from_rgb(252, 137, 97),
+    Color32::from_rgb(252, 253, 191),
+];
+
+fn spectrogram_color(level: f32) -> Color32 {
+    let position = level.clamp(0.0, 1.0) * (SPECTROGRAM_COLORMAP.len() - 1) as f32;
+    let index = (position.floor() as usize).min(SPECTROGRAM_COLORMAP.len() - 2);
+    let t = position - index as f32;
+
+    let a = Rgba::from(SPECTROGRAM_COLORMAP[index]);
+    let b = Rgba::from(SPECTROGRAM_COLORMAP[index + 1]);
+    Color32::from(a * (1.0 - t) + b * t)
+}
+
+#[derive(Clone, Default)]
+struct SpectrogramState {
+    texture: Option<TextureHandle>,
+    /// Texture column the next spectrum goes to, holding the oldest one.
+    write_column: usize,
+    /// Audio position of the newest column, in frames.
+    audio_position: Option<f64>,
+}
+
+// ----------------------------------------------------------------------------
+
+/// Scrolling time/frequency view, adding a column to the right edge every
+/// `hop_size` frames of audio.
+#[must_use = "You should put this widget in an ui with `ui.add(widget);`"]
+pub struct SpectrogramWidget<'a, SampleType>
+where
+    SampleType: SampleRange<SampleType>,
+{
+    buffer: Option<&'a [SampleType]>,
+    buffer_layout: BufferLayout,
+    channels: usize,
+    sample_rate: f32,
+    fft_size: usize,
+    hop_size: Option<usize>,
+    frames_written: Option<usize>,
+    width: f32,
+    height: f32,
+    history_length: usize,
+    logarithmic: bool,
+    frequency_range: Option<RangeInclusive<f32>>,
+    decibel_range: RangeInclusive<f32>,
+}
+
+impl<'a, SampleType> Default for SpectrogramWidget<'a, SampleType>
+where
+    SampleType: SampleRange<SampleType>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, SampleType> SpectrogramWidget<'a, SampleType>
+where
+    SampleType: SampleRange<SampleType>,
+{
+    pub fn new() -> Self {
+        Self {
+            buffer: None,
+            buffer_layout: BufferLayout::Interleaved,
+            channels: 1,
+            sample_rate: 44100.0,
+            fft_size: 1024,
+            hop_size: None,
+            frames_written: None,
+            width: 256.0,
+            height: 64.0,
+            history_length: 256,
+            logarithmic: true,
+            frequency_range: None,
+            decibel_range: -90.0..=0.0,
+        }
+    }
+
+    pub fn buffer(mut self, buffer: &'a [SampleType]) -> Self {
+        self.buffer = Some(buffer);
+        self
+    }
+
+    pub fn buffer_layout(mut self, buffer_layout: BufferLayout) -> Self {
+        self.buffer_layout = buffer_layout;
+        self
+    }
+
+    pub fn channels(mut self, channels: usize) -> Self {
+        self.channels = channels;
+        self
+    }
+
+    pub fn sample_rate(mut self, sample_rate: impl Into<f32>) -> Self {
+        self.sample_rate = sample_rate.into();
+        self
+    }
+
+    /// Number of frames analyzed from the end of the buffer, must be a power
+    /// of two of at least two.
+    pub fn fft_size(mut self, fft_size: usize) -> Self {
+        assert!(
+            fft_size >= 2 && fft_size.is_power_of_two(),
+            "FFT size must be a power of two of at least two"
+        );
+        self.fft_size = fft_size;
+        self
+    }
+
+    /// Number of frames between two columns, defaults to the FFT size.
+    pub fn hop_size(mut self, hop_size: usize) -> Self {
+        assert!(hop_size > 0);
+        self.hop_size = Some(hop_size);
+        self
+    }
+
+    /// Number of frames written to the buffer so far, like
+    /// [`WaveformRingBuffer::frames_written`](crate::displays::WaveformRingBuffer::frames_written).
+    /// Without it, the spectrogram scrolls along with the elapsed time.
+    pub fn frames_written(mut self, frames_written: usize) -> Self {
+        self.frames_written = Some(frames_written);
+        self
+    }
+
+    pub fn width(mut self, width: impl Into<f32>) -> Self {
+        self.width = width.into();
+        self
+    }
+
+    pub fn height(mut self, height: impl Into<f32>) -> Self {
+        self.height = height.into();
+        self
+    }
+
+    /// Number of columns kept on screen.
+    pub fn history_length(mut self, history_length: usize) -> Self {
+        assert!(history_length > 0);
+        self.history_length = history_length;
+        self
+    }
+
+    pub fn logarithmic(mut self, logarithmic: bool) -> Self {
+        self.logarithmic = logarithmic;
+        self
+    }
+
+    /// Displayed frequency range in Hz, defaults to 20 Hz up to the Nyquist
+    /// frequency.
+    pub fn frequency_range(mut self, frequency_range: RangeInclusive<f32>) -> Self {
+        self.frequency_range = Some(frequency_range);
+        self
+    }
+
+    pub fn decibel_range(mut self, decibel_range: RangeInclusive<f32>) -> Self {
+        self.decibel_range = decibel_range;
+        self
+    }
+}
+
+impl<'a, SampleType> Widget for SpectrogramWidget<'a, SampleType>
+where
+    SampleType: SampleRange<SampleType> + Copy,
+{
+    fn ui(self, ui: &mut Ui) -> Response {
+        let desired_size = vec2(self.width, self.height);
+        let (rect, response) = ui.allocate_exact_size(desired_size, Sense::hover());
+
+        let visuals = *ui.style().interact(&response);
+
+        let frequency_range = self
+            .frequency_range
+            .clone()
+            .unwrap_or(20.0..=self.sample_rate / 2.0);
+
+        let spectrogram_rect = rect.shrink(2.0);
+        let image_size = [
+            self.history_length,
+            (spectrogram_rect.height().round() as usize).max(1),
+        ];
+
+        let [columns, rows] = image_size;
+
+        let mut state = ui
+            .memory_mut(|memory| memory.data.get_temp::<SpectrogramState>(response.id))
+            .unwrap_or_default();
+
+        let texture = match &mut state.texture {
+            Some(texture) if texture.size() == image_size => texture,
+            _ => {
+                state = SpectrogramState::default();
+                state.texture.insert(ui.ctx().load_texture(
+                    format!("spectrogram_{:?}", response.id),
+                    ColorImage::new(image_size, spectrogram_color(0.0)),
+                    TextureOptions::LINEAR,
+                ))
+            }
+        };
+
+        // Scrolls along with the audio rather than with the repaints
+        let audio_position = match self.frames_written {
+            Some(frames_written) => frames_written as f64,
+            None => ui.input(|input| input.time) * self.sample_rate as f64,
+        };
+        let hop_size = self.hop_size.unwrap_or(self.fft_size) as f64;
+
+        let new_columns = match state.audio_position {
+            Some(last_position) if audio_position >= last_position => {
+                let new_columns = ((audio_position - last_position) / hop_size).floor();
+                state.audio_position = Some(last_position + new_columns * hop_size);
+                (new_columns as usize).min(columns)
+            }
+            _ => {
+                state.audio_position = Some(audio_position);
+                1
+            }
+        };
+
+        if let Some(buffer) = self.buffer.filter(|_| new_columns > 0) {
+            let spectrum =
+                buffer_spectrum(buffer, self.buffer_layout, self.channels, self.fft_size);
+            let bin_width = self.sample_rate / self.fft_size as f32;
+
+            let column = (0..rows)
+                .map(|row| {
+                    // Low frequencies at the bottom
+                    let t = |row: usize| 1.0 - row as f32 / rows as f32;
+                    let frequency_band =
+                        axis_to_frequency(t(row + 1), frequency_range.clone(), self.logarithmic)
+                            ..=axis_to_frequency(t(row), frequency_range.clone(), self.logarithmic);
+
+                    spectrogram_color(remap_clamp(
+                        magnitude_to_decibels(band_magnitude(&spectrum, bin_width, frequency_band)),
+                        self.decibel_range.clone(),
+                        0.0..=1.0,
+                    ))
+                })
+                .collect_vec();
+
+            // Columns added at once all show the latest spectrum, uploaded in at
+            // most two runs as they wrap around the end of the texture
+            let mut remaining_columns = new_columns;
+            while remaining_columns > 0 {
+                let run_length = remaining_columns.min(columns - state.write_column);
+                let run = ColorImage {
+                    size: [run_length, rows],
+                    pixels: (0..rows * run_length)
+                        .map(|index| column[index / run_length])
+                        .collect(),
+                };
+
+                texture.set_partial([state.write_column, 0], run, TextureOptions::LINEAR);
+
+                state.write_column = (state.write_column + run_length) % columns;
+                remaining_columns -= run_length;
+            }
+        }
+
+        let texture_id = texture.id();
+        let write_column = state.write_column;
+
+        ui.memory_mut(|memory| memory.data.insert_temp(response.id, state));
+
+        if ui.is_rect_visible(rect) {
+            ui.painter().rect(
+                rect,
+                visuals.rounding,
+                ui.style().visuals.extreme_bg_color,
+                visuals.fg_stroke,
+            );
+
+            // The texture is a ring of columns, starting with the oldest one
+            let split = write_column as f32 / columns as f32;
+            let split_x = lerp(spectrogram_rect.x_range(), 1.0 - split);
+
+            ui.painter().image(
+                texture_id,
+                Rect::from_x_y_ranges(
+                    spectrogram_rect.left()..=split_x,
+                    spectrogram_rect.y_range(),
+                ),
+                Rect::from_min_max(pos2(split, 0.0), pos2(1.0, 1.0)),
+                Color32::WHITE,
+            );
+
+            ui.painter().image(
+                texture_id,
+                Rect::from_x_y_ranges(
+                    split_x..=spectrogram_rect.right(),
+                    spectrogram_rect.y_range(),
+                ),
+                Rect::from_min_max(pos2(0.0, 0.0), pos2(split, 1.0)),
+                Color32::WHITE,
+            );
+        }
+
+        response
+    }
+}
@@ -0,0 +1,364 @@
+use std::f32::consts::TAU;
+use std::ops::RangeInclusive;
+
+use egui::{lerp, remap, remap_clamp};
+use itertools::Itertools;
+
+use crate::displays::waveform_display::{BufferLayout, SampleRange};
+
+// ----------------------------------------------------------------------------
+
+/// In-place iterative radix-2 FFT.
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    assert!(n.is_power_of_two(), "FFT size must be a power of two");
+    assert_eq!(im.len(), n);
+
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut length = 2;
+    while length <= n {
+        let angle = -TAU / length as f32;
+
+        for start in (0..n).step_by(length) {
+            for k in 0..(length / 2) {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let (a, b) = (start + k, start + k + length / 2);
+
+                let tr = re[b] * cos - im[b] * sin;
+                let ti = re[b] * sin + im[b] * cos;
+
+                re[b] = re[a] - tr;
+                im[b] = im[a] - ti;
+                re[a] += tr;
+                im[a] += ti;
+            }
+        }
+
+        length <<= 1;
+    }
+}
+
+/// Hann windowed magnitude spectrum of `samples` in the `-1.0..=1.0` range,
+/// from DC up to and including Nyquist, scaled so a full scale sine wave
+/// peaks at `1.0`.
+fn magnitude_spectrum(samples: &[f32]) -> Vec<f32> {
+    let n = samples.len();
+
+    let window = |index: usize| 0.5 - 0.5 * (TAU * index as f32 / n as f32).cos();
+    let window_gain = n as f32 / 2.0;
+
+    let mut re = samples
+        .iter()
+        .enumerate()
+        .map(|(index, sample)| sample * window(index))
+        .collect_vec();
+    let mut im = vec![0.0; n];
+
+    fft(&mut re, &mut im);
+
+    re.iter()
+        .zip(im.iter())
+        .take(n / 2 + 1)
+        .enumerate()
+        .map(|(bin, (re, im))| {
+            // DC and Nyquist have no negative frequency counterpart to fold
+            let scale = if bin == 0 || bin == n / 2 { 1.0 } else { 2.0 };
+            scale * re.hypot(*im) / window_gain
+        })
+        .collect()
+}
+
+/// Magnitude spectrum of the last `fft_size` frames of a buffer, with the
+/// channels combined by their power.
+pub(crate) fn buffer_spectrum<SampleType>(
+    buffer: &[SampleType],
+    buffer_layout: BufferLayout,
+    channels: usize,
+    fft_size: usize,
+) -> Vec<f32>
+where
+    SampleType: SampleRange<SampleType> + Copy,
+{
+    assert_eq!(buffer.len() % channels, 0);
+    let channel_buffer_length = buffer.len() / channels;
+
+    let channel_spectra = (0..channels)
+        .map(|channel_id| {
+            let mut samples = buffer_layout
+                .channel_samples(buffer, channels, channel_id)
+                .skip(channel_buffer_length.saturating_sub(fft_size))
+                .map(|sample| remap(sample.to_f32(), SampleType::DISPLAY_RANGE, -1.0..=1.0))
+                .collect_vec();
+
+            // Zero-pad short buffers at the front, keeping the latest samples
+            let padding = fft_size - samples.len();
+            samples.resize(fft_size, 0.0);
+            samples.rotate_right(padding);
+
+            magnitude_spectrum(&samples)
+        })
+        .collect_vec();
+
+    (0..=(fft_size / 2))
+        .map(|bin| {
+            let power = channel_spectra
+                .iter()
+                .map(|spectrum| spectrum[bin].powi(2))
+                .sum::<f32>();
+            (power / channels as f32).sqrt()
+        })
+        .collect()
+}
+
+/// Position of `frequency` along a frequency axis, in the `0.0..=1.0` range.
+pub(crate) fn frequency_to_axis(
+    frequency: f32,
+    frequency_range: RangeInclusive<f32>,
+    logarithmic: bool,
+) -> f32 {
+    if logarithmic {
+        remap_clamp(
+            frequency.max(f32::MIN_POSITIVE).log10(),
+            frequency_range.start().max(f32::MIN_POSITIVE).log10()
+                ..=frequency_range.end().max(f32::MIN_POSITIVE).log10(),
+            0.0..=1.0,
+        )
+    } else {
+        remap_clamp(frequency, frequency_range, 0.0..=1.0)
+    }
+}
+
+pub(crate) fn axis_to_frequency(
+    t: f32,
+    frequency_range: RangeInclusive<f32>,
+    logarithmic: bool,
+) -> f32 {
+    if logarithmic {
+        10.0f32.powf(lerp(
+            frequency_range.start().max(f32::MIN_POSITIVE).log10()
+                ..=frequency_range.end().max(f32::MIN_POSITIVE).log10(),
+            t,
+        ))
+    } else {
+        lerp(frequency_range, t)
+    }
+}
+
+/// Largest magnitude within a frequency band, interpolated from the
+/// neighbouring bins when the band falls between two bins.
+pub(crate) fn band_magnitude(
+    spectrum: &[f32],
+    bin_width: f32,
+    frequency_band: RangeInclusive<f32>,
+) -> f32 {
+    let first_bin = (frequency_band.start() / bin_width).ceil() as usize;
+    let last_bin = (frequency_band.end() / bin_width).floor() as usize;
+
+    if first_bin <= last_bin && first_bin < spectrum.len() {
+        spectrum[first_bin..=last_bin.min(spectrum.len() - 1)]
+            .iter()
+            .copied()
+            .fold(0.0, f32::max)
+    } else {
+        let center_bin = (frequency_band.start() + frequency_band.end()) / 2.0 / bin_width;
+        let lower_bin = (center_bin.floor() as usize).min(spectrum.len() - 1);
+        let upper_bin = (lower_bin + 1).min(spectrum.len() - 1);
+        lerp(
+            spectrum[lower_bin]..=spectrum[upper_bin],
+            center_bin - center_bin.floor(),
+        )
+    }
+}
+
+pub(crate) fn magnitude_to_decibels(magnitude: f32) -> f32 {
+    20.0 * magnitude.max(f32::MIN_POSITIVE).log10()
+}
+
+/// Round frequencies to draw gridlines at, with their labels.
+pub(crate) fn frequency_gridlines(
+    frequency_range: RangeInclusive<f32>,
+    logarithmic: bool,
+) -> Vec<(f32, String)> {
+    let label = |frequency: f32| {
+        if frequency >= 1000.0 {
+            format!("{}k", frequency / 1000.0)
+        } else {
+            format!("{frequency}")
+        }
+    };
+
+    let frequencies = if logarithmic {
+        [10.0, 100.0, 1000.0, 10000.0, 100000.0]
+            .into_iter()
+            .flat_map(|decade| [decade, decade * 2.0, decade * 5.0])
+            .collect_vec()
+    } else {
+        let step = (frequency_range.end() - frequency_range.start()) / 5.0;
+        let magnitude = 10.0f32.powf(step.log10().floor());
+        let step = (step / magnitude).round() * magnitude;
+        (1..=10).map(|index| index as f32 * step).collect_vec()
+    };
+
+    frequencies
+        .into_iter()
+        .filter(|frequency| frequency_range.contains(frequency))
+        .map(|frequency| (frequency, label(frequency)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::TAU;
+
+    use super::{band_magnitude, buffer_spectrum, fft, magnitude_spectrum};
+    use crate::displays::waveform_display::BufferLayout;
+
+    const FFT_SIZE: usize = 1024;
+    const SAMPLE_RATE: f32 = 48000.0;
+    const EPSILON: f32 = 1e-3;
+
+    fn sine(bin: f32, length: usize) -> Vec<f32> {
+        (0..length)
+            .map(|index| (TAU * bin * index as f32 / FFT_SIZE as f32).sin())
+            .collect()
+    }
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < EPSILON, "{a} != {b}");
+    }
+
+    fn peak_bin(spectrum: &[f32]) -> usize {
+        (0..spectrum.len())
+            .max_by(|&a, &b| spectrum[a].total_cmp(&spectrum[b]))
+            .unwrap()
+    }
+
+    #[test]
+    fn impulse_has_a_flat_spectrum() {
+        let mut re = vec![0.0; 16];
+        let mut im = vec![0.0; 16];
+        re[0] = 1.0;
+
+        fft(&mut re, &mut im);
+
+        for (re, im) in re.into_iter().zip(im) {
+            assert_close(re, 1.0);
+            assert_close(im, 0.0);
+        }
+    }
+
+    #[test]
+    fn sine_lands_in_its_bin() {
+        let spectrum = magnitude_spectrum(&sine(37.0, FFT_SIZE));
+
+        assert_eq!(spectrum.len(), FFT_SIZE / 2 + 1);
+        assert_eq!(peak_bin(&spectrum), 37);
+        assert_close(spectrum[37], 1.0);
+
+        // Hann window main lobe
+        assert_close(spectrum[36], 0.5);
+        assert_close(spectrum[38], 0.5);
+
+        for (bin, magnitude) in spectrum.iter().enumerate() {
+            if !(36..=38).contains(&bin) {
+                assert_close(*magnitude, 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn sine_between_bins_peaks_next_to_it() {
+        let spectrum = magnitude_spectrum(&sine(100.5, FFT_SIZE));
+        assert!([100, 101].contains(&peak_bin(&spectrum)));
+    }
+
+    #[test]
+    fn dc_is_not_folded() {
+        let spectrum = magnitude_spectrum(&[0.5; FFT_SIZE]);
+
+        assert_close(spectrum[0], 0.5);
+
+        // Hann window main lobe, folded like every other bin
+        assert_close(spectrum[1], 0.5);
+        assert!(spectrum[2..].iter().all(|&magnitude| magnitude < EPSILON));
+    }
+
+    #[test]
+    fn nyquist_is_kept_and_not_folded() {
+        let alternating = (0..FFT_SIZE)
+            .map(|index| if index % 2 == 0 { 1.0 } else { -1.0 })
+            .collect::<Vec<_>>();
+        let spectrum = magnitude_spectrum(&alternating);
+
+        assert_eq!(spectrum.len(), FFT_SIZE / 2 + 1);
+        assert_close(spectrum[FFT_SIZE / 2], 1.0);
+        assert_close(spectrum[FFT_SIZE / 2 - 1], 1.0);
+        assert!(spectrum[..(FFT_SIZE / 2 - 1)]
+            .iter()
+            .all(|&magnitude| magnitude < EPSILON));
+    }
+
+    #[test]
+    fn smallest_fft_has_dc_and_nyquist_bins() {
+        let spectrum = buffer_spectrum(&[0.5f32, -0.5], BufferLayout::Interleaved, 1, 2);
+        assert_eq!(spectrum.len(), 2);
+    }
+
+    #[test]
+    fn buffer_spectrum_combines_channels_by_power() {
+        // Sine on the left channel, silence on the right
+        let buffer = sine(37.0, FFT_SIZE)
+            .into_iter()
+            .flat_map(|sample| [sample, 0.0])
+            .collect::<Vec<_>>();
+
+        let spectrum = buffer_spectrum(&buffer, BufferLayout::Interleaved, 2, FFT_SIZE);
+        assert_eq!(spectrum.len(), FFT_SIZE / 2 + 1);
+        assert_close(spectrum[37], 0.5f32.sqrt());
+    }
+
+    #[test]
+    fn buffer_spectrum_pads_short_buffers() {
+        let spectrum = buffer_spectrum(&[0i16; 10], BufferLayout::Interleaved, 1, FFT_SIZE);
+        assert_eq!(spectrum.len(), FFT_SIZE / 2 + 1);
+        assert!(spectrum.iter().all(|&magnitude| magnitude < EPSILON));
+    }
+
+    #[test]
+    fn bands_find_the_sine_and_nyquist() {
+        let bin_width = SAMPLE_RATE / FFT_SIZE as f32;
+        let spectrum = magnitude_spectrum(&sine(37.0, FFT_SIZE));
+
+        let frequency = 37.0 * bin_width;
+        assert_close(
+            band_magnitude(
+                &spectrum,
+                bin_width,
+                (frequency - 10.0)..=(frequency + 10.0),
+            ),
+            1.0,
+        );
+        assert_close(
+            band_magnitude(
+                &spectrum,
+                bin_width,
+                (SAMPLE_RATE / 2.0)..=(SAMPLE_RATE / 2.0),
+            ),
+            0.0,
+        );
+    }
+}
@@ -0,0 +1,332 @@
+use std::ops::RangeInclusive;
+
+use egui::{
+    pos2, remap_clamp, vec2, Align2, FontId, FontSelection, Rect, Response, Sense, Shape, Stroke,
+    Ui, Widget,
+};
+use itertools::Itertools;
+use strum::{Display, EnumIter};
+
+use crate::displays::peak_hold::update_peaks;
+use crate::displays::spectrum::{
+    axis_to_frequency, band_magnitude, buffer_spectrum, frequency_gridlines, frequency_to_axis,
+    magnitude_to_decibels,
+};
+use crate::displays::waveform_display::{BufferLayout, SampleRange};
+
+// ----------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, Display, EnumIter, Eq, PartialEq)]
+pub enum SpectrumMode {
+    #[strum(to_string = "Bars")]
+    Bars,
+
+    #[strum(to_string = "Line")]
+    Line,
+}
+
+#[must_use = "You should put this widget in an ui with `ui.add(widget);`"]
+pub struct SpectrumAnalyzerWidget<'a, SampleType>
+where
+    SampleType: SampleRange<SampleType>,
+{
+    buffer: Option<&'a [SampleType]>,
+    buffer_layout: BufferLayout,
+    channels: usize,
+    sample_rate: f32,
+    fft_size: usize,
+    width: f32,
+    height: f32,
+    mode: SpectrumMode,
+    bar_count: usize,
+    logarithmic: bool,
+    frequency_range: Option<RangeInclusive<f32>>,
+    decibel_range: RangeInclusive<f32>,
+    peak_hold: Option<f32>,
+    peak_decay_time: f32,
+    show_grid: bool,
+}
+
+impl<'a, SampleType> Default for SpectrumAnalyzerWidget<'a, SampleType>
+where
+    SampleType: SampleRange<SampleType>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, SampleType> SpectrumAnalyzerWidget<'a, SampleType>
+where
+    SampleType: SampleRange<SampleType>,
+{
+    pub fn new() -> Self {
+        Self {
+            buffer: None,
+            buffer_layout: BufferLayout::Interleaved,
+            channels: 1,
+            sample_rate: 44100.0,
+            fft_size: 1024,
+            width: 256.0,
+            height: 64.0,
+            mode: SpectrumMode::Bars,
+            bar_count: 32,
+            logarithmic: true,
+            frequency_range: None,
+            decibel_range: -90.0..=0.0,
+            peak_hold: Some(1.0),
+            peak_decay_time: 2.0,
+            show_grid: true,
+        }
+    }
+
+    pub fn buffer(mut self, buffer: &'a [SampleType]) -> Self {
+        self.buffer = Some(buffer);
+        self
+    }
+
+    pub fn buffer_layout(mut self, buffer_layout: BufferLayout) -> Self {
+        self.buffer_layout = buffer_layout;
+        self
+    }
+
+    pub fn channels(mut self, channels: usize) -> Self {
+        self.channels = channels;
+        self
+    }
+
+    pub fn sample_rate(mut self, sample_rate: impl Into<f32>) -> Self {
+        self.sample_rate = sample_rate.into();
+        self
+    }
+
+    /// Number of frames analyzed from the end of the buffer, must be a power
+    /// of two of at least two.
+    pub fn fft_size(mut self, fft_size: usize) -> Self {
+        assert!(
+            fft_size >= 2 && fft_size.is_power_of_two(),
+            "FFT size must be a power of two of at least two"
+        );
+        self.fft_size = fft_size;
+        self
+    }
+
+    pub fn width(mut self, width: impl Into<f32>) -> Self {
+        self.width = width.into();
+        self
+    }
+
+    pub fn height(mut self, height: impl Into<f32>) -> Self {
+        self.height = height.into();
+        self
+    }
+
+    pub fn mode(mut self, mode: SpectrumMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn bar_count(mut self, bar_count: usize) -> Self {
+        assert!(bar_count > 0);
+        self.bar_count = bar_count;
+        self
+    }
+
+    pub fn logarithmic(mut self, logarithmic: bool) -> Self {
+        self.logarithmic = logarithmic;
+        self
+    }
+
+    /// Displayed frequency range in Hz, defaults to 20 Hz up to the Nyquist
+    /// frequency.
+    pub fn frequency_range(mut self, frequency_range: RangeInclusive<f32>) -> Self {
+        self.frequency_range = Some(frequency_range);
+        self
+    }
+
+    pub fn decibel_range(mut self, decibel_range: RangeInclusive<f32>) -> Self {
+        self.decibel_range = decibel_range;
+        self
+    }
+
+    /// Time in seconds the peaks are held before decaying.
+    pub fn peak_hold(mut self, peak_hold: Option<f32>) -> Self {
+        self.peak_hold = peak_hold;
+        self
+    }
+
+    /// Time in seconds the peaks take to decay through the whole range.
+    pub fn peak_decay_time(mut self, peak_decay_time: impl Into<f32>) -> Self {
+        self.peak_decay_time = peak_decay_time.into();
+        self
+    }
+
+    pub fn show_grid(mut self, show_grid: bool) -> Self {
+        self.show_grid = show_grid;
+        self
+    }
+}
+
+impl<'a, SampleType> Widget for SpectrumAnalyzerWidget<'a, SampleType>
+where
+    SampleType: SampleRange<SampleType> + Copy,
+{
+    fn ui(self, ui: &mut Ui) -> Response {
+        let desired_size = vec2(self.width, self.height);
+        let (rect, response) = ui.allocate_exact_size(desired_size, Sense::hover());
+
+        let visuals = *ui.style().interact(&response);
+
+        let font_id = FontSelection::Default.resolve(ui.style());
+        let label_font_id = FontId::new(font_id.size * 0.75, font_id.family);
+
+        let frequency_range = self
+            .frequency_range
+            .clone()
+            .unwrap_or(20.0..=self.sample_rate / 2.0);
+
+        let spectrum_rect = rect.shrink(4.0);
+
+        // Normalized levels of the bars, or of the pixel columns in line mode
+        let levels = self.buffer.map(|buffer| {
+            let spectrum =
+                buffer_spectrum(buffer, self.buffer_layout, self.channels, self.fft_size);
+            let bin_width = self.sample_rate / self.fft_size as f32;
+
+            let slot_count = match self.mode {
+                SpectrumMode::Bars => self.bar_count,
+                SpectrumMode::Line => (spectrum_rect.width().round() as usize).max(2),
+            };
+
+            (0..slot_count)
+                .map(|slot| {
+                    let frequency_band = axis_to_frequency(
+                        slot as f32 / slot_count as f32,
+                        frequency_range.clone(),
+                        self.logarithmic,
+                    )
+                        ..=axis_to_frequency(
+                            (slot + 1) as f32 / slot_count as f32,
+                            frequency_range.clone(),
+                            self.logarithmic,
+                        );
+
+                    remap_clamp(
+                        magnitude_to_decibels(band_magnitude(&spectrum, bin_width, frequency_band)),
+                        self.decibel_range.clone(),
+                        0.0..=1.0,
+                    )
+                })
+                .collect_vec()
+        });
+
+        let peaks = levels.as_ref().and_then(|levels| {
+            self.peak_hold.map(|peak_hold| {
+                update_peaks(ui, response.id, levels, peak_hold, self.peak_decay_time)
+            })
+        });
+
+        if ui.is_rect_visible(rect) {
+            ui.painter().rect(
+                rect,
+                visuals.rounding,
+                ui.style().visuals.extreme_bg_color,
+                visuals.fg_stroke,
+            );
+
+            if self.show_grid {
+                let grid_stroke = Stroke::new(
+                    ui.style().visuals.noninteractive().fg_stroke.width,
+                    ui.style()
+                        .visuals
+                        .noninteractive()
+                        .fg_stroke
+                        .color
+                        .linear_multiply(0.25),
+                );
+
+                for (frequency, label) in
+                    frequency_gridlines(frequency_range.clone(), self.logarithmic)
+                {
+                    let x = spectrum_rect.left()
+                        + frequency_to_axis(frequency, frequency_range.clone(), self.logarithmic)
+                            * spectrum_rect.width();
+
+                    ui.painter().line_segment(
+                        [
+                            pos2(x, spectrum_rect.top()),
+                            pos2(x, spectrum_rect.bottom()),
+                        ],
+                        grid_stroke,
+                    );
+
+                    ui.painter().text(
+                        pos2(x + 2.0, spectrum_rect.top()),
+                        Align2::LEFT_TOP,
+                        label,
+                        label_font_id.clone(),
+                        ui.style().noninteractive().text_color(),
+                    );
+                }
+            }
+
+            let level_y =
+                |level: f32| remap_clamp(level, 0.0..=1.0, spectrum_rect.bottom_up_range());
+
+            if let Some(levels) = levels {
+                let slot_width = spectrum_rect.width() / levels.len() as f32;
+                let slot_x = |slot: usize| spectrum_rect.left() + slot as f32 * slot_width;
+
+                match self.mode {
+                    SpectrumMode::Bars => {
+                        for (slot, &level) in levels.iter().enumerate() {
+                            ui.painter().rect_filled(
+                                Rect::from_x_y_ranges(
+                                    slot_x(slot)..=(slot_x(slot + 1) - 1.0),
+                                    level_y(level)..=spectrum_rect.bottom(),
+                                ),
+                                0.0,
+                                visuals.fg_stroke.color,
+                            );
+                        }
+
+                        if let Some(peaks) = peaks {
+                            for (slot, &peak) in peaks.iter().enumerate() {
+                                ui.painter().line_segment(
+                                    [
+                                        pos2(slot_x(slot), level_y(peak)),
+                                        pos2(slot_x(slot + 1) - 1.0, level_y(peak)),
+                                    ],
+                                    ui.style().visuals.selection.stroke,
+                                );
+                            }
+                        }
+                    }
+                    SpectrumMode::Line => {
+                        let line_points = |levels: &[f32]| {
+                            levels
+                                .iter()
+                                .enumerate()
+                                .map(|(slot, &level)| {
+                                    pos2(slot_x(slot) + slot_width / 2.0, level_y(level))
+                                })
+                                .collect_vec()
+                        };
+
+                        if let Some(peaks) = peaks {
+                            ui.painter().add(Shape::line(
+                                line_points(&peaks),
+                                ui.style().visuals.selection.stroke,
+                            ));
+                        }
+
+                        ui.painter()
+                            .add(Shape::line(line_points(&levels), visuals.fg_stroke));
+                    }
+                }
+            }
+        }
+
+        response
+    }
+}
@@ -0,0 +1,590 @@
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+use egui::util::cache::{ComputerMut, FrameCache};
+use egui::Context;
+
+use crate::displays::sample_format::{Endianness, SampleFormat};
+use crate::displays::waveform_display::BufferLayout;
+use crate::displays::waveform_summary::WaveformSummary;
+
+// ----------------------------------------------------------------------------
+
+const WAVE_FORMAT_PCM: u16 = 0x0001;
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 0x0003;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+#[derive(Debug)]
+pub enum WavError {
+    Io(io::Error),
+    InvalidHeader,
+    MissingChunk(&'static str),
+    UnsupportedFormat {
+        format_tag: u16,
+        bits_per_sample: u16,
+    },
+}
+
+impl fmt::Display for WavError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WavError::Io(err) => write!(f, "{err}"),
+            WavError::InvalidHeader => write!(f, "Not a RIFF/WAVE file"),
+            WavError::MissingChunk(chunk_id) => write!(f, "Missing \"{chunk_id}\" chunk"),
+            WavError::UnsupportedFormat {
+                format_tag,
+                bits_per_sample,
+            } => write!(
+                f,
+                "Unsupported sample format (format tag {format_tag:#06X}, {bits_per_sample} bits)"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for WavError {}
+
+impl From<io::Error> for WavError {
+    fn from(err: io::Error) -> Self {
+        WavError::Io(err)
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+struct ChunkReader<'a> {
+    bytes: &'a [u8],
+    endianness: Endianness,
+}
+
+impl<'a> ChunkReader<'a> {
+    fn take(&mut self, length: usize) -> Option<&'a [u8]> {
+        if self.bytes.len() < length {
+            return None;
+        }
+
+        let (head, tail) = self.bytes.split_at(length);
+        self.bytes = tail;
+        Some(head)
+    }
+
+    fn read_u16(&mut self) -> Option<u16> {
+        let bytes = self.take(2)?.try_into().ok()?;
+        Some(match self.endianness {
+            Endianness::Little => u16::from_le_bytes(bytes),
+            Endianness::Big => u16::from_be_bytes(bytes),
+        })
+    }
+
+    fn read_u32(&mut self) -> Option<u32> {
+        let bytes = self.take(4)?.try_into().ok()?;
+        Some(match self.endianness {
+            Endianness::Little => u32::from_le_bytes(bytes),
+            Endianness::Big => u32::from_be_bytes(bytes),
+        })
+    }
+
+    /// Next chunk id and data, truncated when the file ends early.
+    fn read_chunk(&mut self) -> Option<(&'a [u8], &'a [u8])> {
+        let chunk_id = self.take(4)?;
+        let chunk_size = self.read_u32()? as usize;
+
+        let chunk_data = self.take(chunk_size.min(self.bytes.len()))?;
+        if chunk_size % 2 == 1 {
+            self.take(1);
+        }
+
+        Some((chunk_id, chunk_data))
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Decoded PCM or floating point WAV file, ready to be drawn with
+/// `WaveformDisplayWidget::summary`.
+pub struct WavFile {
+    sample_format: SampleFormat,
+    sample_rate: u32,
+    summary: WaveformSummary<f32>,
+}
+
+impl WavFile {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, WavError> {
+        let endianness = match bytes.get(0..4) {
+            Some(b"RIFF") => Endianness::Little,
+            Some(b"RIFX") => Endianness::Big,
+            _ => return Err(WavError::InvalidHeader),
+        };
+
+        // Skipping the RIFF chunk id and size
+        let mut reader = ChunkReader {
+            bytes: bytes.get(8..).ok_or(WavError::InvalidHeader)?,
+            endianness,
+        };
+
+        if reader.take(4) != Some(b"WAVE") {
+            return Err(WavError::InvalidHeader);
+        }
+
+        let mut format_chunk = None;
+        let mut data_chunk = None;
+
+        while let Some((chunk_id, chunk_data)) = reader.read_chunk() {
+            match chunk_id {
+                b"fmt " => format_chunk = Some(chunk_data),
+                b"data" => data_chunk = Some(chunk_data),
+                _ => {}
+            }
+        }
+
+        let mut format_reader = ChunkReader {
+            bytes: format_chunk.ok_or(WavError::MissingChunk("fmt "))?,
+            endianness,
+        };
+        let data_chunk = data_chunk.ok_or(WavError::MissingChunk("data"))?;
+
+        let mut read_format = || -> Option<_> {
+            let mut format_tag = format_reader.read_u16()?;
+            let channels = format_reader.read_u16()?;
+            let sample_rate = format_reader.read_u32()?;
+            let _byte_rate = format_reader.read_u32()?;
+            let _block_align = format_reader.read_u16()?;
+            let bits_per_sample = format_reader.read_u16()?;
+
+            // The actual format tag is at the start of the sub-format GUID
+            if format_tag == WAVE_FORMAT_EXTENSIBLE {
+                let _extension_size = format_reader.read_u16()?;
+                let _valid_bits_per_sample = format_reader.read_u16()?;
+                let _channel_mask = format_reader.read_u32()?;
+                format_tag = format_reader.read_u16()?;
+            }
+
+            Some((format_tag, channels, sample_rate, bits_per_sample))
+        };
+
+        let (format_tag, channels, sample_rate, bits_per_sample) =
+            read_format().ok_or(WavError::InvalidHeader)?;
+
+        let sample_format = match (format_tag, bits_per_sample) {
+            (WAVE_FORMAT_PCM, 8) => SampleFormat::U8,
+            (WAVE_FORMAT_PCM, 16) => SampleFormat::I16,
+            (WAVE_FORMAT_PCM, 24) => SampleFormat::I24,
+            (WAVE_FORMAT_PCM, 32) => SampleFormat::I32,
+            (WAVE_FORMAT_IEEE_FLOAT, 32) => SampleFormat::F32,
+            (WAVE_FORMAT_IEEE_FLOAT, 64) => SampleFormat::F64,
+            _ => {
+                return Err(WavError::UnsupportedFormat {
+                    format_tag,
+                    bits_per_sample,
+                })
+            }
+        };
+
+        if channels == 0 {
+            return Err(WavError::InvalidHeader);
+        }
+
+        // Drop the trailing partial frame of truncated files
+        let frame_size = sample_format.bytes_per_sample() * channels as usize;
+        let data_chunk = &data_chunk[..(data_chunk.len() / frame_size * frame_size)];
+
+        let samples = sample_format.decode(data_chunk, endianness);
+
+        Ok(Self {
+            sample_format,
+            sample_rate,
+            summary: WaveformSummary::new(&samples, channels as usize, BufferLayout::Interleaved),
+        })
+    }
+
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, WavError> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    /// Same as [`WavFile::open`], but the file is only decoded once while it
+    /// keeps being displayed.
+    pub fn cached_open(ctx: &Context, path: &Path) -> Arc<Result<Self, WavError>> {
+        ctx.memory_mut(|memory| memory.caches.cache::<WavFileCache<'_>>().get(path))
+    }
+
+    /// Same as [`WavFile::from_bytes`], but the bytes are only decoded once
+    /// while they keep being displayed.
+    pub fn cached_from_bytes(ctx: &Context, bytes: &[u8]) -> Arc<Result<Self, WavError>> {
+        ctx.memory_mut(|memory| memory.caches.cache::<WavFileCache<'_>>().get(bytes))
+    }
+
+    pub fn sample_format(&self) -> SampleFormat {
+        self.sample_format
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn channels(&self) -> usize {
+        self.summary.channels()
+    }
+
+    /// Length of the file in frames.
+    pub fn len(&self) -> usize {
+        self.summary.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.summary.is_empty()
+    }
+
+    /// Length of the file in seconds.
+    pub fn duration(&self) -> f32 {
+        self.summary.len() as f32 / self.sample_rate as f32
+    }
+
+    /// Peak overview of the whole file.
+    pub fn summary(&self) -> &WaveformSummary<f32> {
+        &self.summary
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+type WavFileCacheValue = Arc<Result<WavFile, WavError>>;
+
+type WavFileCache<'a> = FrameCache<WavFileCacheValue, WavFileComputer>;
+
+#[derive(Default)]
+struct WavFileComputer;
+
+impl ComputerMut<&Path, WavFileCacheValue> for WavFileComputer {
+    fn compute(&mut self, key: &Path) -> WavFileCacheValue {
+        Arc::new(WavFile::open(key))
+    }
+}
+
+impl ComputerMut<&[u8], WavFileCacheValue> for WavFileComputer {
+    fn compute(&mut self, key: &[u8]) -> WavFileCacheValue {
+        Arc::new(WavFile::from_bytes(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ChunkReader, WavError, WavFile, WAVE_FORMAT_EXTENSIBLE, WAVE_FORMAT_IEEE_FLOAT,
+        WAVE_FORMAT_PCM,
+    };
+    use crate::displays::sample_format::{Endianness, SampleFormat};
+
+    fn u16_bytes(value: u16, endianness: Endianness) -> [u8; 2] {
+        match endianness {
+            Endianness::Little => value.to_le_bytes(),
+            Endianness::Big => value.to_be_bytes(),
+        }
+    }
+
+    fn u32_bytes(value: u32, endianness: Endianness) -> [u8; 4] {
+        match endianness {
+            Endianness::Little => value.to_le_bytes(),
+            Endianness::Big => value.to_be_bytes(),
+        }
+    }
+
+    fn chunk(chunk_id: &[u8; 4], chunk_data: &[u8], endianness: Endianness) -> Vec<u8> {
+        let mut bytes = chunk_id.to_vec();
+        bytes.extend(u32_bytes(chunk_data.len() as u32, endianness));
+        bytes.extend(chunk_data);
+
+        if chunk_data.len() % 2 == 1 {
+            bytes.push(0);
+        }
+
+        bytes
+    }
+
+    fn format_chunk(
+        format_tag: u16,
+        channels: u16,
+        bits_per_sample: u16,
+        endianness: Endianness,
+    ) -> Vec<u8> {
+        let block_align = channels * bits_per_sample / 8;
+
+        let mut chunk_data = Vec::new();
+        chunk_data.extend(u16_bytes(format_tag, endianness));
+        chunk_data.extend(u16_bytes(channels, endianness));
+        chunk_data.extend(u32_bytes(44100, endianness));
+        chunk_data.extend(u32_bytes(44100 * block_align as u32, endianness));
+        chunk_data.extend(u16_bytes(block_align, endianness));
+        chunk_data.extend(u16_bytes(bits_per_sample, endianness));
+
+        chunk(b"fmt ", &chunk_data, endianness)
+    }
+
+    fn extensible_format_chunk(
+        sub_format_tag: u16,
+        channels: u16,
+        bits_per_sample: u16,
+    ) -> Vec<u8> {
+        let mut chunk_data = format_chunk(
+            WAVE_FORMAT_EXTENSIBLE,
+            channels,
+            bits_per_sample,
+            Endianness::Little,
+        )[8..]
+            .to_vec();
+        chunk_data.extend(22u16.to_le_bytes());
+        chunk_data.extend(bits_per_sample.to_le_bytes());
+        chunk_data.extend(0x3u32.to_le_bytes());
+
+        // KSDATAFORMAT_SUBTYPE_* GUID
+        chunk_data.extend(sub_format_tag.to_le_bytes());
+        chunk_data.extend([
+            0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71,
+        ]);
+
+        chunk(b"fmt ", &chunk_data, Endianness::Little)
+    }
+
+    fn wav_bytes(chunks: &[Vec<u8>], endianness: Endianness) -> Vec<u8> {
+        let chunks = chunks.concat();
+
+        let mut bytes = match endianness {
+            Endianness::Little => b"RIFF".to_vec(),
+            Endianness::Big => b"RIFX".to_vec(),
+        };
+        bytes.extend(u32_bytes(4 + chunks.len() as u32, endianness));
+        bytes.extend(b"WAVE");
+        bytes.extend(chunks);
+        bytes
+    }
+
+    fn i16_data(samples: &[i16], endianness: Endianness) -> Vec<u8> {
+        samples
+            .iter()
+            .flat_map(|&sample| u16_bytes(sample as u16, endianness))
+            .collect()
+    }
+
+    fn stereo_i16_wav(endianness: Endianness) -> Vec<u8> {
+        wav_bytes(
+            &[
+                format_chunk(WAVE_FORMAT_PCM, 2, 16, endianness),
+                chunk(
+                    b"data",
+                    &i16_data(&[i16::MIN, 0x4000, 0, -0x2000], endianness),
+                    endianness,
+                ),
+            ],
+            endianness,
+        )
+    }
+
+    #[test]
+    fn riff_files_are_little_endian() {
+        let wav_file = WavFile::from_bytes(&stereo_i16_wav(Endianness::Little)).unwrap();
+
+        assert_eq!(wav_file.sample_format(), SampleFormat::I16);
+        assert_eq!(wav_file.sample_rate(), 44100);
+        assert_eq!(wav_file.channels(), 2);
+        assert_eq!(wav_file.len(), 2);
+        assert_eq!(wav_file.summary().samples(0), [-1.0, 0.0]);
+        assert_eq!(wav_file.summary().samples(1), [0.5, -0.25]);
+    }
+
+    #[test]
+    fn rifx_files_are_big_endian() {
+        let wav_file = WavFile::from_bytes(&stereo_i16_wav(Endianness::Big)).unwrap();
+
+        assert_eq!(wav_file.sample_format(), SampleFormat::I16);
+        assert_eq!(wav_file.sample_rate(), 44100);
+        assert_eq!(wav_file.summary().samples(0), [-1.0, 0.0]);
+        assert_eq!(wav_file.summary().samples(1), [0.5, -0.25]);
+    }
+
+    #[test]
+    fn extensible_format_uses_the_sub_format() {
+        let bytes = wav_bytes(
+            &[
+                extensible_format_chunk(WAVE_FORMAT_IEEE_FLOAT, 1, 32),
+                chunk(
+                    b"data",
+                    &[0.25f32.to_le_bytes(), (-0.75f32).to_le_bytes()].concat(),
+                    Endianness::Little,
+                ),
+            ],
+            Endianness::Little,
+        );
+
+        let wav_file = WavFile::from_bytes(&bytes).unwrap();
+        assert_eq!(wav_file.sample_format(), SampleFormat::F32);
+        assert_eq!(wav_file.summary().samples(0), [0.25, -0.75]);
+
+        let bytes = wav_bytes(
+            &[
+                extensible_format_chunk(WAVE_FORMAT_PCM, 1, 24),
+                chunk(b"data", &[0x00, 0x00, 0x40], Endianness::Little),
+            ],
+            Endianness::Little,
+        );
+
+        let wav_file = WavFile::from_bytes(&bytes).unwrap();
+        assert_eq!(wav_file.sample_format(), SampleFormat::I24);
+        assert_eq!(wav_file.summary().samples(0), [0.5]);
+    }
+
+    #[test]
+    fn odd_chunks_are_padded() {
+        let bytes = wav_bytes(
+            &[
+                chunk(b"LIST", b"odd", Endianness::Little),
+                format_chunk(WAVE_FORMAT_PCM, 1, 8, Endianness::Little),
+                chunk(b"data", &[0x00, 0x80, 0xC0], Endianness::Little),
+                chunk(b"cue ", b"x", Endianness::Little),
+            ],
+            Endianness::Little,
+        );
+
+        let wav_file = WavFile::from_bytes(&bytes).unwrap();
+        assert_eq!(wav_file.sample_format(), SampleFormat::U8);
+        assert_eq!(wav_file.summary().samples(0), [-1.0, 0.0, 0.5]);
+    }
+
+    #[test]
+    fn truncated_data_chunk_is_read_up_to_the_end_of_the_file() {
+        let mut bytes = stereo_i16_wav(Endianness::Little);
+
+        // Announcing a longer data chunk than what's left in the file
+        let data_size_offset = bytes.len() - 8 - 4;
+        bytes[data_size_offset..][..4].copy_from_slice(&1000u32.to_le_bytes());
+
+        let wav_file = WavFile::from_bytes(&bytes).unwrap();
+        assert_eq!(wav_file.len(), 2);
+        assert_eq!(wav_file.summary().samples(1), [0.5, -0.25]);
+    }
+
+    #[test]
+    fn trailing_partial_frame_is_dropped() {
+        let mut bytes = stereo_i16_wav(Endianness::Little);
+        bytes.truncate(bytes.len() - 3);
+
+        let wav_file = WavFile::from_bytes(&bytes).unwrap();
+        assert_eq!(wav_file.len(), 1);
+        assert_eq!(wav_file.summary().samples(0), [-1.0]);
+        assert_eq!(wav_file.summary().samples(1), [0.5]);
+    }
+
+    #[test]
+    fn invalid_headers_are_rejected() {
+        let mut not_wave = stereo_i16_wav(Endianness::Little);
+        not_wave[8..12].copy_from_slice(b"AVI ");
+
+        let mut zero_channels = stereo_i16_wav(Endianness::Little);
+        zero_channels[22..24].copy_from_slice(&0u16.to_le_bytes());
+
+        let short_format = wav_bytes(
+            &[
+                chunk(b"fmt ", &[0x01, 0x00, 0x01, 0x00], Endianness::Little),
+                chunk(b"data", &[0x80], Endianness::Little),
+            ],
+            Endianness::Little,
+        );
+
+        for bytes in [
+            &b""[..],
+            b"RIFF",
+            b"RIFF\x04\x00\x00\x00",
+            b"RIFF\x04\x00\x00\x00WAV",
+            b"FORM\x04\x00\x00\x00WAVE",
+            &not_wave,
+            &zero_channels,
+            &short_format,
+        ] {
+            assert!(matches!(
+                WavFile::from_bytes(bytes),
+                Err(WavError::InvalidHeader)
+            ));
+        }
+    }
+
+    #[test]
+    fn missing_chunks_are_reported() {
+        let without_format = wav_bytes(
+            &[chunk(b"data", &[0x80], Endianness::Little)],
+            Endianness::Little,
+        );
+        assert!(matches!(
+            WavFile::from_bytes(&without_format),
+            Err(WavError::MissingChunk("fmt "))
+        ));
+
+        let without_data = wav_bytes(
+            &[format_chunk(WAVE_FORMAT_PCM, 1, 8, Endianness::Little)],
+            Endianness::Little,
+        );
+        assert!(matches!(
+            WavFile::from_bytes(&without_data),
+            Err(WavError::MissingChunk("data"))
+        ));
+    }
+
+    #[test]
+    fn unsupported_formats_are_reported() {
+        for (format_tag, bits_per_sample) in [
+            (WAVE_FORMAT_PCM, 12),
+            (WAVE_FORMAT_IEEE_FLOAT, 16),
+            (0x0002, 4),
+        ] {
+            let bytes = wav_bytes(
+                &[
+                    format_chunk(format_tag, 1, bits_per_sample, Endianness::Little),
+                    chunk(b"data", &[0x00, 0x00], Endianness::Little),
+                ],
+                Endianness::Little,
+            );
+
+            assert!(matches!(
+                WavFile::from_bytes(&bytes),
+                Err(WavError::UnsupportedFormat {
+                    format_tag: tag,
+                    bits_per_sample: bits,
+                }) if tag == format_tag && bits == bits_per_sample
+            ));
+        }
+    }
+
+    #[test]
+    fn chunk_reader_skips_padding_and_truncates() {
+        let bytes = [
+            chunk(b"abcd", b"xyz", Endianness::Big),
+            chunk(b"efgh", b"", Endianness::Big),
+            b"ijkl\x00\x00\x00\x10tail".to_vec(),
+        ]
+        .concat();
+
+        let mut reader = ChunkReader {
+            bytes: &bytes,
+            endianness: Endianness::Big,
+        };
+
+        assert_eq!(reader.read_chunk(), Some((&b"abcd"[..], &b"xyz"[..])));
+        assert_eq!(reader.read_chunk(), Some((&b"efgh"[..], &b""[..])));
+        assert_eq!(reader.read_chunk(), Some((&b"ijkl"[..], &b"tail"[..])));
+        assert_eq!(reader.read_chunk(), None);
+    }
+
+    #[test]
+    fn chunk_reader_stops_at_partial_headers() {
+        let mut reader = ChunkReader {
+            bytes: b"abcd\x02\x00",
+            endianness: Endianness::Little,
+        };
+        assert_eq!(reader.read_chunk(), None);
+
+        let mut reader = ChunkReader {
+            bytes: b"ab",
+            endianness: Endianness::Little,
+        };
+        assert_eq!(reader.read_chunk(), None);
+    }
+}
@@ -1,9 +1,17 @@
-use std::ops::RangeInclusive;
+use std::borrow::Cow;
+use std::ops::{Range, RangeInclusive};
 
+use ecolor::Hsva;
 use egui::{
-    pos2, remap_clamp, vec2, Align2, FontSelection, Rect, Response, Sense, Shape, Ui, Widget,
+    lerp, pos2, remap, remap_clamp, vec2, Align2, Color32, FontId, FontSelection, Mesh,
+    PointerButton, Rect, Response, Sense, Shape, Stroke, Ui, Vec2, Widget,
 };
-use itertools::Itertools;
+use itertools::{Either, Itertools};
+use strum::{Display, EnumIter};
+
+use crate::displays::sample_format::{Endianness, SampleFormat};
+use crate::displays::waveform_ring_buffer::{AtomicSample, WaveformRingBuffer};
+use crate::displays::waveform_summary::{peak_envelope, WaveformSummary};
 
 // ----------------------------------------------------------------------------
 
@@ -28,11 +36,8 @@ pub enum SignalEdge {
 }
 
 impl SignalEdge {
-    fn from_samples<SampleType>(sample_a: &SampleType, sample_b: &SampleType) -> Option<SignalEdge>
-    where
-        SampleType: SampleRange<SampleType> + PartialOrd,
-    {
-        match (*sample_a >= SampleType::ZERO, *sample_b >= SampleType::ZERO) {
+    fn from_samples(sample_a: f32, sample_b: f32, level: f32) -> Option<SignalEdge> {
+        match (sample_a >= level, sample_b >= level) {
             (false, true) => Some(SignalEdge::RisingEdge),
             (true, false) => Some(SignalEdge::FallingEdge),
             _ => None,
@@ -42,57 +47,275 @@ impl SignalEdge {
 
 // ----------------------------------------------------------------------------
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, Display, EnumIter, Eq, PartialEq)]
+pub enum TriggerEdge {
+    #[strum(to_string = "Rising")]
+    Rising,
+
+    #[strum(to_string = "Falling")]
+    Falling,
+
+    #[strum(to_string = "Both")]
+    Both,
+}
+
+impl TriggerEdge {
+    fn matches(self, signal_edge: SignalEdge) -> bool {
+        match self {
+            TriggerEdge::Rising => signal_edge == SignalEdge::RisingEdge,
+            TriggerEdge::Falling => signal_edge == SignalEdge::FallingEdge,
+            TriggerEdge::Both => true,
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, Display, EnumIter, Eq, PartialEq)]
+pub enum TriggerMode {
+    /// Free-runs when there's nothing to trigger on.
+    #[strum(to_string = "Auto")]
+    Auto,
+
+    /// Holds the last triggered waveform when there's nothing to trigger on.
+    #[strum(to_string = "Normal")]
+    Normal,
+
+    /// Holds the first triggered waveform until re-armed.
+    #[strum(to_string = "Single")]
+    Single,
+}
+
+#[derive(Clone, Default)]
+struct TriggerState {
+    window: Option<Vec<f32>>,
+    capture_time: Option<f64>,
+}
+
+// ----------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, Display, EnumIter, Eq, PartialEq)]
+pub enum WaveformDisplayMode {
+    /// Channels drawn over time.
+    #[strum(to_string = "Waveform")]
+    Waveform,
+
+    /// Stereo phase view, with the left channel on the horizontal axis and
+    /// the right channel on the vertical axis.
+    #[strum(to_string = "Lissajous")]
+    Lissajous,
+
+    /// Stereo phase view rotated by 45 degrees, mono signals draw a vertical
+    /// line.
+    #[strum(to_string = "Goniometer")]
+    Goniometer,
+}
+
+#[derive(Clone, Copy, Debug, Display, EnumIter, Eq, PartialEq)]
+pub enum ChannelLayout {
+    /// Channels in equal columns next to each other.
+    #[strum(to_string = "Side by side")]
+    SideBySide,
+
+    /// Channels in equal lanes above each other, sharing the time ruler of
+    /// the bottom lane.
+    #[strum(to_string = "Stacked")]
+    Stacked,
+
+    /// Channels drawn over each other, each in its own color.
+    #[strum(to_string = "Overlaid")]
+    Overlaid,
+}
+
+/// Correlation of two channels, from -1.0 (out of phase) through 0.0
+/// (unrelated or silent) to 1.0 (mono).
+fn stereo_correlation(left: &[f32], right: &[f32]) -> f32 {
+    let (left_right, left_left, right_right) = left.iter().zip(right).fold(
+        (0.0, 0.0, 0.0),
+        |(left_right, left_left, right_right), (&left, &right)| {
+            (
+                left_right + left * right,
+                left_left + left * left,
+                right_right + right * right,
+            )
+        },
+    );
+
+    let energy = (left_left * right_right).sqrt();
+    if energy > 0.0 {
+        (left_right / energy).clamp(-1.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum BufferLayout {
     Planar,
     Interleaved,
 }
 
+impl BufferLayout {
+    pub(crate) fn channel_samples<'b, T: Copy>(
+        self,
+        buffer: &'b [T],
+        channels: usize,
+        channel_id: usize,
+    ) -> impl Iterator<Item = T> + 'b {
+        let channel_buffer_length = buffer.len() / channels;
+
+        match self {
+            BufferLayout::Planar => Either::Left(
+                buffer
+                    .iter()
+                    .copied()
+                    .skip(channel_id * channel_buffer_length)
+                    .take(channel_buffer_length),
+            ),
+            BufferLayout::Interleaved => Either::Right(
+                buffer
+                    .iter()
+                    .copied()
+                    .skip(channel_id)
+                    .step_by(channels)
+                    .take(channel_buffer_length),
+            ),
+        }
+    }
+}
+
 // ----------------------------------------------------------------------------
 
 pub trait SampleRange<T> {
     const ZERO: T;
     const DISPLAY_RANGE: RangeInclusive<f32>;
+
+    /// Sample value in the same units as `DISPLAY_RANGE`.
+    fn to_f32(self) -> f32;
 }
 
 impl SampleRange<u8> for u8 {
     const ZERO: u8 = 128;
     const DISPLAY_RANGE: RangeInclusive<f32> = 0.0..=255.0;
+
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
 }
 
 impl SampleRange<i8> for i8 {
     const ZERO: i8 = 0;
     const DISPLAY_RANGE: RangeInclusive<f32> = -128.0..=127.0;
+
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
 }
 
 impl SampleRange<u16> for u16 {
     const ZERO: u16 = 32768;
     const DISPLAY_RANGE: RangeInclusive<f32> = 0.0..=65535.0;
+
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
 }
 
 impl SampleRange<i16> for i16 {
     const ZERO: i16 = 0;
     const DISPLAY_RANGE: RangeInclusive<f32> = -32768.0..=32767.0;
+
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
 }
 
 impl SampleRange<u32> for u32 {
     const ZERO: u32 = 2147483648;
     const DISPLAY_RANGE: RangeInclusive<f32> = 0.0..=4294967295.0;
+
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
 }
 
 impl SampleRange<i32> for i32 {
     const ZERO: i32 = 0;
     const DISPLAY_RANGE: RangeInclusive<f32> = -2147483648.0..=2147483647.0;
+
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
 }
 
 impl SampleRange<f32> for f32 {
     const ZERO: f32 = 0.0;
     const DISPLAY_RANGE: RangeInclusive<f32> = -1.0..=1.0;
+
+    fn to_f32(self) -> f32 {
+        self
+    }
 }
 
 impl SampleRange<f64> for f64 {
     const ZERO: f64 = 0.0;
     const DISPLAY_RANGE: RangeInclusive<f32> = -1.0..=1.0;
+
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Shortest stretch of a recording the view can be zoomed into, in frames.
+const MINIMUM_VIEW_LENGTH: f64 = 16.0;
+
+/// Visible part of a recording, in frames.
+#[derive(Clone, Copy, Debug)]
+struct WaveformView {
+    start: f64,
+    length: f64,
+}
+
+impl WaveformView {
+    fn frame_at(&self, rect: Rect, x: f32) -> f64 {
+        self.start + ((x - rect.left()) / rect.width()) as f64 * self.length
+    }
+
+    fn x_at(&self, rect: Rect, frame: f64) -> f32 {
+        rect.left() + ((frame - self.start) / self.length) as f32 * rect.width()
+    }
+
+    fn pan(&mut self, rect: Rect, delta_x: f32) {
+        self.start -= (delta_x / rect.width()) as f64 * self.length;
+    }
+
+    fn clamp(&mut self, recording_length: f64, minimum_length: f64) {
+        self.length = self
+            .length
+            .clamp(minimum_length.min(recording_length), recording_length);
+        self.start = self.start.clamp(0.0, recording_length - self.length);
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum WaveformDrag {
+    Cursor,
+    Selection(usize),
+}
+
+/// Rounds `step` up to the next 1, 2 or 5 times power of ten.
+fn nice_step(step: f64) -> f64 {
+    let magnitude = 10.0f64.powf(step.log10().floor());
+
+    [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|factor| factor * magnitude)
+        .find(|&nice_step| nice_step >= step)
+        .unwrap_or(10.0 * magnitude)
 }
 
 // ----------------------------------------------------------------------------
@@ -104,6 +327,9 @@ where
 {
     get_set_value: GetSetValue<'a>,
     buffer: Option<&'a [SampleType]>,
+    decoded_buffer: Option<Vec<SampleType>>,
+    ring_buffer: Option<&'a WaveformRingBuffer<SampleType>>,
+    summary: Option<&'a WaveformSummary<SampleType>>,
     buffer_layout: BufferLayout,
     channels: usize,
     window_size: Option<usize>,
@@ -113,6 +339,22 @@ where
     channel_names: Option<Vec<String>>,
     show_header: bool,
     waveform_resolution: usize,
+    peak_decimation: bool,
+    trigger_edge: TriggerEdge,
+    trigger_level: Option<f32>,
+    trigger_holdoff: f32,
+    trigger_mode: TriggerMode,
+    rearm_trigger: bool,
+    cursor: Option<&'a mut usize>,
+    selection: Option<&'a mut Option<Range<usize>>>,
+    sample_rate: Option<f32>,
+    show_ruler: bool,
+    show_grid: bool,
+    display_mode: WaveformDisplayMode,
+    channel_layout: ChannelLayout,
+    channel_colors: Option<Vec<Color32>>,
+    channel_mute: Option<&'a mut [bool]>,
+    channel_solo: Option<&'a mut [bool]>,
 }
 
 impl<'a, SampleType> WaveformDisplayWidget<'a, SampleType>
@@ -132,6 +374,9 @@ where
         Self {
             get_set_value: Box::new(get_set_value),
             buffer: None,
+            decoded_buffer: None,
+            ring_buffer: None,
+            summary: None,
             buffer_layout: BufferLayout::Interleaved,
             channels: 1,
             window_size: None,
@@ -141,6 +386,22 @@ where
             channel_names: None,
             show_header: true,
             waveform_resolution: 1,
+            peak_decimation: false,
+            trigger_edge: TriggerEdge::Rising,
+            trigger_level: None,
+            trigger_holdoff: 0.0,
+            trigger_mode: TriggerMode::Auto,
+            rearm_trigger: false,
+            cursor: None,
+            selection: None,
+            sample_rate: None,
+            show_ruler: false,
+            show_grid: false,
+            display_mode: WaveformDisplayMode::Waveform,
+            channel_layout: ChannelLayout::SideBySide,
+            channel_colors: None,
+            channel_mute: None,
+            channel_solo: None,
         }
     }
 
@@ -149,6 +410,24 @@ where
         self
     }
 
+    /// Reads the samples directly from a ring buffer filled by another thread,
+    /// overriding the buffer, buffer layout and channel count settings.
+    pub fn ring_buffer(mut self, ring_buffer: &'a WaveformRingBuffer<SampleType>) -> Self
+    where
+        SampleType: AtomicSample,
+    {
+        self.ring_buffer = Some(ring_buffer);
+        self
+    }
+
+    /// Draws a whole recording from its precomputed summary, overriding the
+    /// buffer and channel count settings. Triggering is not available in
+    /// this mode.
+    pub fn summary(mut self, summary: &'a WaveformSummary<SampleType>) -> Self {
+        self.summary = Some(summary);
+        self
+    }
+
     pub fn buffer_layout(mut self, buffer_layout: BufferLayout) -> Self {
         self.buffer_layout = buffer_layout;
         self
@@ -193,17 +472,416 @@ where
         self.waveform_resolution = waveform_resolution;
         self
     }
+
+    /// Draws the minimum and maximum of the samples falling into each pixel
+    /// column as a filled envelope, instead of a line through every
+    /// `waveform_resolution`-th sample, when there are more samples than pixels.
+    /// Waveforms with a [`WaveformSummary`] are always drawn this way.
+    pub fn peak_decimation(mut self, peak_decimation: bool) -> Self {
+        self.peak_decimation = peak_decimation;
+        self
+    }
+
+    pub fn trigger_edge(mut self, trigger_edge: TriggerEdge) -> Self {
+        self.trigger_edge = trigger_edge;
+        self
+    }
+
+    /// Trigger level in sample units, defaults to the zero level of the
+    /// sample type.
+    pub fn trigger_level(mut self, trigger_level: impl Into<f32>) -> Self {
+        self.trigger_level = Some(trigger_level.into());
+        self
+    }
+
+    /// Time in seconds a triggered waveform is held before triggering again.
+    pub fn trigger_holdoff(mut self, trigger_holdoff: impl Into<f32>) -> Self {
+        self.trigger_holdoff = trigger_holdoff.into();
+        self
+    }
+
+    pub fn trigger_mode(mut self, trigger_mode: TriggerMode) -> Self {
+        self.trigger_mode = trigger_mode;
+        self
+    }
+
+    /// Discards the held waveform and re-arms the trigger, eg. for taking
+    /// another capture in single mode.
+    pub fn rearm_trigger(mut self, rearm_trigger: bool) -> Self {
+        self.rearm_trigger = rearm_trigger;
+        self
+    }
+
+    /// Frame position of a cursor, placed by clicking on the waveform and
+    /// moved by dragging it. Only available when drawing a summary, which can
+    /// also be zoomed with the mouse wheel and panned by dragging with the
+    /// secondary or middle mouse button.
+    pub fn cursor(mut self, cursor: &'a mut usize) -> Self {
+        self.cursor = Some(cursor);
+        self
+    }
+
+    /// Frame range selected by dragging over the waveform, cleared by
+    /// clicking. Only available when drawing a summary.
+    pub fn selection(mut self, selection: &'a mut Option<Range<usize>>) -> Self {
+        self.selection = Some(selection);
+        self
+    }
+
+    /// Sample rate in Hz, the time ruler is labeled in seconds instead of
+    /// frames when set.
+    pub fn sample_rate(mut self, sample_rate: impl Into<f32>) -> Self {
+        self.sample_rate = Some(sample_rate.into());
+        self
+    }
+
+    pub fn show_ruler(mut self, show_ruler: bool) -> Self {
+        self.show_ruler = show_ruler;
+        self
+    }
+
+    pub fn show_grid(mut self, show_grid: bool) -> Self {
+        self.show_grid = show_grid;
+        self
+    }
+
+    /// Draws a stereo buffer as a Lissajous or goniometer phase view with a
+    /// correlation meter, instead of side by side waveforms. Not available
+    /// when drawing a summary or other than stereo buffers.
+    pub fn display_mode(mut self, display_mode: WaveformDisplayMode) -> Self {
+        self.display_mode = display_mode;
+        self
+    }
+
+    pub fn channel_layout(mut self, channel_layout: ChannelLayout) -> Self {
+        self.channel_layout = channel_layout;
+        self
+    }
+
+    /// Waveform color of each channel, defaults to the foreground color, or
+    /// to evenly spaced hues when the channels are overlaid.
+    pub fn channel_colors(mut self, channel_colors: &[Color32]) -> Self {
+        self.channel_colors = Some(channel_colors.to_vec());
+        self
+    }
+
+    /// Per-channel mute flags, toggled by the "M" buttons in the channel
+    /// headers.
+    pub fn channel_mute(mut self, channel_mute: &'a mut [bool]) -> Self {
+        self.channel_mute = Some(channel_mute);
+        self
+    }
+
+    /// Per-channel solo flags, toggled by the "S" buttons in the channel
+    /// headers. While any channel is soloed, the others are drawn as muted.
+    pub fn channel_solo(mut self, channel_solo: &'a mut [bool]) -> Self {
+        self.channel_solo = Some(channel_solo);
+        self
+    }
+}
+
+impl<'a> WaveformDisplayWidget<'a, f32> {
+    /// Decodes the samples from raw bytes, eg. the data chunk of a WAV file,
+    /// overriding the buffer setting. The buffer layout and channel count
+    /// settings still apply.
+    pub fn byte_buffer(
+        mut self,
+        bytes: &[u8],
+        sample_format: SampleFormat,
+        endianness: Endianness,
+    ) -> Self {
+        self.decoded_buffer = Some(sample_format.decode(bytes, endianness));
+        self
+    }
 }
 
 impl<'a, SampleType> Widget for WaveformDisplayWidget<'a, SampleType>
 where
-    SampleType: SampleRange<SampleType> + Into<f32> + Copy + PartialOrd,
+    SampleType: SampleRange<SampleType> + AtomicSample + Copy + PartialOrd + Send + Sync + 'static,
 {
     fn ui(mut self, ui: &mut Ui) -> Response {
         let desired_size = vec2(self.width, self.height);
-        let (rect, mut response) = ui.allocate_exact_size(desired_size, Sense::click());
 
-        if response.clicked() {
+        // Navigating by dragging is only available when drawing a summary
+        let sense = if self.summary.is_some() {
+            Sense::click_and_drag()
+        } else {
+            Sense::click()
+        };
+
+        let (rect, mut response) = ui.allocate_exact_size(desired_size, sense);
+
+        // Snapshots reuse the allocation of the previous frame
+        let snapshot_id = response.id.with("ring_buffer_snapshot");
+        let ring_buffer_snapshot = self.ring_buffer.map(|ring_buffer| {
+            self.channels = ring_buffer.channels();
+            self.buffer_layout = BufferLayout::Interleaved;
+
+            let mut snapshot = ui.memory_mut(|memory| {
+                std::mem::take(
+                    memory
+                        .data
+                        .get_temp_mut_or_default::<Vec<SampleType>>(snapshot_id),
+                )
+            });
+            ring_buffer.snapshot_into(&mut snapshot);
+            snapshot
+        });
+
+        let buffer = ring_buffer_snapshot
+            .as_deref()
+            .or(self.decoded_buffer.as_deref())
+            .or(self.buffer);
+
+        if let Some(summary) = self.summary {
+            self.channels = summary.channels();
+        }
+
+        let font_id = FontSelection::Default.resolve(ui.style());
+        let ruler_font_id = FontId::new(font_id.size * 0.75, font_id.family.clone());
+
+        if let Some(ref channel_colors) = self.channel_colors {
+            assert_eq!(channel_colors.len(), self.channels);
+        }
+
+        if let Some(ref channel_mute) = self.channel_mute {
+            assert_eq!(channel_mute.len(), self.channels);
+        }
+
+        if let Some(ref channel_solo) = self.channel_solo {
+            assert_eq!(channel_solo.len(), self.channels);
+        }
+
+        // Splits `rect` into equal columns, or rows when `vertical`
+        let split_rect = |rect: Rect, vertical: bool| {
+            let channel_size = if vertical {
+                vec2(rect.width(), rect.height() / self.channels as f32)
+            } else {
+                vec2(rect.width() / self.channels as f32, rect.height())
+            };
+            let channel_offset = if vertical {
+                vec2(0.0, channel_size.y)
+            } else {
+                vec2(channel_size.x, 0.0)
+            };
+
+            (0..self.channels)
+                .map(|channel_id| {
+                    Rect::from_min_size(
+                        rect.left_top() + channel_offset * channel_id as f32,
+                        channel_size,
+                    )
+                })
+                .collect_vec()
+        };
+
+        let channel_rects = match self.channel_layout {
+            _ if self.channels == 1 => vec![rect],
+            ChannelLayout::SideBySide => split_rect(rect, false),
+            ChannelLayout::Stacked => split_rect(rect, true),
+            ChannelLayout::Overlaid => vec![rect; self.channels],
+        };
+
+        let header_height = if self.show_header { font_id.size } else { 0.0 };
+
+        let header_rect = |rect: Rect| {
+            let mut tmp = rect;
+            tmp.set_height(header_height);
+            tmp
+        };
+
+        // Overlaid channels share their header
+        let header_rects = match self.channel_layout {
+            ChannelLayout::Overlaid if self.channels > 1 => split_rect(header_rect(rect), false),
+            _ => channel_rects.iter().copied().map(header_rect).collect_vec(),
+        };
+
+        // Mute and solo buttons at the left of each channel header
+        let toggle_rects = |channel_id: usize| {
+            let toggle_rect = Rect::from_min_size(
+                header_rects[channel_id].left_top(),
+                Vec2::splat(header_height),
+            );
+            (toggle_rect, toggle_rect.translate(vec2(header_height, 0.0)))
+        };
+
+        let channel_has_ruler = |channel_id: usize| {
+            self.show_ruler
+                && (self.channel_layout != ChannelLayout::Stacked
+                    || channel_id == self.channels - 1)
+        };
+
+        let waveform_rect = |channel_id: usize| {
+            let channel_rect = channel_rects[channel_id];
+            let ruler_height = if channel_has_ruler(channel_id) {
+                ruler_font_id.size + 4.0
+            } else {
+                0.0
+            };
+            let waveform_vertical_margin = 4.0;
+
+            Rect::from_min_max(
+                channel_rect.left_top() + vec2(0.0, header_height),
+                channel_rect.right_bottom() - vec2(0.0, ruler_height),
+            )
+            .shrink2(vec2(0.0, waveform_vertical_margin))
+        };
+
+        // Buffers other than stereo fall back to the waveform view
+        let phase_view = self.display_mode != WaveformDisplayMode::Waveform
+            && self.summary.is_none()
+            && self.channels == 2;
+
+        // Mute and solo
+        let mut channel_toggled = false;
+
+        if let Some(pointer_position) = response
+            .interact_pointer_pos()
+            .filter(|_| response.clicked() && self.show_header && !phase_view)
+        {
+            for channel_id in 0..self.channels {
+                let (mute_rect, solo_rect) = toggle_rects(channel_id);
+
+                for (toggle_rect, channel_toggles) in [
+                    (mute_rect, &mut self.channel_mute),
+                    (solo_rect, &mut self.channel_solo),
+                ] {
+                    if let Some(channel_toggles) = channel_toggles {
+                        if toggle_rect.contains(pointer_position) {
+                            channel_toggles[channel_id] = !channel_toggles[channel_id];
+                            channel_toggled = true;
+                        }
+                    }
+                }
+            }
+        }
+
+        if channel_toggled {
+            response.mark_changed();
+        }
+
+        let clicked = response.clicked() && !channel_toggled;
+
+        // Navigation
+        let mut view = None;
+
+        if let Some(summary) = self.summary.filter(|summary| !summary.is_empty()) {
+            let view_id = response.id.with("view");
+            let recording_length = summary.len() as f64;
+
+            let mut summary_view = ui
+                .memory_mut(|memory| memory.data.get_temp::<WaveformView>(view_id))
+                .unwrap_or(WaveformView {
+                    start: 0.0,
+                    length: recording_length,
+                });
+
+            let pointer_rect = response.hover_pos().and_then(|pointer_position| {
+                channel_rects
+                    .iter()
+                    .position(|channel_rect| channel_rect.contains(pointer_position))
+                    .map(|channel_id| (pointer_position, waveform_rect(channel_id)))
+            });
+
+            if let Some((pointer_position, pointer_rect)) = pointer_rect {
+                let (scroll_delta, zoom_delta) =
+                    ui.input(|input| (input.scroll_delta, input.zoom_delta()));
+
+                let zoom = zoom_delta as f64 * (-scroll_delta.y as f64 / 200.0).exp();
+                if zoom != 1.0 {
+                    let anchor = summary_view.frame_at(pointer_rect, pointer_position.x);
+                    summary_view.length /= zoom;
+                    summary_view.start = anchor - (anchor - summary_view.start) / zoom;
+                }
+
+                if scroll_delta.x != 0.0 {
+                    summary_view.pan(pointer_rect, scroll_delta.x);
+                }
+
+                if scroll_delta != Vec2::ZERO {
+                    ui.input_mut(|input| input.scroll_delta = Vec2::ZERO);
+                }
+
+                if response.dragged_by(PointerButton::Secondary)
+                    || response.dragged_by(PointerButton::Middle)
+                {
+                    summary_view.pan(pointer_rect, response.drag_delta().x);
+                }
+
+                summary_view.clamp(recording_length, MINIMUM_VIEW_LENGTH);
+
+                let pointer_frame = summary_view
+                    .frame_at(pointer_rect, pointer_position.x)
+                    .clamp(0.0, recording_length - 1.0)
+                    .round() as usize;
+
+                let drag_id = response.id.with("drag");
+                let in_header = pointer_position.y < pointer_rect.top();
+
+                if response.drag_started_by(PointerButton::Primary) && !in_header {
+                    let cursor_x = self
+                        .cursor
+                        .as_ref()
+                        .map(|cursor| summary_view.x_at(pointer_rect, **cursor as f64));
+
+                    let drag_state = if cursor_x.map_or(false, |cursor_x| {
+                        (cursor_x - pointer_position.x).abs() < 4.0
+                    }) {
+                        Some(WaveformDrag::Cursor)
+                    } else if self.selection.is_some() {
+                        Some(WaveformDrag::Selection(pointer_frame))
+                    } else {
+                        None
+                    };
+
+                    if let Some(drag_state) = drag_state {
+                        ui.memory_mut(|memory| memory.data.insert_temp(drag_id, drag_state));
+                    }
+                }
+
+                if response.dragged_by(PointerButton::Primary) {
+                    match ui.memory_mut(|memory| memory.data.get_temp::<WaveformDrag>(drag_id)) {
+                        Some(WaveformDrag::Cursor) => {
+                            if let Some(ref mut cursor) = self.cursor {
+                                **cursor = pointer_frame;
+                                response.mark_changed();
+                            }
+                        }
+                        Some(WaveformDrag::Selection(anchor)) => {
+                            if let Some(ref mut selection) = self.selection {
+                                **selection =
+                                    Some(anchor.min(pointer_frame)..anchor.max(pointer_frame));
+                                response.mark_changed();
+                            }
+                        }
+                        None => {}
+                    }
+                }
+
+                if response.drag_released() {
+                    ui.memory_mut(|memory| memory.data.remove::<WaveformDrag>(drag_id));
+                }
+
+                if clicked {
+                    if in_header || (self.cursor.is_none() && self.selection.is_none()) {
+                        let track_enabled = get(&mut self.get_set_value);
+                        set(&mut self.get_set_value, !track_enabled);
+                    } else {
+                        if let Some(ref mut cursor) = self.cursor {
+                            **cursor = pointer_frame;
+                        }
+
+                        if let Some(ref mut selection) = self.selection {
+                            **selection = None;
+                        }
+                    }
+                    response.mark_changed();
+                }
+            }
+
+            ui.memory_mut(|memory| memory.data.insert_temp(view_id, summary_view));
+            view = Some(summary_view);
+        } else if clicked {
             let track_enabled = get(&mut self.get_set_value);
             set(&mut self.get_set_value, !track_enabled);
             response.mark_changed();
@@ -214,14 +892,22 @@ where
 
             let visuals = *ui.style().interact(&response);
 
-            let font_id = FontSelection::Default.resolve(ui.style());
-
             let foreground_color = if track_enabled {
                 visuals.text_color()
             } else {
                 ui.style().noninteractive().text_color()
             };
 
+            let grid_stroke = Stroke::new(
+                ui.style().visuals.noninteractive().fg_stroke.width,
+                ui.style()
+                    .visuals
+                    .noninteractive()
+                    .fg_stroke
+                    .color
+                    .linear_multiply(0.25),
+            );
+
             ui.painter().rect(
                 rect,
                 visuals.rounding,
@@ -229,182 +915,684 @@ where
                 visuals.fg_stroke,
             );
 
-            if let Some(buffer) = self.buffer {
-                assert_eq!(buffer.len() % self.channels, 0);
-
-                let channel_buffer_length = buffer.len() / self.channels;
-                assert_eq!(channel_buffer_length.rem_euclid(2), 0);
+            if buffer.is_some() || self.summary.is_some() {
+                let channel_buffers = buffer.map(|buffer| {
+                    assert_eq!(buffer.len() % self.channels, 0);
+
+                    let channel_buffer_length = buffer.len() / self.channels;
+                    assert_eq!(channel_buffer_length.rem_euclid(2), 0);
+
+                    if self.channels == 1 {
+                        vec![Cow::Borrowed(buffer)]
+                    } else {
+                        (0..self.channels)
+                            .map(|channel_id| {
+                                Cow::Owned(
+                                    self.buffer_layout
+                                        .channel_samples(buffer, self.channels, channel_id)
+                                        .collect_vec(),
+                                )
+                            })
+                            .collect_vec()
+                    }
+                });
 
                 if let Some(ref channel_names) = self.channel_names {
                     assert_eq!(channel_names.len(), self.channels);
                 }
 
-                let render_channel =
-                    |rect: Rect, channel_buffer: &[SampleType], channel_name: &Option<String>| {
-                        let header_height = font_id.size;
-                        let waveform_vertical_margin = 4.0;
-
-                        // Header
-                        if self.show_header {
-                            let header_rect = {
-                                let mut tmp = rect;
-                                tmp.set_height(header_height);
-                                tmp
-                            };
+                let any_channel_soloed = self
+                    .channel_solo
+                    .as_deref()
+                    .map_or(false, |channel_solo| channel_solo.contains(&true));
+
+                let channel_audible = |channel_id: usize| {
+                    let muted = self
+                        .channel_mute
+                        .as_deref()
+                        .map_or(false, |channel_mute| channel_mute[channel_id]);
+                    let soloed = self
+                        .channel_solo
+                        .as_deref()
+                        .map_or(false, |channel_solo| channel_solo[channel_id]);
+
+                    !muted && (soloed || !any_channel_soloed)
+                };
+
+                let overlaid = self.channel_layout == ChannelLayout::Overlaid;
+
+                let channel_color = |channel_id: usize| {
+                    let color = match self.channel_colors {
+                        Some(ref channel_colors) => channel_colors[channel_id],
+                        None if overlaid && self.channels > 1 => Color32::from(Hsva::new(
+                            channel_id as f32 / self.channels as f32,
+                            0.75,
+                            0.9,
+                            1.0,
+                        )),
+                        None => visuals.fg_stroke.color,
+                    };
+
+                    if channel_audible(channel_id) {
+                        color
+                    } else {
+                        color.linear_multiply(0.25)
+                    }
+                };
+
+                let render_channel = |channel_id: usize, channel_name: &Option<String>| {
+                    // Header
+                    if self.show_header {
+                        let header_rect = header_rects[channel_id];
+
+                        if let Some(channel_name) = channel_name {
+                            ui.painter().text(
+                                header_rect.center(),
+                                Align2::CENTER_CENTER,
+                                channel_name,
+                                font_id.clone(),
+                                if overlaid {
+                                    channel_color(channel_id)
+                                } else {
+                                    foreground_color
+                                },
+                            );
+                        }
+
+                        if track_enabled && channel_audible(channel_id) {
+                            ui.painter().text(
+                                header_rect.right_center(),
+                                Align2::RIGHT_CENTER,
+                                '\u{1F508}',
+                                font_id.clone(),
+                                foreground_color,
+                            );
+                        }
+
+                        let (mute_rect, solo_rect) = toggle_rects(channel_id);
+
+                        for (toggle_rect, channel_toggles, toggle_label) in [
+                            (mute_rect, self.channel_mute.as_deref(), "M"),
+                            (solo_rect, self.channel_solo.as_deref(), "S"),
+                        ] {
+                            if let Some(channel_toggles) = channel_toggles {
+                                let toggle_rect = toggle_rect.shrink(1.0);
+
+                                if channel_toggles[channel_id] {
+                                    ui.painter().rect_filled(
+                                        toggle_rect,
+                                        visuals.rounding,
+                                        ui.style().visuals.selection.bg_fill,
+                                    );
+                                }
+
+                                ui.painter().rect_stroke(
+                                    toggle_rect,
+                                    visuals.rounding,
+                                    ui.style().visuals.noninteractive().fg_stroke,
+                                );
 
-                            if let Some(channel_name) = channel_name {
                                 ui.painter().text(
-                                    header_rect.center(),
+                                    toggle_rect.center(),
                                     Align2::CENTER_CENTER,
-                                    channel_name,
-                                    font_id.clone(),
+                                    toggle_label,
+                                    ruler_font_id.clone(),
                                     foreground_color,
                                 );
                             }
+                        }
+                    }
 
-                            if track_enabled {
-                                ui.painter().text(
-                                    header_rect.right_center(),
-                                    Align2::RIGHT_CENTER,
-                                    '\u{1F508}',
-                                    font_id.clone(),
-                                    foreground_color,
+                    // Waveform
+                    {
+                        let rect = channel_rects[channel_id];
+                        let waveform_rect = waveform_rect(channel_id);
+
+                        // Overlaid channels share the grid, ruler, cursor and selection
+                        let first_layer = !overlaid || channel_id == 0;
+                        let waveform_stroke =
+                            Stroke::new(visuals.fg_stroke.width, channel_color(channel_id));
+
+                        let columns = (waveform_rect.width().round() as usize).max(1);
+
+                        let sample_y = |sample: f32| {
+                            remap_clamp(
+                                sample,
+                                SampleType::DISPLAY_RANGE,
+                                waveform_rect.bottom_up_range(),
+                            )
+                        };
+
+                        if self.show_grid && first_layer {
+                            for t in [0.25, 0.75] {
+                                let y = lerp(waveform_rect.y_range(), t);
+                                ui.painter().line_segment(
+                                    [
+                                        pos2(waveform_rect.left(), y),
+                                        pos2(waveform_rect.right(), y),
+                                    ],
+                                    grid_stroke,
                                 );
                             }
                         }
 
-                        // Waveform
-                        {
-                            let waveform_rect = if self.show_header {
-                                let mut tmp = rect;
-                                tmp = tmp.translate(vec2(0.0, header_height));
-                                tmp.set_height(rect.height() - header_height);
-                                tmp.shrink2(vec2(0.0, waveform_vertical_margin))
+                        if first_layer {
+                            ui.painter().line_segment(
+                                [waveform_rect.left_center(), waveform_rect.right_center()],
+                                ui.style().visuals.noninteractive().fg_stroke,
+                            );
+                        }
+
+                        // Draws `samples` starting at frame `start` into `view`
+                        let paint_line = |samples: &[f32], start: usize, view: WaveformView| {
+                            let waveform_points = samples
+                                .iter()
+                                .enumerate()
+                                .step_by(self.waveform_resolution)
+                                .map(|(index, &sample)| {
+                                    pos2(
+                                        view.x_at(waveform_rect, (start + index) as f64),
+                                        sample_y(sample),
+                                    )
+                                })
+                                .collect_vec();
+
+                            ui.painter()
+                                .add(Shape::line(waveform_points, waveform_stroke));
+                        };
+
+                        let paint_envelope = |envelope: &[(f32, f32)]| {
+                            let mut mesh = Mesh::default();
+
+                            for (column, &(min, max)) in envelope.iter().enumerate() {
+                                let x = remap_clamp(
+                                    column as f32,
+                                    0.0..=(envelope.len() as f32 - 1.0),
+                                    waveform_rect.x_range(),
+                                );
+
+                                // Keep flat stretches visible as a hairline
+                                let (top, bottom) = (sample_y(max), sample_y(min));
+                                let thickness = (bottom - top).max(waveform_stroke.width);
+                                let center = (top + bottom) / 2.0;
+
+                                mesh.colored_vertex(
+                                    pos2(x, center - thickness / 2.0),
+                                    waveform_stroke.color,
+                                );
+                                mesh.colored_vertex(
+                                    pos2(x, center + thickness / 2.0),
+                                    waveform_stroke.color,
+                                );
+
+                                if column > 0 {
+                                    let index = column as u32 * 2;
+                                    mesh.add_triangle(index - 2, index - 1, index);
+                                    mesh.add_triangle(index - 1, index + 1, index);
+                                }
+                            }
+
+                            ui.painter().add(mesh);
+                        };
+
+                        let paint_ruler = |view: WaveformView, origin: f64| {
+                            if !channel_has_ruler(channel_id) || !first_layer {
+                                return;
+                            }
+
+                            let ruler_rect = Rect::from_min_max(
+                                pos2(rect.left(), waveform_rect.bottom()),
+                                rect.right_bottom(),
+                            );
+
+                            // Aim for a label every 80 points, at round positions
+                            let units_per_frame = self
+                                .sample_rate
+                                .map_or(1.0, |sample_rate| 1.0 / sample_rate as f64);
+                            let step = nice_step(
+                                80.0 * view.length / waveform_rect.width() as f64 * units_per_frame,
+                            )
+                            .max(units_per_frame);
+                            let decimals = (-step.log10().floor()).max(0.0) as usize;
+
+                            let first_tick =
+                                ((view.start - origin) * units_per_frame / step).ceil();
+                            let last_tick = ((view.start + view.length - origin) * units_per_frame
+                                / step)
+                                .floor();
+
+                            for tick in (first_tick as i64)..=(last_tick as i64) {
+                                let value = tick as f64 * step;
+                                let x = view.x_at(waveform_rect, origin + value / units_per_frame);
+
+                                if self.show_grid {
+                                    ui.painter().line_segment(
+                                        [
+                                            pos2(x, waveform_rect.top()),
+                                            pos2(x, waveform_rect.bottom()),
+                                        ],
+                                        grid_stroke,
+                                    );
+                                }
+
+                                ui.painter().line_segment(
+                                    [pos2(x, ruler_rect.top()), pos2(x, ruler_rect.top() + 3.0)],
+                                    ui.style().visuals.noninteractive().fg_stroke,
+                                );
+
+                                let label = if self.sample_rate.is_some() {
+                                    format!("{value:.decimals$} s")
+                                } else {
+                                    format!("{value:.0}")
+                                };
+
+                                ui.painter().text(
+                                    pos2(x, ruler_rect.bottom()),
+                                    Align2::CENTER_BOTTOM,
+                                    label,
+                                    ruler_font_id.clone(),
+                                    ui.style().noninteractive().text_color(),
+                                );
+                            }
+                        };
+
+                        if let (Some(summary), Some(view)) = (self.summary, view) {
+                            let visible_range = (view.start.floor() as usize)
+                                ..((view.start + view.length).ceil() as usize).min(summary.len());
+
+                            // Selection and cursor
+                            let selection_stroke = ui.style().visuals.selection.stroke;
+
+                            if let Some(Some(ref selection)) =
+                                self.selection.as_deref().filter(|_| first_layer)
+                            {
+                                ui.painter().rect_filled(
+                                    Rect::from_x_y_ranges(
+                                        view.x_at(waveform_rect, selection.start as f64)
+                                            .max(waveform_rect.left())
+                                            ..=view
+                                                .x_at(waveform_rect, selection.end as f64)
+                                                .min(waveform_rect.right()),
+                                        waveform_rect.y_range(),
+                                    ),
+                                    0.0,
+                                    ui.style().visuals.selection.bg_fill.linear_multiply(0.5),
+                                );
+                            }
+
+                            if visible_range.len() > columns {
+                                paint_envelope(&summary.peak_envelope(
+                                    channel_id,
+                                    visible_range,
+                                    columns,
+                                ));
                             } else {
-                                rect.shrink2(vec2(0.0, waveform_vertical_margin))
-                            };
+                                paint_line(
+                                    &summary.samples(channel_id)[visible_range.clone()],
+                                    visible_range.start,
+                                    view,
+                                );
+                            }
+
+                            if let Some(cursor) = self.cursor.as_deref().filter(|_| first_layer) {
+                                let x = view.x_at(waveform_rect, *cursor as f64);
+                                if waveform_rect.x_range().contains(&x) {
+                                    ui.painter().line_segment(
+                                        [
+                                            pos2(x, waveform_rect.top()),
+                                            pos2(x, waveform_rect.bottom()),
+                                        ],
+                                        selection_stroke,
+                                    );
+                                }
+                            }
+
+                            paint_ruler(view, 0.0);
+                        } else if let Some(ref channel_buffers) = channel_buffers {
+                            let channel_buffer = &channel_buffers[channel_id];
+                            let channel_buffer_length = channel_buffer.len();
 
                             let window_size = self.window_size.unwrap_or(channel_buffer_length / 2); // Default window size
                             assert!(window_size <= channel_buffer_length);
                             assert_eq!(window_size.rem_euclid(2), 0);
 
+                            let window_view = WaveformView {
+                                start: 0.0,
+                                length: (window_size - 1) as f64,
+                            };
+
                             let window_center_valid_range =
                                 (window_size / 2)..=(channel_buffer_length - (window_size / 2));
 
-                            let left_signal_edge_delta = channel_buffer
-                                [..=(channel_buffer_length / 2) + 1]
-                                .iter()
-                                .rev()
-                                .tuple_windows()
-                                .map(|(a, b)| (b, a))
-                                .enumerate()
-                                .map(|(delta, (a, b))| (delta, SignalEdge::from_samples(a, b)))
-                                .find(|(_, signal_edge)| {
-                                    *signal_edge == Some(SignalEdge::RisingEdge)
+                            let trigger_level = self
+                                .trigger_level
+                                .unwrap_or_else(|| SampleType::ZERO.to_f32());
+
+                            // Trigger on the matching edge closest to the buffer center
+                            let trigger_position = window_center_valid_range
+                                .clone()
+                                .filter(|&index| index > 0)
+                                .filter(|&index| {
+                                    SignalEdge::from_samples(
+                                        channel_buffer[index - 1].to_f32(),
+                                        channel_buffer[index].to_f32(),
+                                        trigger_level,
+                                    )
+                                    .map_or(false, |signal_edge| {
+                                        self.trigger_edge.matches(signal_edge)
+                                    })
                                 })
-                                .map_or(channel_buffer_length / 2, |(delta, _)| delta);
+                                .min_by_key(|&index| index.abs_diff(channel_buffer_length / 2));
+
+                            let capture_window = |window_center: usize| {
+                                channel_buffer[(window_center - window_size / 2)
+                                    ..(window_center + window_size / 2)]
+                                    .iter()
+                                    .map(|&sample| sample.to_f32())
+                                    .collect_vec()
+                            };
 
-                            let right_signal_edge_delta = channel_buffer
-                                [(channel_buffer_length / 2)..]
-                                .iter()
-                                .tuple_windows()
-                                .enumerate()
-                                .map(|(delta, (a, b))| (delta, SignalEdge::from_samples(a, b)))
-                                .find(|(_, signal_edge)| {
-                                    *signal_edge == Some(SignalEdge::RisingEdge)
+                            let (time, trigger_state_id) =
+                                (ui.input(|input| input.time), response.id.with(channel_id));
+
+                            let mut trigger_state = ui
+                                .memory_mut(|memory| {
+                                    memory.data.get_temp::<TriggerState>(trigger_state_id)
                                 })
-                                .map_or(channel_buffer_length / 2, |(delta, _)| delta);
+                                .unwrap_or_default();
 
-                            let mut window_center =
-                                if right_signal_edge_delta < left_signal_edge_delta {
-                                    (channel_buffer_length / 2) + right_signal_edge_delta
-                                } else {
-                                    (channel_buffer_length / 2) - left_signal_edge_delta
-                                };
+                            if self.rearm_trigger {
+                                trigger_state = TriggerState::default();
+                            }
+
+                            let holdoff_elapsed = match trigger_state.capture_time {
+                                Some(capture_time) => {
+                                    time - capture_time >= self.trigger_holdoff as f64
+                                }
+                                None => true,
+                            };
 
-                            if !window_center_valid_range.contains(&window_center) {
-                                window_center = channel_buffer_length / 2;
+                            let trigger_armed = match self.trigger_mode {
+                                TriggerMode::Auto | TriggerMode::Normal => holdoff_elapsed,
+                                TriggerMode::Single => trigger_state.capture_time.is_none(),
+                            };
+
+                            if trigger_armed {
+                                if let Some(trigger_position) = trigger_position {
+                                    trigger_state = TriggerState {
+                                        window: Some(capture_window(trigger_position)),
+                                        capture_time: Some(time),
+                                    };
+                                } else if self.trigger_mode == TriggerMode::Auto {
+                                    // Free-running while there's nothing to trigger on
+                                    trigger_state.window =
+                                        Some(capture_window(channel_buffer_length / 2));
+                                    trigger_state.capture_time = None;
+                                }
                             }
 
-                            let waveform_points = channel_buffer[(window_center - window_size / 2)
-                                ..(window_center + window_size / 2)]
-                                .iter()
-                                .enumerate()
-                                .step_by(self.waveform_resolution)
-                                .map(|(index, &sample)| {
-                                    pos2(
-                                        remap_clamp(
-                                            index as f32,
-                                            0.0..=(window_size as f32 - 1.0),
-                                            waveform_rect.x_range(),
-                                        ),
-                                        remap_clamp(
-                                            sample.into(),
-                                            SampleType::DISPLAY_RANGE,
-                                            waveform_rect.bottom_up_range(),
+                            ui.memory_mut(|memory| {
+                                memory
+                                    .data
+                                    .insert_temp(trigger_state_id, trigger_state.clone())
+                            });
+
+                            if let Some(window) = trigger_state.window {
+                                if window.len() > columns && self.peak_decimation {
+                                    paint_envelope(&peak_envelope(&window, columns));
+                                } else {
+                                    paint_line(&window, 0, window_view);
+                                }
+
+                                // Trigger marker
+                                if trigger_state.capture_time.is_some() {
+                                    let trigger_stroke = ui.style().visuals.selection.stroke;
+                                    let trigger_point = pos2(
+                                        window_view.x_at(waveform_rect, (window_size / 2) as f64),
+                                        sample_y(trigger_level),
+                                    );
+
+                                    ui.painter().line_segment(
+                                        [
+                                            pos2(trigger_point.x, waveform_rect.top()),
+                                            pos2(trigger_point.x, waveform_rect.bottom()),
+                                        ],
+                                        Stroke::new(
+                                            trigger_stroke.width,
+                                            trigger_stroke.color.linear_multiply(0.25),
                                         ),
-                                    )
-                                })
-                                .collect_vec();
+                                    );
 
-                            ui.painter().line_segment(
-                                [waveform_rect.left_center(), waveform_rect.right_center()],
-                                ui.style().visuals.noninteractive().fg_stroke,
+                                    ui.painter()
+                                        .circle_stroke(trigger_point, 3.0, trigger_stroke);
+                                }
+                            }
+
+                            // Oscilloscope time is relative to the trigger point
+                            paint_ruler(window_view, (window_size / 2) as f64);
+                        }
+                    }
+                };
+
+                if let (true, Some(ref channel_buffers)) = (phase_view, &channel_buffers) {
+                    let channel_buffer_length = channel_buffers[0].len();
+                    let window_size = self.window_size.unwrap_or(channel_buffer_length / 2);
+                    assert!(window_size <= channel_buffer_length);
+
+                    // Latest window of both channels, normalized to -1.0..=1.0
+                    let [left, right] = [0, 1].map(|channel_id| {
+                        channel_buffers[channel_id][(channel_buffer_length - window_size)..]
+                            .iter()
+                            .map(|&sample| {
+                                remap(sample.to_f32(), SampleType::DISPLAY_RANGE, -1.0..=1.0)
+                            })
+                            .collect_vec()
+                    });
+
+                    let header_height = if self.show_header { font_id.size } else { 0.0 };
+                    let meter_height = ruler_font_id.size + 4.0;
+
+                    // Header
+                    if self.show_header {
+                        let header_rect = {
+                            let mut tmp = rect;
+                            tmp.set_height(header_height);
+                            tmp
+                        };
+
+                        if let Some(ref track_name) = self.track_name {
+                            ui.painter().text(
+                                header_rect.center(),
+                                Align2::CENTER_CENTER,
+                                track_name,
+                                font_id.clone(),
+                                foreground_color,
                             );
+                        }
 
-                            ui.painter()
-                                .add(Shape::line(waveform_points, visuals.fg_stroke));
+                        if track_enabled {
+                            ui.painter().text(
+                                header_rect.right_center(),
+                                Align2::RIGHT_CENTER,
+                                '\u{1F508}',
+                                font_id.clone(),
+                                foreground_color,
+                            );
                         }
-                    };
+                    }
 
-                if self.channels == 1 {
-                    render_channel(rect, buffer, &self.track_name);
-                } else {
-                    for channel_id in 0..self.channels {
-                        let channel_buffer = match self.buffer_layout {
-                            BufferLayout::Planar => buffer
-                                .iter()
-                                .copied()
-                                .skip(channel_id * channel_buffer_length)
-                                .take(channel_buffer_length)
-                                .collect_vec(),
-                            BufferLayout::Interleaved => buffer
-                                .iter()
-                                .copied()
-                                .skip(channel_id)
-                                .step_by(self.channels)
-                                .take(channel_buffer_length)
-                                .collect_vec(),
+                    // Phase view
+                    {
+                        let plot_area = Rect::from_min_max(
+                            rect.left_top() + vec2(0.0, header_height),
+                            rect.right_bottom() - vec2(0.0, meter_height),
+                        )
+                        .shrink(4.0);
+                        let plot_rect = Rect::from_center_size(
+                            plot_area.center(),
+                            Vec2::splat(plot_area.width().min(plot_area.height()).max(0.0)),
+                        );
+                        let plot_radius = plot_rect.width() / 2.0;
+
+                        let plot_point = |x: f32, y: f32| {
+                            plot_rect.center()
+                                + vec2(x.clamp(-1.0, 1.0), -y.clamp(-1.0, 1.0)) * plot_radius
+                        };
+
+                        let axis_stroke = ui.style().visuals.noninteractive().fg_stroke;
+                        let axis_label = |position, anchor, label: &str| {
+                            ui.painter().text(
+                                position,
+                                anchor,
+                                label,
+                                ruler_font_id.clone(),
+                                ui.style().noninteractive().text_color(),
+                            );
                         };
-                        assert_eq!(channel_buffer.len(), channel_buffer_length);
 
-                        let channel_rect = Rect::from_min_size(
-                            rect.left_top()
-                                + rect.size() / vec2(self.channels as f32, 1.0)
-                                    * vec2(channel_id as f32, 0.0),
-                            rect.size() / vec2(self.channels as f32, 1.0),
+                        ui.painter().line_segment(
+                            [plot_rect.left_center(), plot_rect.right_center()],
+                            grid_stroke,
                         );
+                        ui.painter().line_segment(
+                            [plot_rect.center_top(), plot_rect.center_bottom()],
+                            grid_stroke,
+                        );
+
+                        match self.display_mode {
+                            WaveformDisplayMode::Lissajous => {
+                                axis_label(plot_rect.right_center(), Align2::RIGHT_BOTTOM, "L");
+                                axis_label(plot_rect.center_top(), Align2::LEFT_TOP, "R");
+                            }
+                            WaveformDisplayMode::Goniometer => {
+                                ui.painter().line_segment(
+                                    [plot_point(-0.5, 0.5), plot_point(0.5, -0.5)],
+                                    grid_stroke,
+                                );
+                                ui.painter().line_segment(
+                                    [plot_point(0.5, 0.5), plot_point(-0.5, -0.5)],
+                                    grid_stroke,
+                                );
 
-                        let channel_name: Option<String> =
-                            if let Some(ref channel_names) = self.channel_names {
-                                channel_names.get(channel_id).cloned()
+                                axis_label(plot_point(-0.5, 0.5), Align2::RIGHT_BOTTOM, "L");
+                                axis_label(plot_point(0.5, 0.5), Align2::LEFT_BOTTOM, "R");
+                                axis_label(plot_rect.center_top(), Align2::LEFT_TOP, "M");
+                                axis_label(plot_rect.right_center(), Align2::RIGHT_BOTTOM, "S");
+                            }
+                            WaveformDisplayMode::Waveform => unreachable!(),
+                        }
+
+                        let phase_points = left
+                            .iter()
+                            .zip(right.iter())
+                            .step_by(self.waveform_resolution)
+                            .map(|(&left, &right)| match self.display_mode {
+                                WaveformDisplayMode::Goniometer => {
+                                    plot_point((right - left) / 2.0, (left + right) / 2.0)
+                                }
+                                _ => plot_point(left, right),
+                            })
+                            .collect_vec();
+
+                        ui.painter()
+                            .add(Shape::line(phase_points, visuals.fg_stroke));
+
+                        ui.painter().rect_stroke(plot_rect, 0.0, axis_stroke);
+                    }
+
+                    // Correlation meter
+                    {
+                        let meter_rect = Rect::from_min_max(
+                            pos2(rect.left(), rect.bottom() - meter_height),
+                            rect.right_bottom(),
+                        )
+                        .shrink2(vec2(4.0, 2.0));
+                        let bar_rect = meter_rect.shrink2(vec2(ruler_font_id.size * 1.5, 0.0));
+
+                        let correlation = ui.ctx().animate_value_with_time(
+                            response.id.with("correlation"),
+                            stereo_correlation(&left, &right),
+                            0.1,
+                        );
+                        let correlation_x =
+                            remap_clamp(correlation, -1.0..=1.0, bar_rect.x_range());
+
+                        ui.painter().line_segment(
+                            [bar_rect.left_center(), bar_rect.right_center()],
+                            grid_stroke,
+                        );
+
+                        ui.painter().rect_filled(
+                            Rect::from_x_y_ranges(
+                                bar_rect.center().x.min(correlation_x)
+                                    ..=bar_rect.center().x.max(correlation_x),
+                                bar_rect.y_range(),
+                            ),
+                            0.0,
+                            if correlation < 0.0 {
+                                ui.style().visuals.error_fg_color
                             } else {
-                                self.track_name.clone()
-                            };
+                                visuals.fg_stroke.color
+                            },
+                        );
 
-                        render_channel(channel_rect, channel_buffer.as_slice(), &channel_name);
+                        ui.painter().line_segment(
+                            [bar_rect.center_top(), bar_rect.center_bottom()],
+                            ui.style().visuals.noninteractive().fg_stroke,
+                        );
 
-                        if channel_id < self.channels - 1 {
-                            ui.painter().line_segment(
-                                [channel_rect.right_top(), channel_rect.right_bottom()],
-                                ui.style().visuals.noninteractive().fg_stroke,
+                        for (position, anchor, label) in [
+                            (meter_rect.left_center(), Align2::LEFT_CENTER, "-1"),
+                            (meter_rect.right_center(), Align2::RIGHT_CENTER, "+1"),
+                        ] {
+                            ui.painter().text(
+                                position,
+                                anchor,
+                                label,
+                                ruler_font_id.clone(),
+                                ui.style().noninteractive().text_color(),
                             );
                         }
                     }
+                } else {
+                    for (channel_id, &channel_rect) in channel_rects.iter().enumerate() {
+                        let channel_name: Option<String> = if self.channels == 1 {
+                            self.track_name.clone()
+                        } else if let Some(ref channel_names) = self.channel_names {
+                            channel_names.get(channel_id).cloned()
+                        } else {
+                            self.track_name.clone()
+                        };
+
+                        render_channel(channel_id, &channel_name);
+
+                        if channel_id < self.channels - 1 {
+                            let separator = match self.channel_layout {
+                                ChannelLayout::SideBySide => {
+                                    Some([channel_rect.right_top(), channel_rect.right_bottom()])
+                                }
+                                ChannelLayout::Stacked => {
+                                    Some([channel_rect.left_bottom(), channel_rect.right_bottom()])
+                                }
+                                ChannelLayout::Overlaid => None,
+                            };
+
+                            if let Some(separator) = separator {
+                                ui.painter().line_segment(
+                                    separator,
+                                    ui.style().visuals.noninteractive().fg_stroke,
+                                );
+                            }
+                        }
+                    }
                 }
             }
         };
 
+        if let Some(snapshot) = ring_buffer_snapshot {
+            ui.memory_mut(|memory| memory.data.insert_temp(snapshot_id, snapshot));
+        }
+
         response
     }
 }
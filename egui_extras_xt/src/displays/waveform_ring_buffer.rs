@@ -0,0 +1,301 @@
+use std::marker::PhantomData;
+use std::sync::atomic::{fence, AtomicU64, AtomicUsize, Ordering};
+
+use crate::displays::waveform_display::SampleRange;
+
+// ----------------------------------------------------------------------------
+
+/// Sample types that can be stored in the slots of a [`WaveformRingBuffer`].
+pub trait AtomicSample: Copy {
+    fn to_bits(self) -> u64;
+    fn from_bits(bits: u64) -> Self;
+}
+
+macro_rules! impl_atomic_sample_int {
+    ($($sample_type:ty => $bits_type:ty),*) => {
+        $(
+            impl AtomicSample for $sample_type {
+                fn to_bits(self) -> u64 {
+                    self as $bits_type as u64
+                }
+
+                fn from_bits(bits: u64) -> Self {
+                    bits as $bits_type as $sample_type
+                }
+            }
+        )*
+    };
+}
+
+impl_atomic_sample_int!(u8 => u8, i8 => u8, u16 => u16, i16 => u16, u32 => u32, i32 => u32);
+
+impl AtomicSample for f32 {
+    fn to_bits(self) -> u64 {
+        f32::to_bits(self) as u64
+    }
+
+    fn from_bits(bits: u64) -> Self {
+        f32::from_bits(bits as u32)
+    }
+}
+
+impl AtomicSample for f64 {
+    fn to_bits(self) -> u64 {
+        f64::to_bits(self)
+    }
+
+    fn from_bits(bits: u64) -> Self {
+        f64::from_bits(bits)
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Lock-free ring buffer holding the last `history_length` frames of an
+/// interleaved sample stream, meant to be filled by a single producer thread
+/// (eg. an audio callback) while the UI thread reads from it.
+///
+/// Readers never block the producer, frames overwritten by the producer while
+/// a snapshot is being taken are returned as silence.
+///
+/// ```
+/// use std::f32::consts::TAU;
+/// use std::sync::Arc;
+///
+/// use egui_extras_xt::displays::WaveformRingBuffer;
+///
+/// let sine = |index: usize| (index as f32 * TAU / 64.0).sin();
+///
+/// let ring_buffer = Arc::new(WaveformRingBuffer::<f32>::new(1, 256));
+/// assert_eq!(ring_buffer.snapshot(), vec![0.0; 256]);
+///
+/// let producer = std::thread::spawn({
+///     let ring_buffer = Arc::clone(&ring_buffer);
+///     move || {
+///         for chunk_start in (0..1000).step_by(100) {
+///             let chunk = (chunk_start..chunk_start + 100).map(sine).collect::<Vec<_>>();
+///             ring_buffer.push(&chunk);
+///         }
+///     }
+/// });
+/// producer.join().unwrap();
+///
+/// assert_eq!(ring_buffer.frames_written(), 1000);
+/// assert_eq!(
+///     ring_buffer.snapshot(),
+///     (1000 - 256..1000).map(sine).collect::<Vec<_>>()
+/// );
+/// ```
+pub struct WaveformRingBuffer<SampleType> {
+    channels: usize,
+    history_length: usize,
+    slots: Box<[AtomicU64]>,
+    /// Write positions wrap around at a multiple of the slot count, keeping
+    /// the slot indices correct after wrapping while still telling how far
+    /// the producer got between two loads.
+    position_modulus: usize,
+    /// End of the samples being written by the push in progress.
+    claimed_position: AtomicUsize,
+    /// End of the samples written by the last finished push.
+    write_position: AtomicUsize,
+    sample_type: PhantomData<SampleType>,
+}
+
+impl<SampleType> WaveformRingBuffer<SampleType>
+where
+    SampleType: SampleRange<SampleType> + AtomicSample,
+{
+    /// History length is measured in frames, one frame holding a sample for
+    /// every channel.
+    pub fn new(channels: usize, history_length: usize) -> Self {
+        assert!(channels > 0, "ring buffer must have at least one channel");
+        assert!(
+            history_length > 0,
+            "ring buffer must hold at least one frame"
+        );
+        assert_eq!(
+            history_length.rem_euclid(2),
+            0,
+            "history length must be even"
+        );
+
+        let slot_count = channels * history_length;
+
+        Self {
+            channels,
+            history_length,
+            slots: (0..slot_count)
+                .map(|_| AtomicU64::new(SampleType::ZERO.to_bits()))
+                .collect(),
+            position_modulus: slot_count * (usize::MAX / 2 / slot_count),
+            claimed_position: AtomicUsize::new(0),
+            write_position: AtomicUsize::new(0),
+            sample_type: PhantomData,
+        }
+    }
+
+    fn advance_position(&self, position: usize, sample_count: usize) -> usize {
+        (position + sample_count % self.position_modulus) % self.position_modulus
+    }
+
+    fn position_distance(&self, from: usize, to: usize) -> usize {
+        (to + self.position_modulus - from) % self.position_modulus
+    }
+
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    pub fn history_length(&self) -> usize {
+        self.history_length
+    }
+
+    /// Number of frames pushed since the creation of the buffer, wrapping
+    /// around after `usize::MAX / 2` samples.
+    pub fn frames_written(&self) -> usize {
+        self.write_position.load(Ordering::Acquire) / self.channels
+    }
+
+    /// Appends interleaved frames to the buffer, overwriting the oldest ones.
+    pub fn push(&self, samples: &[SampleType]) {
+        assert_eq!(samples.len() % self.channels, 0, "partial frame pushed");
+
+        let slot_count = self.slots.len();
+        let write_position = self.write_position.load(Ordering::Relaxed);
+        let end_position = self.advance_position(write_position, samples.len());
+
+        // Readers seeing any of the samples below also see the claim
+        self.claimed_position.store(end_position, Ordering::Relaxed);
+        fence(Ordering::Release);
+
+        // Only the last `history_length` frames survive a large push anyway.
+        let skipped = samples.len().saturating_sub(slot_count);
+
+        for (offset, sample) in samples.iter().enumerate().skip(skipped) {
+            self.slots[(write_position % slot_count + offset % slot_count) % slot_count]
+                .store(sample.to_bits(), Ordering::Relaxed);
+        }
+
+        self.write_position.store(end_position, Ordering::Release);
+    }
+
+    /// Copies the last `history_length` frames in chronological order, as
+    /// interleaved samples. Frames not written yet are silent.
+    pub fn snapshot(&self) -> Vec<SampleType> {
+        let mut snapshot = Vec::new();
+        self.snapshot_into(&mut snapshot);
+        snapshot
+    }
+
+    /// Same as [`Self::snapshot`], reusing the allocation of `snapshot`.
+    pub fn snapshot_into(&self, snapshot: &mut Vec<SampleType>) {
+        let slot_count = self.slots.len();
+        let start_position = self.write_position.load(Ordering::Acquire);
+
+        snapshot.clear();
+        snapshot.extend((0..slot_count).map(|offset| {
+            SampleType::from_bits(
+                self.slots[(start_position % slot_count + offset) % slot_count]
+                    .load(Ordering::Relaxed),
+            )
+        }));
+
+        // The oldest frames may have been overwritten by newer ones while copying,
+        // pushes are whole frames so the overwritten range never splits a frame
+        fence(Ordering::Acquire);
+        let end_position = self.claimed_position.load(Ordering::Relaxed);
+        let overwritten = self
+            .position_distance(start_position, end_position)
+            .min(slot_count);
+
+        snapshot[..overwritten].fill(SampleType::ZERO);
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::TAU;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    use super::WaveformRingBuffer;
+
+    const HISTORY_LENGTH: usize = 256;
+    const FRAME_COUNT: usize = 200_000;
+    const CHUNK_LENGTH: usize = 37;
+
+    fn sine(frame: usize) -> f32 {
+        (frame as f32 * TAU / 100.0).sin()
+    }
+
+    /// Stereo frame with a sine on the left channel and the frame index on the
+    /// right channel, telling which frame the samples come from.
+    fn frame(frame: usize) -> [f32; 2] {
+        [sine(frame), frame as f32]
+    }
+
+    #[test]
+    fn concurrent_snapshots_keep_frames_aligned() {
+        let ring_buffer = Arc::new(WaveformRingBuffer::<f32>::new(2, HISTORY_LENGTH));
+        let producer_done = Arc::new(AtomicBool::new(false));
+
+        // Chunks not dividing the history length wrap around at varying offsets
+        let producer = std::thread::spawn({
+            let ring_buffer = Arc::clone(&ring_buffer);
+            let producer_done = Arc::clone(&producer_done);
+            move || {
+                for chunk_start in (0..FRAME_COUNT).step_by(CHUNK_LENGTH) {
+                    let chunk = (chunk_start..(chunk_start + CHUNK_LENGTH).min(FRAME_COUNT))
+                        .flat_map(frame)
+                        .collect::<Vec<_>>();
+                    ring_buffer.push(&chunk);
+                }
+                producer_done.store(true, Ordering::Release);
+            }
+        });
+
+        let mut snapshot = Vec::new();
+        let mut snapshot_count = 0;
+
+        while !producer_done.load(Ordering::Acquire) {
+            ring_buffer.snapshot_into(&mut snapshot);
+            assert_eq!(snapshot.len(), HISTORY_LENGTH * 2);
+
+            // Silent frames (not written yet or overwritten while copying) may only
+            // precede the written frames, which must be consecutive and intact
+            let written_frames = snapshot
+                .chunks_exact(2)
+                .skip_while(|samples| samples == &[0.0, 0.0])
+                .collect::<Vec<_>>();
+
+            if let Some(first_frame) = written_frames.first() {
+                let first_index = first_frame[1] as usize;
+
+                for (offset, samples) in written_frames.iter().enumerate() {
+                    assert_eq!(*samples, frame(first_index + offset));
+                }
+            }
+
+            snapshot_count += 1;
+        }
+
+        producer.join().unwrap();
+        assert!(snapshot_count > 0);
+
+        assert_eq!(ring_buffer.frames_written(), FRAME_COUNT);
+        assert_eq!(
+            ring_buffer.snapshot(),
+            (FRAME_COUNT - HISTORY_LENGTH..FRAME_COUNT)
+                .flat_map(frame)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "ring buffer must hold at least one frame")]
+    fn empty_history_is_rejected() {
+        let _ = WaveformRingBuffer::<f32>::new(2, 0);
+    }
+}
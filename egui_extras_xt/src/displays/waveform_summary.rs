@@ -0,0 +1,266 @@
+use std::marker::PhantomData;
+use std::ops::Range;
+
+use itertools::Itertools;
+
+use crate::displays::waveform_display::{BufferLayout, SampleRange};
+
+// ----------------------------------------------------------------------------
+
+/// Number of blocks of a summary level merged into a single block of the
+/// next level.
+const SUMMARY_LEVEL_FACTOR: usize = 4;
+
+/// Minimum and maximum sample of every pixel column, for drawing more
+/// samples than there are pixels without dropping peaks.
+pub(crate) fn peak_envelope(samples: &[f32], columns: usize) -> Vec<(f32, f32)> {
+    (0..columns)
+        .map(|column| {
+            let column_range = column_range(0..samples.len(), column, columns);
+            min_max(samples[column_range].iter().map(|&sample| (sample, sample)))
+        })
+        .collect()
+}
+
+fn column_range(range: Range<usize>, column: usize, columns: usize) -> Range<usize> {
+    let column_start = range.start + (range.len() * column) / columns;
+    let column_end = range.start + (range.len() * (column + 1)) / columns;
+    column_start..column_end.max(column_start + 1).min(range.end)
+}
+
+fn min_max(blocks: impl Iterator<Item = (f32, f32)>) -> (f32, f32) {
+    blocks
+        .reduce(|(min_a, max_a), (min_b, max_b)| (min_a.min(min_b), max_a.max(max_b)))
+        .unwrap_or((0.0, 0.0))
+}
+
+// ----------------------------------------------------------------------------
+
+/// Precomputed multi-resolution min/max summary of a recording, so drawing
+/// long recordings doesn't have to visit every sample on every frame.
+pub struct WaveformSummary<SampleType> {
+    channels: Vec<WaveformSummaryChannel>,
+    length: usize,
+    sample_type: PhantomData<SampleType>,
+}
+
+struct WaveformSummaryChannel {
+    samples: Vec<f32>,
+    levels: Vec<Vec<(f32, f32)>>,
+}
+
+impl WaveformSummaryChannel {
+    /// Minimum and maximum sample in `range`. The ends of the range not
+    /// aligned to the blocks of a level are read from the finer levels
+    /// below it, down to the samples themselves.
+    fn min_max(&self, range: Range<usize>) -> (f32, f32) {
+        let block = |level_index: usize, block_index: usize| match level_index {
+            0 => (self.samples[block_index], self.samples[block_index]),
+            _ => self.levels[level_index - 1][block_index],
+        };
+
+        let (mut start, mut end) = (range.start, range.end);
+        let mut peaks = None;
+        let mut merge = |(min, max): (f32, f32)| {
+            peaks = Some(match peaks {
+                Some((peak_min, peak_max)) => (min.min(peak_min), max.max(peak_max)),
+                None => (min, max),
+            });
+        };
+
+        let mut block_size = 1;
+        for level_index in 0..=self.levels.len() {
+            let next_block_size = block_size * SUMMARY_LEVEL_FACTOR;
+            let is_coarsest = level_index == self.levels.len();
+
+            while start < end && (is_coarsest || start % next_block_size != 0) {
+                merge(block(level_index, start / block_size));
+                start += block_size;
+            }
+
+            while start < end && end % next_block_size != 0 {
+                end -= block_size;
+                merge(block(level_index, end / block_size));
+            }
+
+            block_size = next_block_size;
+        }
+
+        peaks.unwrap_or((0.0, 0.0))
+    }
+}
+
+impl<SampleType> WaveformSummary<SampleType>
+where
+    SampleType: SampleRange<SampleType> + Copy,
+{
+    pub fn new(buffer: &[SampleType], channels: usize, buffer_layout: BufferLayout) -> Self {
+        assert!(channels > 0, "summary must have at least one channel");
+        assert_eq!(buffer.len() % channels, 0);
+
+        let length = buffer.len() / channels;
+
+        let channels = (0..channels)
+            .map(|channel_id| {
+                let samples = buffer_layout
+                    .channel_samples(buffer, channels, channel_id)
+                    .map(SampleType::to_f32)
+                    .collect_vec();
+
+                let mut levels: Vec<Vec<(f32, f32)>> = Vec::new();
+                loop {
+                    let level = match levels.last() {
+                        Some(previous_level) if previous_level.len() <= 1 => break,
+                        Some(previous_level) => previous_level
+                            .chunks(SUMMARY_LEVEL_FACTOR)
+                            .map(|blocks| min_max(blocks.iter().copied()))
+                            .collect_vec(),
+                        None => samples
+                            .chunks(SUMMARY_LEVEL_FACTOR)
+                            .map(|block| min_max(block.iter().map(|&sample| (sample, sample))))
+                            .collect_vec(),
+                    };
+                    levels.push(level);
+                }
+
+                WaveformSummaryChannel { samples, levels }
+            })
+            .collect_vec();
+
+        Self {
+            channels,
+            length,
+            sample_type: PhantomData,
+        }
+    }
+
+    pub fn channels(&self) -> usize {
+        self.channels.len()
+    }
+
+    /// Length of the recording in frames.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    pub(crate) fn samples(&self, channel_id: usize) -> &[f32] {
+        &self.channels[channel_id].samples
+    }
+
+    /// Same as [`peak_envelope`] over `range`, reading whole blocks from the
+    /// coarsest summary levels that fit in each column.
+    pub(crate) fn peak_envelope(
+        &self,
+        channel_id: usize,
+        range: Range<usize>,
+        columns: usize,
+    ) -> Vec<(f32, f32)> {
+        let channel = &self.channels[channel_id];
+
+        (0..columns)
+            .map(|column| channel.min_max(column_range(range.clone(), column, columns)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use super::{peak_envelope, WaveformSummary};
+    use crate::displays::waveform_display::BufferLayout;
+
+    const FRAME_COUNT: usize = 5000;
+
+    /// Linear congruential generator, good enough for picking test cases.
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next(&mut self) -> u64 {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            self.0 >> 33
+        }
+
+        fn below(&mut self, bound: usize) -> usize {
+            self.next() as usize % bound
+        }
+
+        fn sample(&mut self) -> f32 {
+            self.below(2001) as f32 / 1000.0 - 1.0
+        }
+    }
+
+    fn assert_envelopes_match(
+        summary: &WaveformSummary<f32>,
+        buffer: &[f32],
+        range: Range<usize>,
+        columns: usize,
+    ) {
+        for channel_id in 0..summary.channels() {
+            let samples = buffer
+                .iter()
+                .skip(channel_id)
+                .step_by(summary.channels())
+                .copied()
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                summary.peak_envelope(channel_id, range.clone(), columns),
+                peak_envelope(&samples[range.clone()], columns),
+                "channel {channel_id}, range {range:?}, {columns} columns"
+            );
+        }
+    }
+
+    #[test]
+    fn summary_envelope_matches_brute_force() {
+        let mut lcg = Lcg(0x5EED);
+        let buffer = (0..FRAME_COUNT * 2)
+            .map(|_| lcg.sample())
+            .collect::<Vec<_>>();
+        let summary = WaveformSummary::new(&buffer, 2, BufferLayout::Interleaved);
+
+        for _ in 0..500 {
+            let start = lcg.below(FRAME_COUNT + 1);
+            let end = start + lcg.below(FRAME_COUNT - start + 1);
+            let columns = 1 + lcg.below(400);
+
+            assert_envelopes_match(&summary, &buffer, start..end, columns);
+        }
+
+        for (range, columns) in [
+            (0..FRAME_COUNT, 1),
+            (0..FRAME_COUNT, 7),
+            (0..FRAME_COUNT, FRAME_COUNT * 2),
+            (123..123, 10),
+            (FRAME_COUNT..FRAME_COUNT, 3),
+            (1234..1235, 1),
+            (1234..1235, 16),
+            (FRAME_COUNT - 1..FRAME_COUNT, 4),
+            (0..FRAME_COUNT, 0),
+        ] {
+            assert_envelopes_match(&summary, &buffer, range, columns);
+        }
+    }
+
+    #[test]
+    fn short_recordings_have_consistent_envelopes() {
+        let mut lcg = Lcg(0xC0FFEE);
+
+        for frame_count in [0, 1, 3, 4, 5, 16, 17] {
+            let buffer = (0..frame_count).map(|_| lcg.sample()).collect::<Vec<_>>();
+            let summary = WaveformSummary::new(&buffer, 1, BufferLayout::Interleaved);
+
+            for columns in 1..=8 {
+                assert_envelopes_match(&summary, &buffer, 0..frame_count, columns);
+            }
+        }
+    }
+}
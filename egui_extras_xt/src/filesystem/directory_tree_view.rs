@@ -213,12 +213,10 @@ impl<'a> DirectoryTreeViewWidget<'a> {
             .inner;
 
         if let Some(selected_path) = self.selected_path {
-            if self.force_selected_open {
-                if selected_path == directory_path {
-                    // TODO: egui 0.20 does not allow specifying separate scrolling alignment per axis.
-                    // Alignment should be (Horizontal::Left, Vertical::Center) here.
-                    header_response.scroll_to_me(Some(Align::Center));
-                }
+            if self.force_selected_open && selected_path == directory_path {
+                // TODO: egui 0.20 does not allow specifying separate scrolling alignment per axis.
+                // Alignment should be (Horizontal::Left, Vertical::Center) here.
+                header_response.scroll_to_me(Some(Align::Center));
             }
         }
 
@@ -1,11 +1,10 @@
 use std::f32::consts::TAU;
-use std::ops::RangeInclusive;
 
 use ecolor::Color32;
-use egui::{self, Response, Sense, Ui, Widget, Shape};
-use emath::{remap_clamp, Vec2, Rot2};
+use egui::{self, Response, Sense, Shape, Ui, Widget};
+use emath::{Rot2, Vec2};
 
-use crate::common::{Orientation, WidgetShape, Winding};
+use crate::common::{WidgetShape, Winding};
 
 // ----------------------------------------------------------------------------
 
@@ -134,15 +133,13 @@ impl<'a> Widget for EncoderKnob<'a> {
             response.mark_changed();
         }
 
-        if response.drag_released() {
-            if self.animated {
-                ui.ctx().clear_animations();
-                ui.ctx().animate_value_with_time(
-                    response.id,
-                    get(&mut self.get_set_value),
-                    ui.style().animation_time,
-                );
-            }
+        if response.drag_released() && self.animated {
+            ui.ctx().clear_animations();
+            ui.ctx().animate_value_with_time(
+                response.id,
+                get(&mut self.get_set_value),
+                ui.style().animation_time,
+            );
         }
 
         if ui.is_rect_visible(rect) {
@@ -181,7 +178,7 @@ impl<'a> Widget for EncoderKnob<'a> {
 
                 if self.show_axes {
                     for axis in 0..self.axis_count {
-                        paint_axis((axis as f32 * (TAU / (self.axis_count as f32)) + value));
+                        paint_axis(axis as f32 * (TAU / (self.axis_count as f32)) + value);
                     }
                 }
             }
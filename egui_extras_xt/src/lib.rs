@@ -1,5 +1,8 @@
 mod hash;
 
+#[cfg(any(feature = "compasses", feature = "displays"))]
+mod display_style;
+
 pub mod common;
 
 #[cfg(feature = "barcodes")]
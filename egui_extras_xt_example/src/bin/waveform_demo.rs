@@ -1,19 +1,54 @@
 use std::f32::consts::TAU;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
 
 use eframe::egui::{self, DragValue};
 use eframe::emath::vec2;
 
-use egui_extras_xt::displays::{BufferLayout, WaveformDisplayWidget};
+use egui_extras_xt::displays::{
+    BufferLayout, ChannelLayout, Endianness, SampleFormat, SpectrogramWidget,
+    SpectrumAnalyzerWidget, SpectrumMode, TriggerEdge, TriggerMode, WavFile, WaveformDisplayMode,
+    WaveformDisplayWidget, WaveformRingBuffer, WaveformSummary,
+};
+use egui_extras_xt::ui::widgets_from_iter::SelectableValueFromIter;
+use strum::IntoEnumIterator;
 
 const BUFFER_SIZE: usize = 1024;
+const MULTICHANNEL_CHANNELS: usize = 4;
 const OUTPUT_FREQUENCY: usize = 44100;
 
 struct WaveformDemoApp {
     enabled: bool,
     buffer: [f32; BUFFER_SIZE],
+    pcm_enabled: bool,
+    pcm_buffer: Vec<u8>,
     left_frequency: f32,
     right_frequency: f32,
     phase: f32,
+    live_enabled: bool,
+    live_buffer: Arc<WaveformRingBuffer<f32>>,
+    live_snapshot: Vec<f32>,
+    trigger_edge: TriggerEdge,
+    trigger_level: f32,
+    trigger_holdoff: f32,
+    trigger_mode: TriggerMode,
+    live_display_mode: WaveformDisplayMode,
+    spectrum_mode: SpectrumMode,
+    multichannel_enabled: bool,
+    multichannel_buffer: Vec<f32>,
+    multichannel_layout: ChannelLayout,
+    multichannel_mute: [bool; MULTICHANNEL_CHANNELS],
+    multichannel_solo: [bool; MULTICHANNEL_CHANNELS],
+    recording_enabled: bool,
+    recording_summary: WaveformSummary<f32>,
+    recording_cursor: usize,
+    recording_selection: Option<Range<usize>>,
+    wav_enabled: bool,
+    wav_bytes: Vec<u8>,
+    wav_path: String,
 }
 
 impl Default for WaveformDemoApp {
@@ -21,18 +56,42 @@ impl Default for WaveformDemoApp {
         let mut tmp = Self {
             enabled: true,
             buffer: [0.0; BUFFER_SIZE],
+            pcm_enabled: true,
+            pcm_buffer: Vec::new(),
             left_frequency: 440.0,
             right_frequency: 440.0,
             phase: 0.0,
+            live_enabled: true,
+            live_buffer: Arc::new(WaveformRingBuffer::new(2, BUFFER_SIZE)),
+            live_snapshot: Vec::new(),
+            trigger_edge: TriggerEdge::Rising,
+            trigger_level: 0.0,
+            trigger_holdoff: 0.0,
+            trigger_mode: TriggerMode::Auto,
+            live_display_mode: WaveformDisplayMode::Waveform,
+            spectrum_mode: SpectrumMode::Bars,
+            multichannel_enabled: true,
+            multichannel_buffer: Self::generate_multichannel_buffer(),
+            multichannel_layout: ChannelLayout::Stacked,
+            multichannel_mute: [false; MULTICHANNEL_CHANNELS],
+            multichannel_solo: [false; MULTICHANNEL_CHANNELS],
+            recording_enabled: true,
+            recording_summary: Self::generate_recording(),
+            recording_cursor: 0,
+            recording_selection: None,
+            wav_enabled: true,
+            wav_bytes: Self::generate_wav_bytes(),
+            wav_path: String::new(),
         };
         tmp.regenerate_buffer();
+        tmp.spawn_live_producer();
         tmp
     }
 }
 
 impl WaveformDemoApp {
     fn regenerate_buffer(&mut self) {
-        for (index, sample) in self.buffer.iter_mut().skip(0).step_by(2).enumerate() {
+        for (index, sample) in self.buffer.iter_mut().step_by(2).enumerate() {
             let q = index as f32 * (self.left_frequency / OUTPUT_FREQUENCY as f32) + self.phase;
             *sample = (q % 1.0) * 2.0 - 1.0;
         }
@@ -41,6 +100,111 @@ impl WaveformDemoApp {
             let q = index as f32 * (self.right_frequency / OUTPUT_FREQUENCY as f32) + self.phase;
             *sample = (q * TAU).sin();
         }
+
+        // Same buffer as packed little endian 24-bit PCM, like in a WAV file
+        self.pcm_buffer = self
+            .buffer
+            .iter()
+            .flat_map(|sample| {
+                let pcm_sample = (sample * 8388607.0) as i32;
+                pcm_sample.to_le_bytes().into_iter().take(3)
+            })
+            .collect();
+    }
+
+    // One harmonic of the same fundamental per channel, in planar layout.
+    fn generate_multichannel_buffer() -> Vec<f32> {
+        (0..MULTICHANNEL_CHANNELS)
+            .flat_map(|channel_id| {
+                (0..BUFFER_SIZE).map(move |index| {
+                    let t = index as f32 / OUTPUT_FREQUENCY as f32;
+                    (t * TAU * 110.0 * (channel_id + 1) as f32).sin() / (channel_id + 1) as f32
+                })
+            })
+            .collect()
+    }
+
+    // A minute long sweep with a few transients, too long to draw sample by sample.
+    fn generate_recording() -> WaveformSummary<f32> {
+        let recording = (0..OUTPUT_FREQUENCY * 60)
+            .map(|index| {
+                let t = index as f32 / OUTPUT_FREQUENCY as f32;
+                let transient = if index % (OUTPUT_FREQUENCY * 7) < 64 {
+                    1.0
+                } else {
+                    0.0
+                };
+                (t * TAU * (50.0 + t * 20.0)).sin() * (t / 60.0) * 0.8 + transient
+            })
+            .collect::<Vec<_>>();
+
+        WaveformSummary::new(&recording, 1, BufferLayout::Interleaved)
+    }
+
+    // A few seconds of 16-bit stereo beeps, as it would be read from a WAV file.
+    fn generate_wav_bytes() -> Vec<u8> {
+        let frames = OUTPUT_FREQUENCY * 3;
+
+        let data = (0..frames)
+            .flat_map(|index| {
+                let t = index as f32 / OUTPUT_FREQUENCY as f32;
+                let envelope = (1.0 - (t * 2.0) % 1.0).powi(4);
+                [440.0, 660.0].map(|frequency| {
+                    ((t * TAU * frequency).sin() * envelope * 32767.0 * 0.8) as i16
+                })
+            })
+            .flat_map(i16::to_le_bytes)
+            .collect::<Vec<_>>();
+
+        // PCM, channels, sample rate, byte rate, block align, bits per sample
+        let format = [
+            &1u16.to_le_bytes()[..],
+            &2u16.to_le_bytes(),
+            &(OUTPUT_FREQUENCY as u32).to_le_bytes(),
+            &(OUTPUT_FREQUENCY as u32 * 4).to_le_bytes(),
+            &4u16.to_le_bytes(),
+            &16u16.to_le_bytes(),
+        ]
+        .concat();
+
+        [
+            &b"RIFF"[..],
+            &(4 + 8 + format.len() as u32 + 8 + data.len() as u32).to_le_bytes(),
+            b"WAVE",
+            b"fmt ",
+            &(format.len() as u32).to_le_bytes(),
+            &format,
+            b"data",
+            &(data.len() as u32).to_le_bytes(),
+            &data,
+        ]
+        .concat()
+    }
+
+    // Stands in for an audio callback pushing samples from another thread.
+    fn spawn_live_producer(&self) {
+        let live_buffer = Arc::clone(&self.live_buffer);
+
+        thread::spawn(move || {
+            let mut index = 0;
+            loop {
+                let samples = (index..index + OUTPUT_FREQUENCY / 100)
+                    .flat_map(|index| {
+                        let t = index as f32 / OUTPUT_FREQUENCY as f32;
+                        let envelope = (t * TAU * 0.5).sin().abs();
+                        [
+                            (t * TAU * 220.0).sin() * envelope,
+                            (t * TAU * 330.0).sin() * (1.0 - envelope),
+                        ]
+                    })
+                    .collect::<Vec<_>>();
+
+                live_buffer.push(&samples);
+                index += OUTPUT_FREQUENCY / 100;
+
+                thread::sleep(Duration::from_millis(10));
+            }
+        });
     }
 }
 
@@ -72,6 +236,165 @@ impl eframe::App for WaveformDemoApp {
                     .buffer_layout(BufferLayout::Interleaved),
             );
 
+            ui.add(
+                WaveformDisplayWidget::new(&mut self.pcm_enabled)
+                    .track_name("Track #1 (24-bit PCM)")
+                    .channels(2)
+                    .channel_names(&["Left", "Right"])
+                    .byte_buffer(&self.pcm_buffer, SampleFormat::I24, Endianness::Little)
+                    .buffer_layout(BufferLayout::Interleaved),
+            );
+
+            ui.separator();
+
+            let mut rearm_trigger = false;
+            ui.horizontal(|ui| {
+                ui.selectable_value_from_iter(
+                    &mut self.live_display_mode,
+                    WaveformDisplayMode::iter(),
+                );
+                ui.separator();
+                ui.selectable_value_from_iter(&mut self.trigger_edge, TriggerEdge::iter());
+                ui.separator();
+                ui.selectable_value_from_iter(&mut self.trigger_mode, TriggerMode::iter());
+                ui.separator();
+                ui.add(
+                    DragValue::new(&mut self.trigger_level)
+                        .speed(0.01)
+                        .clamp_range(-1.0..=1.0)
+                        .prefix("Level: "),
+                );
+                ui.add(
+                    DragValue::new(&mut self.trigger_holdoff)
+                        .speed(0.01)
+                        .clamp_range(0.0..=f32::INFINITY)
+                        .prefix("Holdoff: ")
+                        .suffix(" s"),
+                );
+                rearm_trigger = ui.button("Re-arm").clicked();
+            });
+
+            ui.add(
+                WaveformDisplayWidget::new(&mut self.live_enabled)
+                    .track_name("Live")
+                    .height(128.0)
+                    .display_mode(self.live_display_mode)
+                    .channel_names(&["Left", "Right"])
+                    .ring_buffer(&self.live_buffer)
+                    .trigger_edge(self.trigger_edge)
+                    .trigger_level(self.trigger_level)
+                    .trigger_holdoff(self.trigger_holdoff)
+                    .trigger_mode(self.trigger_mode)
+                    .rearm_trigger(rearm_trigger)
+                    .sample_rate(OUTPUT_FREQUENCY as f32)
+                    .show_ruler(true),
+            );
+            ctx.request_repaint();
+
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.selectable_value_from_iter(&mut self.spectrum_mode, SpectrumMode::iter());
+            });
+
+            self.live_buffer.snapshot_into(&mut self.live_snapshot);
+            ui.horizontal(|ui| {
+                ui.add(
+                    SpectrumAnalyzerWidget::new()
+                        .buffer(&self.live_snapshot)
+                        .channels(2)
+                        .sample_rate(OUTPUT_FREQUENCY as f32)
+                        .mode(self.spectrum_mode),
+                );
+
+                ui.add(
+                    SpectrogramWidget::new()
+                        .buffer(&self.live_snapshot)
+                        .frames_written(self.live_buffer.frames_written())
+                        .channels(2)
+                        .sample_rate(OUTPUT_FREQUENCY as f32)
+                        .frequency_range(20.0..=2000.0),
+                );
+            });
+
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.selectable_value_from_iter(&mut self.multichannel_layout, ChannelLayout::iter());
+            });
+
+            ui.add(
+                WaveformDisplayWidget::new(&mut self.multichannel_enabled)
+                    .track_name("Multichannel")
+                    .width(512.0)
+                    .height(192.0)
+                    .channels(MULTICHANNEL_CHANNELS)
+                    .channel_names(&["1st", "2nd", "3rd", "4th"])
+                    .buffer(&self.multichannel_buffer)
+                    .buffer_layout(BufferLayout::Planar)
+                    .channel_layout(self.multichannel_layout)
+                    .channel_mute(&mut self.multichannel_mute)
+                    .channel_solo(&mut self.multichannel_solo),
+            );
+
+            ui.separator();
+
+            ui.add(
+                WaveformDisplayWidget::new(&mut self.recording_enabled)
+                    .track_name("Recording")
+                    .width(512.0)
+                    .height(96.0)
+                    .summary(&self.recording_summary)
+                    .cursor(&mut self.recording_cursor)
+                    .selection(&mut self.recording_selection)
+                    .sample_rate(OUTPUT_FREQUENCY as f32)
+                    .show_ruler(true)
+                    .show_grid(true),
+            );
+
+            ui.label(format!(
+                "Cursor: {}, selection: {:?}",
+                self.recording_cursor, self.recording_selection
+            ));
+
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.label("WAV file:");
+                ui.text_edit_singleline(&mut self.wav_path)
+                    .on_hover_text("Leave empty for a generated file");
+            });
+
+            let wav_file = if self.wav_path.is_empty() {
+                WavFile::cached_from_bytes(ctx, &self.wav_bytes)
+            } else {
+                WavFile::cached_open(ctx, Path::new(&self.wav_path))
+            };
+
+            match wav_file.as_ref() {
+                Ok(wav_file) => {
+                    ui.add(
+                        WaveformDisplayWidget::new(&mut self.wav_enabled)
+                            .track_name("WAV")
+                            .width(512.0)
+                            .summary(wav_file.summary())
+                            .sample_rate(wav_file.sample_rate() as f32)
+                            .show_ruler(true),
+                    );
+
+                    ui.label(format!(
+                        "{}, {} channels, {} Hz, {:.2} s",
+                        wav_file.sample_format(),
+                        wav_file.channels(),
+                        wav_file.sample_rate(),
+                        wav_file.duration()
+                    ));
+                }
+                Err(err) => {
+                    ui.colored_label(ui.style().visuals.error_fg_color, err.to_string());
+                }
+            }
+
             ui.separator();
             egui::ScrollArea::both().show(ui, |ui| {
                 ctx.settings_ui(ui);
@@ -82,7 +405,7 @@ impl eframe::App for WaveformDemoApp {
 
 fn main() -> Result<(), eframe::Error> {
     let options = eframe::NativeOptions {
-        initial_window_size: Some(vec2(640.0, 480.0)),
+        initial_window_size: Some(vec2(640.0, 960.0)),
         ..Default::default()
     };
 
@@ -0,0 +1,130 @@
+use eframe::egui::{DragValue, Grid, Ui};
+use egui_extras_xt::common::{Orientation, Winding};
+use egui_extras_xt::compasses::{AttitudeIndicator, CompassStyle, CompassStylePreset};
+use egui_extras_xt::ui::widgets_from_iter::SelectableValueFromIter;
+use strum::IntoEnumIterator;
+
+use crate::pages::ui::{compass_style_ui, widget_orientation_ui};
+use crate::pages::PageImpl;
+
+pub struct AttitudeIndicatorPage {
+    pitch: f32,
+    roll: f32,
+    interactive: bool,
+    diameter: f32,
+    orientation: Orientation,
+    winding: Winding,
+    pitch_spread: f32,
+    pitch_ladder_step: f32,
+    animated: bool,
+    show_pitch_ladder: bool,
+    show_roll_scale: bool,
+    style: Option<CompassStyle>,
+    style_preset: CompassStylePreset,
+}
+
+impl Default for AttitudeIndicatorPage {
+    fn default() -> AttitudeIndicatorPage {
+        AttitudeIndicatorPage {
+            pitch: 5.0f32.to_radians(),
+            roll: 15.0f32.to_radians(),
+            interactive: true,
+            diameter: 192.0,
+            orientation: Orientation::Top,
+            winding: Winding::Clockwise,
+            pitch_spread: 60.0f32.to_radians(),
+            pitch_ladder_step: 10.0f32.to_radians(),
+            animated: false,
+            show_pitch_ladder: true,
+            show_roll_scale: true,
+            style: None,
+            style_preset: CompassStylePreset::Aviation,
+        }
+    }
+}
+
+impl PageImpl for AttitudeIndicatorPage {
+    fn ui(&mut self, ui: &mut Ui) {
+        ui.add(
+            AttitudeIndicator::new(&mut self.pitch, &mut self.roll)
+                .interactive(self.interactive)
+                .diameter(self.diameter)
+                .orientation(self.orientation)
+                .winding(self.winding)
+                .pitch_spread(self.pitch_spread)
+                .pitch_ladder_step(self.pitch_ladder_step)
+                .animated(self.animated)
+                .show_pitch_ladder(self.show_pitch_ladder)
+                .show_roll_scale(self.show_roll_scale)
+                .style(self.style.clone()),
+        );
+        ui.separator();
+
+        Grid::new("attitude_indicator_properties")
+            .num_columns(2)
+            .spacing([20.0, 10.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Pitch");
+                ui.drag_angle(&mut self.pitch);
+                ui.end_row();
+
+                ui.label("Roll");
+                ui.drag_angle(&mut self.roll);
+                ui.end_row();
+
+                ui.label("Interactive");
+                ui.checkbox(&mut self.interactive, "");
+                ui.end_row();
+
+                ui.label("Diameter");
+                ui.add(DragValue::new(&mut self.diameter).clamp_range(1.0..=f32::MAX));
+                ui.end_row();
+
+                ui.label("Orientation");
+                widget_orientation_ui(ui, &mut self.orientation);
+                ui.end_row();
+
+                ui.label("Winding");
+                ui.horizontal(|ui| {
+                    ui.selectable_value_from_iter(&mut self.winding, Winding::iter());
+                });
+                ui.end_row();
+
+                ui.label("Pitch spread");
+                ui.drag_angle(&mut self.pitch_spread);
+                self.pitch_spread = self.pitch_spread.max(1.0f32.to_radians());
+                ui.end_row();
+
+                ui.label("Pitch ladder step");
+                ui.drag_angle(&mut self.pitch_ladder_step);
+                self.pitch_ladder_step = self.pitch_ladder_step.max(1.0f32.to_radians());
+                ui.end_row();
+
+                ui.label("Animated");
+                ui.checkbox(&mut self.animated, "");
+                ui.end_row();
+
+                ui.label("Show pitch ladder");
+                ui.checkbox(&mut self.show_pitch_ladder, "");
+                ui.end_row();
+
+                ui.label("Show roll scale");
+                ui.checkbox(&mut self.show_roll_scale, "");
+                ui.end_row();
+
+                ui.label("Style");
+                ui.vertical(|ui| {
+                    let mut system_style = self.style.is_none();
+                    if ui.checkbox(&mut system_style, "System style").changed() {
+                        self.style = (!system_style).then(|| self.style_preset.style());
+                    }
+
+                    if let Some(style) = &mut self.style {
+                        compass_style_ui(ui, style, &mut self.style_preset);
+                    }
+                });
+                ui.end_row();
+            });
+    }
+}
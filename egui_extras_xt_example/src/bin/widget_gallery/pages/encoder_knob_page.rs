@@ -1,14 +1,10 @@
-use std::ops::RangeInclusive;
-
 use eframe::egui::{DragValue, Grid, Ui};
-use egui_extras_xt::common::{Orientation, WidgetShape, Winding};
+use egui_extras_xt::common::{WidgetShape, Winding};
 use egui_extras_xt::knobs::EncoderKnob;
-use egui_extras_xt::ui::drag_rangeinclusive::DragRangeInclusive;
-use egui_extras_xt::ui::optional_value_widget::OptionalValueWidget;
 use egui_extras_xt::ui::widgets_from_iter::SelectableValueFromIter;
 use strum::IntoEnumIterator;
 
-use crate::pages::ui::{widget_orientation_ui, widget_shape_ui};
+use crate::pages::ui::widget_shape_ui;
 use crate::pages::PageImpl;
 
 pub struct EncoderKnobPage {
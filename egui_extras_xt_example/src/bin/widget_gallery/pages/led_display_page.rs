@@ -1,10 +1,13 @@
 use std::ops::RangeInclusive;
 
 use eframe::egui::{DragValue, Grid, Ui};
-use egui_extras_xt::displays::{DisplayStyle, DisplayStylePreset, LedDisplay};
+use eframe::epaint::Color32;
+use egui_extras_xt::common::WidgetShape;
+use egui_extras_xt::displays::{DisplayGlow, DisplayStyle, DisplayStylePreset, LedDisplay};
 use egui_extras_xt::ui::drag_rangeinclusive::DragRangeInclusive;
+use egui_extras_xt::ui::optional_value_widget::OptionalValueWidget;
 
-use crate::pages::ui::display_style_ui;
+use crate::pages::ui::{display_glow_ui, display_style_ui, widget_shape_ui};
 use crate::pages::PageImpl;
 
 pub struct LedDisplayPage {
@@ -12,8 +15,11 @@ pub struct LedDisplayPage {
     diameter: f32,
     padding: f32,
     range: RangeInclusive<f32>,
+    shape: WidgetShape,
+    color: Option<Color32>,
     style: DisplayStyle,
     style_preset: DisplayStylePreset,
+    glow: Option<DisplayGlow>,
     animated: bool,
 }
 
@@ -24,8 +30,11 @@ impl Default for LedDisplayPage {
             diameter: 16.0,
             padding: 0.25,
             range: 0.0..=1.0,
+            shape: WidgetShape::Circle,
+            color: None,
             style: DisplayStylePreset::Default.style(),
             style_preset: DisplayStylePreset::Default,
+            glow: None,
             animated: true,
         }
     }
@@ -38,7 +47,10 @@ impl PageImpl for LedDisplayPage {
                 .diameter(self.diameter)
                 .padding(self.padding)
                 .range(self.range.clone())
+                .shape(self.shape.clone())
+                .color(self.color)
                 .style(self.style)
+                .glow(self.glow)
                 .animated(self.animated),
         );
         ui.separator();
@@ -64,10 +76,24 @@ impl PageImpl for LedDisplayPage {
                 ui.drag_rangeinclusive(&mut self.range);
                 ui.end_row();
 
+                ui.label("Shape");
+                widget_shape_ui(ui, &mut self.shape);
+                ui.end_row();
+
+                ui.label("Color");
+                ui.optional_value_widget(&mut self.color, |ui, color| {
+                    ui.color_edit_button_srgba(color)
+                });
+                ui.end_row();
+
                 ui.label("Style");
                 display_style_ui(ui, &mut self.style, &mut self.style_preset);
                 ui.end_row();
 
+                ui.label("Glow");
+                ui.optional_value_widget(&mut self.glow, display_glow_ui);
+                ui.end_row();
+
                 ui.label("Animated");
                 ui.checkbox(&mut self.animated, "");
                 ui.end_row();
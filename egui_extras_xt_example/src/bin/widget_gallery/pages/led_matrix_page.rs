@@ -0,0 +1,174 @@
+use eframe::egui::{DragValue, Grid, TextEdit, Ui};
+use eframe::epaint::Color32;
+use egui_extras_xt::common::WidgetShape;
+use egui_extras_xt::displays::{DisplayGlow, DisplayStyle, DisplayStylePreset, LedMatrix};
+use egui_extras_xt::ui::optional_value_widget::OptionalValueWidget;
+use egui_extras_xt::ui::widgets_from_iter::SelectableValueFromIter;
+use strum::{Display, EnumIter, IntoEnumIterator};
+
+use crate::pages::ui::{display_glow_ui, display_style_ui, widget_shape_ui};
+use crate::pages::PageImpl;
+
+#[derive(Clone, Copy, Debug, Display, EnumIter, Eq, PartialEq)]
+enum LedMatrixContent {
+    #[strum(to_string = "Text")]
+    Text,
+
+    #[strum(to_string = "Bitmap")]
+    Bitmap,
+
+    #[strum(to_string = "Colors")]
+    Colors,
+}
+
+pub struct LedMatrixPage {
+    content: LedMatrixContent,
+    text: String,
+    text_width: usize,
+    scroll_speed: f32,
+    led_diameter: f32,
+    led_spacing: f32,
+    padding: f32,
+    shape: WidgetShape,
+    style: DisplayStyle,
+    style_preset: DisplayStylePreset,
+    glow: Option<DisplayGlow>,
+}
+
+impl Default for LedMatrixPage {
+    fn default() -> LedMatrixPage {
+        LedMatrixPage {
+            content: LedMatrixContent::Text,
+            text: "Hello, World!".to_owned(),
+            text_width: 32,
+            scroll_speed: 10.0,
+            led_diameter: 8.0,
+            led_spacing: 2.0,
+            padding: 4.0,
+            shape: WidgetShape::Circle,
+            style: DisplayStylePreset::Default.style(),
+            style_preset: DisplayStylePreset::Default,
+            glow: None,
+        }
+    }
+}
+
+impl PageImpl for LedMatrixPage {
+    fn ui(&mut self, ui: &mut Ui) {
+        let time = ui.input(|input| input.time);
+
+        let led_matrix = match self.content {
+            LedMatrixContent::Text => {
+                ui.ctx().request_repaint();
+
+                let offset = (time * self.scroll_speed as f64) as isize;
+                LedMatrix::from_text(&self.text, self.text_width, offset)
+            }
+            LedMatrixContent::Bitmap => {
+                #[rustfmt::skip]
+                const SMILEY: [&str; 8] = [
+                    "..####..",
+                    ".#....#.",
+                    "#.#..#.#",
+                    "#......#",
+                    "#.#..#.#",
+                    "#..##..#",
+                    ".#....#.",
+                    "..####..",
+                ];
+
+                let bitmap = SMILEY
+                    .iter()
+                    .flat_map(|row| row.chars().map(|c| c == '#'))
+                    .collect::<Vec<_>>();
+
+                LedMatrix::from_bitmap(8, 8, &bitmap)
+            }
+            LedMatrixContent::Colors => {
+                ui.ctx().request_repaint();
+
+                let colors = (0..16)
+                    .flat_map(|y| {
+                        (0..16).map(move |x| {
+                            let phase = (x + y) as f64 / 8.0 + time;
+                            let channel =
+                                |offset: f64| ((phase + offset).sin() * 127.5 + 127.5) as u8;
+
+                            Color32::from_rgb(channel(0.0), channel(2.0), channel(4.0))
+                        })
+                    })
+                    .collect::<Vec<_>>();
+
+                LedMatrix::from_colors(16, 16, &colors)
+            }
+        };
+
+        ui.add(
+            led_matrix
+                .led_diameter(self.led_diameter)
+                .led_spacing(self.led_spacing)
+                .padding(self.padding)
+                .shape(self.shape.clone())
+                .style(self.style)
+                .glow(self.glow),
+        );
+        ui.separator();
+
+        Grid::new("led_matrix_properties")
+            .num_columns(2)
+            .spacing([20.0, 10.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Content");
+                ui.horizontal(|ui| {
+                    ui.selectable_value_from_iter(&mut self.content, LedMatrixContent::iter());
+                });
+                ui.end_row();
+
+                ui.label("Text");
+                ui.add_enabled(
+                    self.content == LedMatrixContent::Text,
+                    TextEdit::singleline(&mut self.text),
+                );
+                ui.end_row();
+
+                ui.label("Text width");
+                ui.add_enabled(
+                    self.content == LedMatrixContent::Text,
+                    DragValue::new(&mut self.text_width).clamp_range(1..=128),
+                );
+                ui.end_row();
+
+                ui.label("Scroll speed");
+                ui.add_enabled(
+                    self.content == LedMatrixContent::Text,
+                    DragValue::new(&mut self.scroll_speed),
+                );
+                ui.end_row();
+
+                ui.label("LED diameter");
+                ui.add(DragValue::new(&mut self.led_diameter));
+                ui.end_row();
+
+                ui.label("LED spacing");
+                ui.add(DragValue::new(&mut self.led_spacing));
+                ui.end_row();
+
+                ui.label("Padding");
+                ui.add(DragValue::new(&mut self.padding));
+                ui.end_row();
+
+                ui.label("Shape");
+                widget_shape_ui(ui, &mut self.shape);
+                ui.end_row();
+
+                ui.label("Style");
+                display_style_ui(ui, &mut self.style, &mut self.style_preset);
+                ui.end_row();
+
+                ui.label("Glow");
+                ui.optional_value_widget(&mut self.glow, display_glow_ui);
+                ui.end_row();
+            });
+    }
+}
@@ -0,0 +1,162 @@
+use std::ops::RangeInclusive;
+
+use eframe::egui::{DragValue, Grid, Ui};
+use egui_extras_xt::common::LinearOrientation;
+use egui_extras_xt::displays::{DisplayGlow, DisplayStyle, DisplayStylePreset, LedMeter};
+use egui_extras_xt::ui::drag_rangeinclusive::DragRangeInclusive;
+use egui_extras_xt::ui::optional_value_widget::OptionalValueWidget;
+use egui_extras_xt::ui::widgets_from_iter::SelectableValueFromIter;
+use strum::IntoEnumIterator;
+
+use crate::pages::ui::{display_glow_ui, display_style_ui};
+use crate::pages::PageImpl;
+
+pub struct LedMeterPage {
+    left_value: f32,
+    right_value: f32,
+    stereo: bool,
+    orientation: LinearOrientation,
+    led_count: usize,
+    led_length: f32,
+    led_thickness: f32,
+    led_spacing: f32,
+    channel_spacing: f32,
+    padding: f32,
+    range: RangeInclusive<f32>,
+    decibels: bool,
+    peak_hold: Option<f32>,
+    peak_decay_time: f32,
+    style: DisplayStyle,
+    style_preset: DisplayStylePreset,
+    glow: Option<DisplayGlow>,
+}
+
+impl Default for LedMeterPage {
+    fn default() -> LedMeterPage {
+        LedMeterPage {
+            left_value: 0.5,
+            right_value: 0.75,
+            stereo: true,
+            orientation: LinearOrientation::Vertical,
+            led_count: 16,
+            led_length: 6.0,
+            led_thickness: 16.0,
+            led_spacing: 2.0,
+            channel_spacing: 4.0,
+            padding: 4.0,
+            range: 0.0..=1.0,
+            decibels: false,
+            peak_hold: Some(1.0),
+            peak_decay_time: 2.0,
+            style: DisplayStylePreset::Default.style(),
+            style_preset: DisplayStylePreset::Default,
+            glow: None,
+        }
+    }
+}
+
+impl PageImpl for LedMeterPage {
+    fn ui(&mut self, ui: &mut Ui) {
+        let led_meter = if self.stereo {
+            LedMeter::stereo(self.left_value, self.right_value)
+        } else {
+            LedMeter::new(self.left_value)
+        };
+
+        ui.add(
+            led_meter
+                .orientation(self.orientation)
+                .led_count(self.led_count.max(1))
+                .led_length(self.led_length)
+                .led_thickness(self.led_thickness)
+                .led_spacing(self.led_spacing)
+                .channel_spacing(self.channel_spacing)
+                .padding(self.padding)
+                .range(self.range.clone())
+                .decibels(self.decibels)
+                .peak_hold(self.peak_hold)
+                .peak_decay_time(self.peak_decay_time)
+                .style(self.style)
+                .glow(self.glow),
+        );
+        ui.separator();
+
+        Grid::new("led_meter_properties")
+            .num_columns(2)
+            .spacing([20.0, 10.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Left value");
+                ui.add(DragValue::new(&mut self.left_value).speed(0.01));
+                ui.end_row();
+
+                ui.label("Right value");
+                ui.add(DragValue::new(&mut self.right_value).speed(0.01));
+                ui.end_row();
+
+                ui.label("Stereo");
+                ui.checkbox(&mut self.stereo, "");
+                ui.end_row();
+
+                ui.label("Orientation");
+                ui.horizontal(|ui| {
+                    ui.selectable_value_from_iter(&mut self.orientation, LinearOrientation::iter());
+                });
+                ui.end_row();
+
+                ui.label("LED count");
+                ui.add(DragValue::new(&mut self.led_count).clamp_range(1..=64));
+                ui.end_row();
+
+                ui.label("LED length");
+                ui.add(DragValue::new(&mut self.led_length));
+                ui.end_row();
+
+                ui.label("LED thickness");
+                ui.add(DragValue::new(&mut self.led_thickness));
+                ui.end_row();
+
+                ui.label("LED spacing");
+                ui.add(DragValue::new(&mut self.led_spacing));
+                ui.end_row();
+
+                ui.label("Channel spacing");
+                ui.add(DragValue::new(&mut self.channel_spacing));
+                ui.end_row();
+
+                ui.label("Padding");
+                ui.add(DragValue::new(&mut self.padding));
+                ui.end_row();
+
+                ui.label("Range");
+                ui.drag_rangeinclusive(&mut self.range);
+                ui.end_row();
+
+                ui.label("Decibels");
+                ui.checkbox(&mut self.decibels, "");
+                ui.end_row();
+
+                ui.label("Peak hold");
+                ui.optional_value_widget(&mut self.peak_hold, |ui, value| {
+                    ui.add(DragValue::new(value).speed(0.1).suffix(" s"))
+                });
+                ui.end_row();
+
+                ui.label("Peak decay time");
+                ui.add(
+                    DragValue::new(&mut self.peak_decay_time)
+                        .speed(0.1)
+                        .suffix(" s"),
+                );
+                ui.end_row();
+
+                ui.label("Style");
+                display_style_ui(ui, &mut self.style, &mut self.style_preset);
+                ui.end_row();
+
+                ui.label("Glow");
+                ui.optional_value_widget(&mut self.glow, display_glow_ui);
+                ui.end_row();
+            });
+    }
+}
@@ -1,15 +1,18 @@
-use eframe::egui::{DragValue, Grid, Ui};
+use eframe::egui::{DragValue, Grid, Id, Ui};
 use eframe::epaint::Color32;
 use egui_extras_xt::common::{Winding, WrapMode};
 use egui_extras_xt::compasses::{
-    CompassAxisLabels, CompassMarker, CompassMarkerShape, DefaultCompassMarkerColor, LinearCompass,
+    CompassAxisLabels, CompassBug, CompassBugKind, CompassMarker, CompassMarkerDrag,
+    CompassMarkerShape, CompassSector, CompassStyle, CompassStylePreset, DefaultCompassMarkerColor,
+    LinearCompass,
 };
 use egui_extras_xt::ui::optional_value_widget::OptionalValueWidget;
 use egui_extras_xt::ui::widgets_from_iter::SelectableValueFromIter;
 use strum::IntoEnumIterator;
 
 use crate::pages::ui::{
-    compass_axis_labels_ui, default_compass_marker_color_ui, default_compass_marker_shape_ui,
+    compass_axis_labels_ui, compass_style_ui, default_compass_marker_color_ui,
+    default_compass_marker_shape_ui,
 };
 use crate::pages::PageImpl;
 
@@ -30,8 +33,16 @@ pub struct LinearCompassPage {
     show_cursor: bool,
     show_ticks: bool,
     show_axes: bool,
+    show_marker_tooltips: bool,
     default_marker_color: DefaultCompassMarkerColor,
     default_marker_shape: CompassMarkerShape,
+    marker_angles: Vec<f32>,
+    selected_marker: Option<Id>,
+    heading_bug: f32,
+    course_bug: f32,
+    waypoint_bug: f32,
+    style: Option<CompassStyle>,
+    style_preset: CompassStylePreset,
 }
 
 impl Default for LinearCompassPage {
@@ -58,18 +69,35 @@ impl Default for LinearCompassPage {
             show_cursor: true,
             show_ticks: true,
             show_axes: true,
+            show_marker_tooltips: true,
             default_marker_color: DefaultCompassMarkerColor::HsvByAngle {
                 hue_phase: 0.0,
                 saturation: 1.0,
                 value: 1.0,
             },
             default_marker_shape: CompassMarkerShape::Square,
+            marker_angles: [
+                0.0, 70.0, 85.0, 100.0, 553.0, 563.0, 240.0, 250.0, 260.0, 270.0, 280.0, 290.0,
+                300.0, 310.0, 320.0, 30.0, 40.0, 50.0,
+            ]
+            .map(f32::to_radians)
+            .to_vec(),
+            selected_marker: None,
+            heading_bug: 20.0f32.to_radians(),
+            course_bug: 35.0f32.to_radians(),
+            waypoint_bug: 300.0f32.to_radians(),
+            style: None,
+            style_preset: CompassStylePreset::Dark,
         }
     }
 }
 
 impl PageImpl for LinearCompassPage {
     fn ui(&mut self, ui: &mut Ui) {
+        let marker_angle = |marker_index: usize| self.marker_angles[marker_index];
+
+        let mut dragged_marker = None;
+
         ui.add(
             LinearCompass::new(&mut self.value)
                 .interactive(self.interactive)
@@ -87,77 +115,103 @@ impl PageImpl for LinearCompassPage {
                 .show_cursor(self.show_cursor)
                 .show_ticks(self.show_ticks)
                 .show_axes(self.show_axes)
+                .show_marker_tooltips(self.show_marker_tooltips)
+                .style(self.style.clone())
                 .default_marker_color(self.default_marker_color)
                 .default_marker_shape(self.default_marker_shape)
+                .selected_marker(&mut self.selected_marker)
+                .dragged_marker(&mut dragged_marker)
                 .markers(&[
-                    CompassMarker::new(0.0f32.to_radians()).label("Default"),
+                    CompassMarker::new(marker_angle(0)).label("Default"),
                     // Grand Theft Auto style markers
-                    CompassMarker::new(70.0f32.to_radians())
+                    CompassMarker::new(marker_angle(1))
                         .shape(CompassMarkerShape::Square)
                         .label("Sweet")
                         .color(Color32::from_rgb(0x00, 0x00, 0xFF)),
-                    CompassMarker::new(85.0f32.to_radians())
+                    CompassMarker::new(marker_angle(2))
                         .shape(CompassMarkerShape::DownArrow)
                         .label("Reece's")
                         .color(Color32::from_rgb(0xFF, 0xFF, 0x00)),
-                    CompassMarker::new(100.0f32.to_radians())
+                    CompassMarker::new(marker_angle(3))
                         .shape(CompassMarkerShape::UpArrow)
                         .label("Big Smoke")
                         .color(Color32::from_rgb(0xFF, 0x00, 0x00)),
                     // Emoji markers
-                    CompassMarker::new(553.0f32.to_radians())
+                    CompassMarker::new(marker_angle(4))
                         .shape(CompassMarkerShape::Emoji('🐱'))
                         .label("Cat")
                         .color(Color32::from_rgb(0xF8, 0xE9, 0xFF)),
-                    CompassMarker::new(563.0f32.to_radians())
+                    CompassMarker::new(marker_angle(5))
                         .shape(CompassMarkerShape::Emoji('🐶'))
                         .label("Dog")
                         .color(Color32::from_rgb(0xC0, 0x8C, 0x85)),
                     // All marker shapes
-                    CompassMarker::new(240.0f32.to_radians())
+                    CompassMarker::new(marker_angle(6))
                         .shape(CompassMarkerShape::Square)
                         .label("A"),
-                    CompassMarker::new(250.0f32.to_radians())
+                    CompassMarker::new(marker_angle(7))
                         .shape(CompassMarkerShape::Circle)
                         .label("B"),
-                    CompassMarker::new(260.0f32.to_radians())
+                    CompassMarker::new(marker_angle(8))
                         .shape(CompassMarkerShape::RightArrow)
                         .label("C"),
-                    CompassMarker::new(270.0f32.to_radians())
+                    CompassMarker::new(marker_angle(9))
                         .shape(CompassMarkerShape::UpArrow)
                         .label("D"),
-                    CompassMarker::new(280.0f32.to_radians())
+                    CompassMarker::new(marker_angle(10))
                         .shape(CompassMarkerShape::LeftArrow)
                         .label("E"),
-                    CompassMarker::new(290.0f32.to_radians())
+                    CompassMarker::new(marker_angle(11))
                         .shape(CompassMarkerShape::DownArrow)
                         .label("F"),
-                    CompassMarker::new(300.0f32.to_radians())
+                    CompassMarker::new(marker_angle(12))
                         .shape(CompassMarkerShape::Diamond)
                         .label("G"),
-                    CompassMarker::new(310.0f32.to_radians())
+                    CompassMarker::new(marker_angle(13))
                         .shape(CompassMarkerShape::Star(5, 0.5))
                         .label("H"),
-                    CompassMarker::new(320.0f32.to_radians())
+                    CompassMarker::new(marker_angle(14))
                         .shape(CompassMarkerShape::Emoji('🗿'))
                         .label("I"),
                     // Transparent colors
-                    CompassMarker::new(30.0f32.to_radians())
+                    CompassMarker::new(marker_angle(15))
                         .shape(CompassMarkerShape::Square)
                         .label("Near")
                         .color(Color32::from_rgb(0x40, 0x80, 0x80).linear_multiply(1.0)),
-                    CompassMarker::new(40.0f32.to_radians())
+                    CompassMarker::new(marker_angle(16))
                         .shape(CompassMarkerShape::Square)
                         .label("Far")
                         .color(Color32::from_rgb(0x40, 0x80, 0x80).linear_multiply(0.5)),
-                    CompassMarker::new(50.0f32.to_radians())
+                    CompassMarker::new(marker_angle(17))
                         .shape(CompassMarkerShape::Square)
                         .label("Very far")
                         .color(Color32::from_rgb(0x40, 0x80, 0x80).linear_multiply(0.25)),
+                ])
+                .bug(CompassBug::new(
+                    CompassBugKind::Heading,
+                    &mut self.heading_bug,
+                ))
+                .bug(CompassBug::new(
+                    CompassBugKind::Course,
+                    &mut self.course_bug,
+                ))
+                .bug(CompassBug::new(
+                    CompassBugKind::Waypoint,
+                    &mut self.waypoint_bug,
+                ))
+                .sectors(&[
+                    CompassSector::new(330.0f32.to_radians(), 30.0f32.to_radians()).label("FOV"),
+                    CompassSector::centered(225.0f32.to_radians(), 30.0f32.to_radians())
+                        .label("Blind")
+                        .color(Color32::from_rgb(0xFF, 0x00, 0x00).linear_multiply(0.25)),
                 ]),
         );
         ui.separator();
 
+        if let Some(CompassMarkerDrag { index, angle, .. }) = dragged_marker {
+            self.marker_angles[index] = angle;
+        }
+
         Grid::new("linear_compass_properties")
             .num_columns(2)
             .spacing([20.0, 10.0])
@@ -167,6 +221,18 @@ impl PageImpl for LinearCompassPage {
                 ui.drag_angle(&mut self.value);
                 ui.end_row();
 
+                ui.label("Heading bug");
+                ui.drag_angle(&mut self.heading_bug);
+                ui.end_row();
+
+                ui.label("Course bug");
+                ui.drag_angle(&mut self.course_bug);
+                ui.end_row();
+
+                ui.label("Waypoint bug");
+                ui.drag_angle(&mut self.waypoint_bug);
+                ui.end_row();
+
                 ui.label("Interactive");
                 ui.checkbox(&mut self.interactive, "");
                 ui.end_row();
@@ -231,6 +297,30 @@ impl PageImpl for LinearCompassPage {
                 ui.checkbox(&mut self.show_axes, "");
                 ui.end_row();
 
+                ui.label("Show marker tooltips");
+                ui.checkbox(&mut self.show_marker_tooltips, "");
+                ui.end_row();
+
+                ui.label("Style");
+                ui.vertical(|ui| {
+                    let mut system_style = self.style.is_none();
+                    if ui.checkbox(&mut system_style, "System style").changed() {
+                        self.style = (!system_style).then(|| self.style_preset.style());
+                    }
+
+                    if let Some(style) = &mut self.style {
+                        compass_style_ui(ui, style, &mut self.style_preset);
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Selected marker");
+                ui.label(match self.selected_marker {
+                    Some(marker_id) => marker_id.short_debug_format(),
+                    None => "None".to_owned(),
+                });
+                ui.end_row();
+
                 ui.label("Default marker color");
                 default_compass_marker_color_ui(ui, &mut self.default_marker_color);
                 ui.end_row();
@@ -0,0 +1,201 @@
+use eframe::egui::{DragValue, Grid, Ui};
+use egui_extras_xt::common::LinearOrientation;
+use egui_extras_xt::compasses::{
+    CompassBug, CompassBugKind, CompassStyle, CompassStylePreset, LinearTape,
+};
+use egui_extras_xt::ui::optional_value_widget::OptionalValueWidget;
+use egui_extras_xt::ui::widgets_from_iter::SelectableValueFromIter;
+use strum::IntoEnumIterator;
+
+use crate::pages::ui::compass_style_ui;
+use crate::pages::PageImpl;
+
+pub struct LinearTapePage {
+    value: f32,
+    interactive: bool,
+    orientation: LinearOrientation,
+    width: f32,
+    height: f32,
+    spread: f32,
+    inverted: bool,
+    mirrored: bool,
+    major_tick_step: f32,
+    minor_tick_step: f32,
+    min: Option<f32>,
+    max: Option<f32>,
+    animated: bool,
+    show_ticks: bool,
+    show_labels: bool,
+    show_readout: bool,
+    readout_rolling_digits: usize,
+    trend_vector: Option<f32>,
+    target_bug: f32,
+    style: Option<CompassStyle>,
+    style_preset: CompassStylePreset,
+}
+
+impl Default for LinearTapePage {
+    fn default() -> LinearTapePage {
+        LinearTapePage {
+            value: 1250.0,
+            interactive: true,
+            orientation: LinearOrientation::Vertical,
+            width: 80.0,
+            height: 320.0,
+            spread: 500.0,
+            inverted: false,
+            mirrored: false,
+            major_tick_step: 100.0,
+            minor_tick_step: 20.0,
+            min: Some(0.0),
+            max: None,
+            animated: false,
+            show_ticks: true,
+            show_labels: true,
+            show_readout: true,
+            readout_rolling_digits: 2,
+            trend_vector: Some(6.0),
+            target_bug: 1500.0,
+            style: None,
+            style_preset: CompassStylePreset::Aviation,
+        }
+    }
+}
+
+impl PageImpl for LinearTapePage {
+    fn ui(&mut self, ui: &mut Ui) {
+        ui.add(
+            LinearTape::new(&mut self.value)
+                .interactive(self.interactive)
+                .orientation(self.orientation)
+                .width(self.width)
+                .height(self.height)
+                .spread(self.spread)
+                .inverted(self.inverted)
+                .mirrored(self.mirrored)
+                .major_tick_step(self.major_tick_step)
+                .minor_tick_step(self.minor_tick_step)
+                .min(self.min)
+                .max(self.max)
+                .animated(self.animated)
+                .show_ticks(self.show_ticks)
+                .show_labels(self.show_labels)
+                .show_readout(self.show_readout)
+                .readout_rolling_digits(self.readout_rolling_digits)
+                .trend_vector(self.trend_vector)
+                .style(self.style.clone())
+                .bug(CompassBug::new(
+                    CompassBugKind::Heading,
+                    &mut self.target_bug,
+                )),
+        );
+        ui.separator();
+
+        Grid::new("linear_tape_properties")
+            .num_columns(2)
+            .spacing([20.0, 10.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Value");
+                ui.add(DragValue::new(&mut self.value));
+                ui.end_row();
+
+                ui.label("Target bug");
+                ui.add(DragValue::new(&mut self.target_bug));
+                ui.end_row();
+
+                ui.label("Interactive");
+                ui.checkbox(&mut self.interactive, "");
+                ui.end_row();
+
+                ui.label("Orientation");
+                ui.horizontal(|ui| {
+                    if ui
+                        .selectable_value_from_iter(
+                            &mut self.orientation,
+                            LinearOrientation::iter(),
+                        )
+                        .changed()
+                    {
+                        std::mem::swap(&mut self.width, &mut self.height);
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Width");
+                ui.add(DragValue::new(&mut self.width));
+                ui.end_row();
+
+                ui.label("Height");
+                ui.add(DragValue::new(&mut self.height));
+                ui.end_row();
+
+                ui.label("Spread");
+                ui.add(DragValue::new(&mut self.spread).clamp_range(1.0..=f32::MAX));
+                ui.end_row();
+
+                ui.label("Inverted");
+                ui.checkbox(&mut self.inverted, "");
+                ui.end_row();
+
+                ui.label("Mirrored");
+                ui.checkbox(&mut self.mirrored, "");
+                ui.end_row();
+
+                ui.label("Major tick step");
+                ui.add(DragValue::new(&mut self.major_tick_step).clamp_range(1.0..=f32::MAX));
+                ui.end_row();
+
+                ui.label("Minor tick step");
+                ui.add(DragValue::new(&mut self.minor_tick_step).clamp_range(1.0..=f32::MAX));
+                ui.end_row();
+
+                ui.label("Minimum");
+                ui.optional_value_widget(&mut self.min, |ui, value| ui.add(DragValue::new(value)));
+                ui.end_row();
+
+                ui.label("Maximum");
+                ui.optional_value_widget(&mut self.max, |ui, value| ui.add(DragValue::new(value)));
+                ui.end_row();
+
+                ui.label("Animated");
+                ui.checkbox(&mut self.animated, "");
+                ui.end_row();
+
+                ui.label("Show ticks");
+                ui.checkbox(&mut self.show_ticks, "");
+                ui.end_row();
+
+                ui.label("Show labels");
+                ui.checkbox(&mut self.show_labels, "");
+                ui.end_row();
+
+                ui.label("Show readout");
+                ui.checkbox(&mut self.show_readout, "");
+                ui.end_row();
+
+                ui.label("Readout rolling digits");
+                ui.add(DragValue::new(&mut self.readout_rolling_digits).clamp_range(1..=4));
+                ui.end_row();
+
+                ui.label("Trend vector");
+                ui.optional_value_widget(&mut self.trend_vector, |ui, value| {
+                    ui.add(DragValue::new(value).suffix(" s"))
+                });
+                ui.end_row();
+
+                ui.label("Style");
+                ui.vertical(|ui| {
+                    let mut system_style = self.style.is_none();
+                    if ui.checkbox(&mut system_style, "System style").changed() {
+                        self.style = (!system_style).then(|| self.style_preset.style());
+                    }
+
+                    if let Some(style) = &mut self.style {
+                        compass_style_ui(ui, style, &mut self.style_preset);
+                    }
+                });
+                ui.end_row();
+            });
+    }
+}
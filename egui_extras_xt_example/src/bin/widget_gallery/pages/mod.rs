@@ -8,6 +8,9 @@ use strum::{Display, EnumIter, EnumProperty};
 mod angle_knob_page;
 use angle_knob_page::AngleKnobPage;
 
+mod attitude_indicator_page;
+use attitude_indicator_page::AttitudeIndicatorPage;
+
 mod audio_knob_page;
 use audio_knob_page::AudioKnobPage;
 
@@ -32,9 +35,21 @@ use indicator_button_page::IndicatorButtonPage;
 mod led_display_page;
 use led_display_page::LedDisplayPage;
 
+mod led_matrix_page;
+use led_matrix_page::LedMatrixPage;
+
+mod led_meter_page;
+use led_meter_page::LedMeterPage;
+
 mod linear_compass_page;
 use linear_compass_page::LinearCompassPage;
 
+mod linear_tape_page;
+use linear_tape_page::LinearTapePage;
+
+mod panel_meter_page;
+use panel_meter_page::PanelMeterPage;
+
 mod polar_compass_page;
 use polar_compass_page::PolarCompassPage;
 
@@ -69,6 +84,10 @@ pub enum PageId {
     #[strum(props(feature = "knobs"))]
     AngleKnobPage,
 
+    #[strum(to_string = "AttitudeIndicator")]
+    #[strum(props(feature = "compasses"))]
+    AttitudeIndicatorPage,
+
     #[strum(to_string = "AudioKnob")]
     #[strum(props(feature = "knobs"))]
     AudioKnobPage,
@@ -101,10 +120,26 @@ pub enum PageId {
     #[strum(props(feature = "displays"))]
     LedDisplayPage,
 
+    #[strum(to_string = "LedMatrix")]
+    #[strum(props(feature = "displays"))]
+    LedMatrixPage,
+
+    #[strum(to_string = "LedMeter")]
+    #[strum(props(feature = "displays"))]
+    LedMeterPage,
+
     #[strum(to_string = "LinearCompass")]
     #[strum(props(feature = "compasses"))]
     LinearCompassPage,
 
+    #[strum(to_string = "LinearTape")]
+    #[strum(props(feature = "compasses"))]
+    LinearTapePage,
+
+    #[strum(to_string = "PanelMeter")]
+    #[strum(props(feature = "displays"))]
+    PanelMeterPage,
+
     #[strum(to_string = "PolarCompass")]
     #[strum(props(feature = "compasses"))]
     PolarCompassPage,
@@ -137,6 +172,7 @@ impl PageId {
     pub fn create_page(&self) -> Box<dyn PageImpl> {
         match *self {
             PageId::AngleKnobPage => Box::<AngleKnobPage>::default(),
+            PageId::AttitudeIndicatorPage => Box::<AttitudeIndicatorPage>::default(),
             PageId::AudioKnobPage => Box::<AudioKnobPage>::default(),
             PageId::EncoderKnobPage => Box::<EncoderKnobPage>::default(),
             PageId::BarcodePage => Box::<BarcodePage>::default(),
@@ -145,7 +181,11 @@ impl PageId {
             PageId::HyperlinkWithIconPage => Box::<HyperlinkWithIconPage>::default(),
             PageId::IndicatorButtonPage => Box::<IndicatorButtonPage>::default(),
             PageId::LedDisplayPage => Box::<LedDisplayPage>::default(),
+            PageId::LedMatrixPage => Box::<LedMatrixPage>::default(),
+            PageId::LedMeterPage => Box::<LedMeterPage>::default(),
             PageId::LinearCompassPage => Box::<LinearCompassPage>::default(),
+            PageId::LinearTapePage => Box::<LinearTapePage>::default(),
+            PageId::PanelMeterPage => Box::<PanelMeterPage>::default(),
             PageId::PolarCompassPage => Box::<PolarCompassPage>::default(),
             PageId::QrCodePage => Box::<QrCodePage>::default(),
             PageId::RotatedLabelPage => Box::<RotatedLabelPage>::default(),
@@ -0,0 +1,143 @@
+use std::f32::consts::TAU;
+use std::ops::RangeInclusive;
+
+use eframe::egui::{DragValue, Grid, Ui};
+use egui_extras_xt::displays::{
+    DisplayGlow, DisplayStyle, DisplayStylePreset, PanelMeter, PanelMeterBallistics,
+};
+use egui_extras_xt::ui::drag_rangeinclusive::DragRangeInclusive;
+use egui_extras_xt::ui::optional_value_widget::OptionalValueWidget;
+use egui_extras_xt::ui::widgets_from_iter::SelectableValueFromIter;
+use strum::IntoEnumIterator;
+
+use crate::pages::ui::{display_glow_ui, display_style_ui};
+use crate::pages::PageImpl;
+
+pub struct PanelMeterPage {
+    value: f32,
+    range: RangeInclusive<f32>,
+    decibels: bool,
+    radius: f32,
+    sweep: f32,
+    label_height: f32,
+    minor_ticks: usize,
+    ballistics: PanelMeterBallistics,
+    peak_led: Option<f32>,
+    peak_led_hold: f32,
+    padding: f32,
+    style: DisplayStyle,
+    style_preset: DisplayStylePreset,
+    glow: Option<DisplayGlow>,
+}
+
+impl Default for PanelMeterPage {
+    fn default() -> PanelMeterPage {
+        PanelMeterPage {
+            value: 0.5,
+            range: 0.0..=1.0,
+            decibels: false,
+            radius: 64.0,
+            sweep: TAU / 4.0,
+            label_height: 10.0,
+            minor_ticks: 4,
+            ballistics: PanelMeterBallistics::Vu,
+            peak_led: Some(0.9),
+            peak_led_hold: 1.0,
+            padding: 4.0,
+            style: DisplayStylePreset::Default.style(),
+            style_preset: DisplayStylePreset::Default,
+            glow: None,
+        }
+    }
+}
+
+impl PageImpl for PanelMeterPage {
+    fn ui(&mut self, ui: &mut Ui) {
+        ui.add(
+            PanelMeter::new(self.value)
+                .range(self.range.clone())
+                .decibels(self.decibels)
+                .radius(self.radius)
+                .sweep(self.sweep)
+                .label_height(self.label_height)
+                .minor_ticks(self.minor_ticks)
+                .ballistics(self.ballistics)
+                .peak_led(self.peak_led)
+                .peak_led_hold(self.peak_led_hold)
+                .padding(self.padding)
+                .style(self.style)
+                .glow(self.glow),
+        );
+        ui.separator();
+
+        Grid::new("panel_meter_properties")
+            .num_columns(2)
+            .spacing([20.0, 10.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Value");
+                ui.add(DragValue::new(&mut self.value).speed(0.01));
+                ui.end_row();
+
+                ui.label("Range");
+                ui.drag_rangeinclusive(&mut self.range);
+                ui.end_row();
+
+                ui.label("Decibels");
+                ui.checkbox(&mut self.decibels, "");
+                ui.end_row();
+
+                ui.label("Radius");
+                ui.add(DragValue::new(&mut self.radius));
+                ui.end_row();
+
+                ui.label("Sweep");
+                ui.drag_angle(&mut self.sweep);
+                self.sweep = self.sweep.clamp(0.1, TAU * 0.75);
+                ui.end_row();
+
+                ui.label("Label height");
+                ui.add(DragValue::new(&mut self.label_height));
+                ui.end_row();
+
+                ui.label("Minor ticks");
+                ui.add(DragValue::new(&mut self.minor_ticks).clamp_range(0..=9));
+                ui.end_row();
+
+                ui.label("Ballistics");
+                ui.horizontal(|ui| {
+                    ui.selectable_value_from_iter(
+                        &mut self.ballistics,
+                        PanelMeterBallistics::iter(),
+                    );
+                });
+                ui.end_row();
+
+                ui.label("Peak LED");
+                ui.optional_value_widget(&mut self.peak_led, |ui, value| {
+                    ui.add(DragValue::new(value).speed(0.01))
+                });
+                ui.end_row();
+
+                ui.label("Peak LED hold");
+                ui.add(
+                    DragValue::new(&mut self.peak_led_hold)
+                        .speed(0.1)
+                        .suffix(" s"),
+                );
+                ui.end_row();
+
+                ui.label("Padding");
+                ui.add(DragValue::new(&mut self.padding));
+                ui.end_row();
+
+                ui.label("Style");
+                display_style_ui(ui, &mut self.style, &mut self.style_preset);
+                ui.end_row();
+
+                ui.label("Glow");
+                ui.optional_value_widget(&mut self.glow, display_glow_ui);
+                ui.end_row();
+            });
+    }
+}
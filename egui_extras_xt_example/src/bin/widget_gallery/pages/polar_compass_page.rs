@@ -1,17 +1,19 @@
-use eframe::egui::{DragValue, Grid, Ui};
+use eframe::egui::{DragValue, Grid, Id, Ui};
 use eframe::epaint::Color32;
 use egui_extras_xt::common::{Orientation, Winding, WrapMode};
 use egui_extras_xt::compasses::{
-    CompassAxisLabels, CompassMarker, CompassMarkerShape, DefaultCompassMarkerColor, PolarCompass,
-    PolarCompassOverflow,
+    CompassAxisLabels, CompassBug, CompassBugKind, CompassMarker, CompassMarkerDrag,
+    CompassMarkerShape, CompassSector, CompassStyle, CompassStylePreset, DefaultCompassMarkerColor,
+    PolarCompass, PolarCompassLabelLayout, PolarCompassMode, PolarCompassOverflow,
 };
+use egui_extras_xt::displays::{DisplayStyle, DisplayStylePreset};
 use egui_extras_xt::ui::optional_value_widget::OptionalValueWidget;
 use egui_extras_xt::ui::widgets_from_iter::SelectableValueFromIter;
 use strum::IntoEnumIterator;
 
 use crate::pages::ui::{
-    compass_axis_labels_ui, default_compass_marker_color_ui, default_compass_marker_shape_ui,
-    widget_orientation_ui,
+    compass_axis_labels_ui, compass_style_ui, default_compass_marker_color_ui,
+    default_compass_marker_shape_ui, display_style_ui, widget_orientation_ui,
 };
 use crate::pages::PageImpl;
 
@@ -20,6 +22,7 @@ pub struct PolarCompassPage {
     interactive: bool,
     orientation: Orientation,
     winding: Winding,
+    mode: PolarCompassMode,
     overflow: PolarCompassOverflow,
     diameter: f32,
     wrap: WrapMode,
@@ -40,8 +43,24 @@ pub struct PolarCompassPage {
     show_cursor: bool,
     show_marker_labels: bool,
     show_marker_lines: bool,
+    show_marker_tooltips: bool,
+    label_layout: PolarCompassLabelLayout,
+    marker_clustering: Option<f32>,
+    marker_trail_length: Option<f32>,
+    marker_velocity_vectors: Option<f32>,
+    radar_sweep: Option<f32>,
+    radar_afterglow: f32,
+    radar_style: DisplayStyle,
+    radar_style_preset: DisplayStylePreset,
     default_marker_color: DefaultCompassMarkerColor,
     default_marker_shape: CompassMarkerShape,
+    marker_positions: Vec<(f32, f32)>,
+    selected_marker: Option<Id>,
+    heading_bug: f32,
+    course_bug: f32,
+    waypoint_bug: f32,
+    style: Option<CompassStyle>,
+    style_preset: CompassStylePreset,
 }
 
 impl Default for PolarCompassPage {
@@ -51,6 +70,7 @@ impl Default for PolarCompassPage {
             interactive: true,
             orientation: Orientation::Top,
             winding: Winding::Clockwise,
+            mode: PolarCompassMode::HeadingUp,
             overflow: PolarCompassOverflow::Saturate,
             diameter: 256.0,
             wrap: WrapMode::Unsigned,
@@ -76,23 +96,66 @@ impl Default for PolarCompassPage {
             show_cursor: true,
             show_marker_labels: true,
             show_marker_lines: true,
+            show_marker_tooltips: true,
+            label_layout: PolarCompassLabelLayout::Offset,
+            marker_clustering: None,
+            marker_trail_length: Some(10.0),
+            marker_velocity_vectors: Some(2.0),
+            radar_sweep: None,
+            radar_afterglow: 0.75,
+            radar_style: DisplayStylePreset::DeLoreanGreen.style(),
+            radar_style_preset: DisplayStylePreset::DeLoreanGreen,
             default_marker_color: DefaultCompassMarkerColor::HsvByAngle {
                 hue_phase: 0.0,
                 saturation: 1.0,
                 value: 1.0,
             },
             default_marker_shape: CompassMarkerShape::Square,
+            marker_positions: vec![
+                (0.0f32.to_radians(), 10.0),
+                (15.0f32.to_radians(), 100.0),
+                (30.0f32.to_radians(), 1000.0),
+                (45.0f32.to_radians(), 10000.0),
+                (135.0f32.to_radians(), 100.0),
+                (157.5f32.to_radians(), 200.0),
+                (180.0f32.to_radians(), 300.0),
+                (202.5f32.to_radians(), 400.0),
+                (225.0f32.to_radians(), 500.0),
+                (247.5f32.to_radians(), 600.0),
+                (270.0f32.to_radians(), 700.0),
+                (292.5f32.to_radians(), 800.0),
+                (315.0f32.to_radians(), 900.0),
+                (337.5f32.to_radians(), 1000.0),
+                (100.0f32.to_radians(), 2000.0),
+                (102.0f32.to_radians(), 2200.0),
+                (104.0f32.to_radians(), 2400.0),
+                (106.0f32.to_radians(), 2600.0),
+            ],
+            selected_marker: None,
+            heading_bug: 20.0f32.to_radians(),
+            course_bug: 35.0f32.to_radians(),
+            waypoint_bug: 300.0f32.to_radians(),
+            style: None,
+            style_preset: CompassStylePreset::Dark,
         }
     }
 }
 
 impl PageImpl for PolarCompassPage {
     fn ui(&mut self, ui: &mut Ui) {
+        let marker = |marker_index: usize| {
+            let (angle, distance) = self.marker_positions[marker_index];
+            CompassMarker::new(angle).distance(distance)
+        };
+
+        let mut dragged_marker = None;
+
         ui.add(
             PolarCompass::new(&mut self.value)
                 .interactive(self.interactive)
                 .orientation(self.orientation)
                 .winding(self.winding)
+                .mode(self.mode)
                 .overflow(self.overflow)
                 .diameter(self.diameter)
                 .wrap(self.wrap)
@@ -113,64 +176,88 @@ impl PageImpl for PolarCompassPage {
                 .show_cursor(self.show_cursor)
                 .show_marker_labels(self.show_marker_labels)
                 .show_marker_lines(self.show_marker_lines)
+                .show_marker_tooltips(self.show_marker_tooltips)
+                .style(self.style.clone())
+                .label_layout(self.label_layout)
+                .marker_clustering(self.marker_clustering)
+                .marker_trail_length(self.marker_trail_length)
+                .marker_velocity_vectors(self.marker_velocity_vectors)
+                .radar_sweep(self.radar_sweep)
+                .radar_afterglow(self.radar_afterglow)
+                .radar_style(self.radar_style)
                 .default_marker_color(self.default_marker_color)
                 .default_marker_shape(self.default_marker_shape)
+                .selected_marker(&mut self.selected_marker)
+                .dragged_marker(&mut dragged_marker)
                 .markers(&[
-                    CompassMarker::new(0.0f32.to_radians())
-                        .distance(10.0)
+                    marker(0)
                         .color(Color32::from_rgb(0xF0, 0xBF, 0x89))
                         .shape(CompassMarkerShape::Diamond)
-                        .label("Haibara"),
-                    CompassMarker::new(15.0f32.to_radians())
-                        .distance(100.0)
+                        .label("Haibara")
+                        .tooltip("Drag to move")
+                        .altitude(120.0),
+                    marker(1)
                         .color(Color32::from_rgb(0x9C, 0xCF, 0xEE))
                         .shape(CompassMarkerShape::DownArrow)
-                        .label("Mitsuhiko"),
-                    CompassMarker::new(30.0f32.to_radians())
-                        .distance(1000.0)
+                        .label("Mitsuhiko")
+                        .velocity(5.0, 90.0f32.to_radians()),
+                    marker(2)
                         .color(Color32::from_rgb(0x8A, 0xDC, 0x71))
                         .shape(CompassMarkerShape::Circle)
                         .label("Genta"),
-                    CompassMarker::new(45.0f32.to_radians())
-                        .distance(10000.0)
+                    marker(3)
                         .color(Color32::from_rgb(0xEF, 0xBB, 0xC4))
                         .shape(CompassMarkerShape::UpArrow)
                         .label("Ayumi"),
                     // Markers with unset colors and shapes
-                    CompassMarker::new(135.0f32.to_radians())
-                        .distance(100.0)
-                        .label("A"),
-                    CompassMarker::new(157.5f32.to_radians())
-                        .distance(200.0)
-                        .label("B"),
-                    CompassMarker::new(180.0f32.to_radians())
-                        .distance(300.0)
-                        .label("C"),
-                    CompassMarker::new(202.5f32.to_radians())
-                        .distance(400.0)
-                        .label("D"),
-                    CompassMarker::new(225.0f32.to_radians())
-                        .distance(500.0)
-                        .label("E"),
-                    CompassMarker::new(247.5f32.to_radians())
-                        .distance(600.0)
-                        .label("F"),
-                    CompassMarker::new(270.0f32.to_radians())
-                        .distance(700.0)
-                        .label("G"),
-                    CompassMarker::new(292.5f32.to_radians())
-                        .distance(800.0)
-                        .label("H"),
-                    CompassMarker::new(315.0f32.to_radians())
-                        .distance(900.0)
-                        .label("I"),
-                    CompassMarker::new(337.5f32.to_radians())
-                        .distance(1000.0)
-                        .label("J"),
+                    marker(4).label("A"),
+                    marker(5).label("B"),
+                    marker(6).label("C"),
+                    marker(7).label("D"),
+                    marker(8).label("E"),
+                    marker(9).label("F"),
+                    marker(10).label("G"),
+                    marker(11).label("H"),
+                    marker(12).label("I"),
+                    marker(13).label("J"),
+                    // Markers with overlapping labels
+                    marker(14).label("Alpha"),
+                    marker(15).label("Bravo"),
+                    marker(16).label("Charlie"),
+                    marker(17).label("Delta"),
+                ])
+                .bug(CompassBug::new(
+                    CompassBugKind::Heading,
+                    &mut self.heading_bug,
+                ))
+                .bug(CompassBug::new(
+                    CompassBugKind::Course,
+                    &mut self.course_bug,
+                ))
+                .bug(CompassBug::new(
+                    CompassBugKind::Waypoint,
+                    &mut self.waypoint_bug,
+                ))
+                .sectors(&[
+                    CompassSector::new(330.0f32.to_radians(), 30.0f32.to_radians()).label("FOV"),
+                    CompassSector::centered(225.0f32.to_radians(), 30.0f32.to_radians())
+                        .distance_range(100.0..=1000.0)
+                        .label("Blind")
+                        .color(Color32::from_rgb(0xFF, 0x00, 0x00).linear_multiply(0.25)),
                 ]),
         );
         ui.separator();
 
+        if let Some(CompassMarkerDrag {
+            index,
+            angle,
+            distance: Some(distance),
+            ..
+        }) = dragged_marker
+        {
+            self.marker_positions[index] = (angle, distance);
+        }
+
         Grid::new("polar_compass_properties")
             .num_columns(2)
             .spacing([20.0, 10.0])
@@ -180,6 +267,18 @@ impl PageImpl for PolarCompassPage {
                 ui.drag_angle(&mut self.value);
                 ui.end_row();
 
+                ui.label("Heading bug");
+                ui.drag_angle(&mut self.heading_bug);
+                ui.end_row();
+
+                ui.label("Course bug");
+                ui.drag_angle(&mut self.course_bug);
+                ui.end_row();
+
+                ui.label("Waypoint bug");
+                ui.drag_angle(&mut self.waypoint_bug);
+                ui.end_row();
+
                 ui.label("Interactive");
                 ui.checkbox(&mut self.interactive, "");
                 ui.end_row();
@@ -194,6 +293,12 @@ impl PageImpl for PolarCompassPage {
                 });
                 ui.end_row();
 
+                ui.label("Mode");
+                ui.horizontal(|ui| {
+                    ui.selectable_value_from_iter(&mut self.mode, PolarCompassMode::iter());
+                });
+                ui.end_row();
+
                 ui.label("Overflow");
                 ui.horizontal(|ui| {
                     ui.selectable_value_from_iter(&mut self.overflow, PolarCompassOverflow::iter());
@@ -278,6 +383,90 @@ impl PageImpl for PolarCompassPage {
                 ui.checkbox(&mut self.show_marker_lines, "");
                 ui.end_row();
 
+                ui.label("Show marker tooltips");
+                ui.checkbox(&mut self.show_marker_tooltips, "");
+                ui.end_row();
+
+                ui.label("Label layout");
+                ui.horizontal(|ui| {
+                    ui.selectable_value_from_iter(
+                        &mut self.label_layout,
+                        PolarCompassLabelLayout::iter(),
+                    );
+                });
+                ui.end_row();
+
+                ui.label("Marker clustering");
+                ui.optional_value_widget(&mut self.marker_clustering, |ui, value| {
+                    ui.add(DragValue::new(value).clamp_range(0.0..=f32::MAX))
+                });
+                ui.end_row();
+
+                ui.label("Marker trail length");
+                ui.optional_value_widget(&mut self.marker_trail_length, |ui, value| {
+                    ui.add(
+                        DragValue::new(value)
+                            .clamp_range(0.1..=f32::MAX)
+                            .speed(0.1)
+                            .suffix(" s"),
+                    )
+                });
+                ui.end_row();
+
+                ui.label("Marker velocity vectors");
+                ui.optional_value_widget(&mut self.marker_velocity_vectors, |ui, value| {
+                    ui.add(
+                        DragValue::new(value)
+                            .clamp_range(0.0..=f32::MAX)
+                            .speed(0.1)
+                            .suffix(" s"),
+                    )
+                });
+                ui.end_row();
+
+                ui.label("Radar sweep");
+                ui.optional_value_widget(&mut self.radar_sweep, |ui, value| {
+                    ui.add(
+                        DragValue::new(value)
+                            .clamp_range(0.1..=f32::MAX)
+                            .speed(0.1)
+                            .suffix(" s"),
+                    )
+                });
+                ui.end_row();
+
+                ui.label("Radar afterglow");
+                ui.add(
+                    DragValue::new(&mut self.radar_afterglow)
+                        .clamp_range(0.01..=1.0)
+                        .speed(0.01),
+                );
+                ui.end_row();
+
+                ui.label("Radar style");
+                display_style_ui(ui, &mut self.radar_style, &mut self.radar_style_preset);
+                ui.end_row();
+
+                ui.label("Style");
+                ui.vertical(|ui| {
+                    let mut system_style = self.style.is_none();
+                    if ui.checkbox(&mut system_style, "System style").changed() {
+                        self.style = (!system_style).then(|| self.style_preset.style());
+                    }
+
+                    if let Some(style) = &mut self.style {
+                        compass_style_ui(ui, style, &mut self.style_preset);
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Selected marker");
+                ui.label(match self.selected_marker {
+                    Some(marker_id) => marker_id.short_debug_format(),
+                    None => "None".to_owned(),
+                });
+                ui.end_row();
+
                 ui.label("Default marker color");
                 default_compass_marker_color_ui(ui, &mut self.default_marker_color);
                 ui.end_row();
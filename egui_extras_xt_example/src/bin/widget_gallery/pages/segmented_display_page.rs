@@ -1,22 +1,28 @@
-use eframe::egui::{DragValue, Grid, Ui};
+use eframe::egui::{DragValue, Grid, TextEdit, Ui};
 use egui_extras_xt::displays::segmented_display::DisplayMetricsPreset;
 use egui_extras_xt::displays::{
-    DisplayKind, DisplayMetrics, DisplayStyle, DisplayStylePreset, SegmentedDisplayWidget,
+    DisplayGlow, DisplayKind, DisplayMetrics, DisplayStyle, DisplayStylePreset,
+    SegmentedDisplayWidget,
 };
+use egui_extras_xt::ui::optional_value_widget::OptionalValueWidget;
 use egui_extras_xt::ui::widgets_from_iter::SelectableValueFromIter;
 use strum::IntoEnumIterator;
 
-use crate::pages::ui::{display_metrics_ui, display_style_ui};
+use crate::pages::ui::{display_glow_ui, display_metrics_ui, display_style_ui};
 use crate::pages::PageImpl;
 
 pub struct SegmentedDisplayPage {
     value: String,
+    editable: bool,
+    edit_charset: String,
     display_kind: DisplayKind,
     digit_height: f32,
     metrics: DisplayMetrics,
     metrics_preset: DisplayMetricsPreset,
     style: DisplayStyle,
     style_preset: DisplayStylePreset,
+    glow: Option<DisplayGlow>,
+    animated: bool,
     show_dots: bool,
     show_colons: bool,
     show_apostrophes: bool,
@@ -26,12 +32,16 @@ impl Default for SegmentedDisplayPage {
     fn default() -> SegmentedDisplayPage {
         SegmentedDisplayPage {
             value: "EGUI_EXTRAS_XT".to_owned(),
+            editable: false,
+            edit_charset: " ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789_".to_owned(),
             display_kind: DisplayKind::SixteenSegment,
             digit_height: 80.0,
             metrics: DisplayMetricsPreset::Default.metrics(),
             metrics_preset: DisplayMetricsPreset::Default,
             style: DisplayStylePreset::Default.style(),
             style_preset: DisplayStylePreset::Default,
+            glow: None,
+            animated: false,
             show_dots: true,
             show_colons: true,
             show_apostrophes: true,
@@ -41,16 +51,25 @@ impl Default for SegmentedDisplayPage {
 
 impl PageImpl for SegmentedDisplayPage {
     fn ui(&mut self, ui: &mut Ui) {
-        ui.add(
-            SegmentedDisplayWidget::new(self.display_kind)
-                .digit_height(self.digit_height)
-                .metrics(self.metrics)
-                .style(self.style)
-                .show_dots(self.show_dots)
-                .show_colons(self.show_colons)
-                .show_apostrophes(self.show_apostrophes)
-                .push_string(&self.value),
-        );
+        let segmented_display = SegmentedDisplayWidget::new(self.display_kind)
+            .digit_height(self.digit_height)
+            .metrics(self.metrics)
+            .style(self.style)
+            .glow(self.glow)
+            .animated(self.animated)
+            .show_dots(self.show_dots)
+            .show_colons(self.show_colons)
+            .show_apostrophes(self.show_apostrophes);
+
+        if self.editable && !self.edit_charset.is_empty() {
+            ui.add(
+                segmented_display
+                    .editable(&mut self.value)
+                    .edit_charset(&self.edit_charset),
+            );
+        } else {
+            ui.add(segmented_display.push_string(&self.value));
+        }
         ui.separator();
 
         Grid::new("segmented_display_properties")
@@ -62,6 +81,14 @@ impl PageImpl for SegmentedDisplayPage {
                 ui.text_edit_singleline(&mut self.value);
                 ui.end_row();
 
+                ui.label("Editable");
+                ui.checkbox(&mut self.editable, "");
+                ui.end_row();
+
+                ui.label("Edit charset");
+                ui.add_enabled(self.editable, TextEdit::singleline(&mut self.edit_charset));
+                ui.end_row();
+
                 ui.label("Display kind");
                 ui.horizontal(|ui| {
                     ui.selectable_value_from_iter(&mut self.display_kind, DisplayKind::iter());
@@ -80,6 +107,14 @@ impl PageImpl for SegmentedDisplayPage {
                 display_style_ui(ui, &mut self.style, &mut self.style_preset);
                 ui.end_row();
 
+                ui.label("Glow");
+                ui.optional_value_widget(&mut self.glow, display_glow_ui);
+                ui.end_row();
+
+                ui.label("Animated");
+                ui.checkbox(&mut self.animated, "");
+                ui.end_row();
+
                 ui.label("Show dots");
                 ui.checkbox(&mut self.show_dots, "");
                 ui.end_row();
@@ -2,12 +2,14 @@ use std::path::PathBuf;
 use std::str::FromStr;
 
 use eframe::egui::{DragValue, Grid, Response, TextEdit, Ui};
-use eframe::epaint::Color32;
+use eframe::epaint::{Color32, FontFamily};
 
 use egui_extras_xt::common::{Orientation, WidgetShape};
-use egui_extras_xt::compasses::{CompassMarkerShape, DefaultCompassMarkerColor};
+use egui_extras_xt::compasses::{
+    CompassMarkerShape, CompassStyle, CompassStylePreset, DefaultCompassMarkerColor,
+};
 use egui_extras_xt::displays::segmented_display::DisplayMetricsPreset;
-use egui_extras_xt::displays::{DisplayMetrics, DisplayStyle, DisplayStylePreset};
+use egui_extras_xt::displays::{DisplayGlow, DisplayMetrics, DisplayStyle, DisplayStylePreset};
 use egui_extras_xt::knobs::{ThumbstickDeadZone, ThumbstickSnap};
 use egui_extras_xt::ui::standard_buttons::StandardButtons;
 use egui_extras_xt::ui::widgets_from_iter::ComboBoxFromIter;
@@ -70,6 +72,23 @@ pub fn display_style_ui(
         });
 }
 
+pub fn display_glow_ui(ui: &mut Ui, glow: &mut DisplayGlow) -> Response {
+    ui.horizontal(|ui| {
+        ui.add(DragValue::new(&mut glow.radius).prefix("Radius: "))
+            | ui.add(
+                DragValue::new(&mut glow.intensity)
+                    .speed(0.01)
+                    .prefix("Intensity: "),
+            )
+            | ui.add(
+                DragValue::new(&mut glow.layers)
+                    .clamp_range(1..=16)
+                    .prefix("Layers: "),
+            )
+    })
+    .inner
+}
+
 pub fn display_metrics_ui(
     ui: &mut Ui,
     metrics: &mut DisplayMetrics,
@@ -351,6 +370,85 @@ pub fn compass_axis_labels_ui(ui: &mut Ui, axis_labels: &mut Vec<String>) {
     });
 }
 
+pub fn compass_style_ui(
+    ui: &mut Ui,
+    style: &mut CompassStyle,
+    style_preset: &mut CompassStylePreset,
+) {
+    Grid::new("compass_style_properties")
+        .num_columns(2)
+        .spacing([20.0, 10.0])
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label("Style preset");
+            ui.horizontal(|ui| {
+                ui.push_id("compass_style_preset_combo", |ui| {
+                    if ui
+                        .combobox_from_iter("", style_preset, CompassStylePreset::iter())
+                        .changed()
+                    {
+                        *style = style_preset.style();
+                    }
+
+                    if ui.reset_button().clicked() {
+                        *style = style_preset.style();
+                    }
+                });
+            });
+            ui.end_row();
+
+            ui.label("Background color");
+            ui.color_edit_button_srgba(&mut style.background_color);
+            ui.end_row();
+
+            for (label, stroke) in [
+                ("Outline stroke", &mut style.outline_stroke),
+                ("Ring stroke", &mut style.ring_stroke),
+                ("Axis stroke", &mut style.axis_stroke),
+                ("Tick stroke", &mut style.tick_stroke),
+                ("Cursor stroke", &mut style.cursor_stroke),
+            ] {
+                ui.label(label);
+                ui.horizontal(|ui| {
+                    ui.color_edit_button_srgba(&mut stroke.color);
+                    ui.add(DragValue::new(&mut stroke.width));
+                });
+                ui.end_row();
+            }
+
+            ui.label("Cursor color");
+            ui.color_edit_button_srgba(&mut style.cursor_color);
+            ui.end_row();
+
+            ui.label("Text color");
+            ui.color_edit_button_srgba(&mut style.text_color);
+            ui.end_row();
+
+            ui.label("Sky color");
+            ui.color_edit_button_srgba(&mut style.sky_color);
+            ui.end_row();
+
+            ui.label("Ground color");
+            ui.color_edit_button_srgba(&mut style.ground_color);
+            ui.end_row();
+
+            ui.label("Label font family");
+            ui.horizontal(|ui| {
+                ui.selectable_value(
+                    &mut style.label_font_family,
+                    FontFamily::Proportional,
+                    "Proportional",
+                );
+                ui.selectable_value(
+                    &mut style.label_font_family,
+                    FontFamily::Monospace,
+                    "Monospace",
+                );
+            });
+            ui.end_row();
+        });
+}
+
 pub fn thumbstick_snap_ui(ui: &mut Ui, value: &mut ThumbstickSnap) {
     ui.horizontal_centered(|ui| {
         ui.push_id("thumbstick_snap_combo", |ui| {